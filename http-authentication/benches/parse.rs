@@ -0,0 +1,138 @@
+//! Compares the owned and the borrowed parsing.
+//!
+//! Besides the timings, the number of allocations per parse is printed.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use http_authentication::{ChallengeRefs, Challenges, Credentials, CredentialsRef};
+
+//
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+//
+const BASIC_CREDENTIALS: &[u8] = b"Basic YWxhZGRpbjpvcGVuc2VzYW1l";
+const BEARER_CREDENTIALS: &[u8] = b"Bearer mF_9.B5f-4.1JqM";
+const CHALLENGES: &str =
+    r#"Basic realm="foo", charset="UTF-8", Bearer realm="bar", scope="openid profile email""#;
+
+fn print_allocations() {
+    let mut buf = [0; 64];
+
+    for (name, n) in [
+        (
+            "Credentials::from_bytes(Basic)",
+            allocations(|| {
+                black_box(Credentials::from_bytes(BASIC_CREDENTIALS).unwrap());
+            }),
+        ),
+        (
+            "CredentialsRef::from_bytes(Basic)",
+            allocations(|| {
+                black_box(CredentialsRef::from_bytes(BASIC_CREDENTIALS, &mut buf).unwrap());
+            }),
+        ),
+        (
+            "Credentials::from_bytes(Bearer)",
+            allocations(|| {
+                black_box(Credentials::from_bytes(BEARER_CREDENTIALS).unwrap());
+            }),
+        ),
+        (
+            "CredentialsRef::from_bytes(Bearer)",
+            allocations(|| {
+                black_box(CredentialsRef::from_bytes(BEARER_CREDENTIALS, &mut buf).unwrap());
+            }),
+        ),
+        (
+            "Challenges::from_str",
+            allocations(|| {
+                black_box(CHALLENGES.parse::<Challenges>().unwrap());
+            }),
+        ),
+        (
+            "ChallengeRefs::new",
+            allocations(|| {
+                for c in ChallengeRefs::new(CHALLENGES) {
+                    black_box(c.unwrap());
+                }
+            }),
+        ),
+    ] {
+        println!("{name}: {n} allocations");
+    }
+}
+
+fn bench_credentials(c: &mut Criterion) {
+    print_allocations();
+
+    let mut group = c.benchmark_group("credentials");
+    group.bench_function("owned_basic", |b| {
+        b.iter(|| Credentials::from_bytes(black_box(BASIC_CREDENTIALS)).unwrap())
+    });
+    group.bench_function("borrowed_basic", |b| {
+        let mut buf = [0; 64];
+        b.iter(|| {
+            CredentialsRef::from_bytes(black_box(BASIC_CREDENTIALS), &mut buf)
+                .unwrap()
+                .as_basic()
+                .map(|c| c.user_id.len())
+        })
+    });
+    group.bench_function("owned_bearer", |b| {
+        b.iter(|| Credentials::from_bytes(black_box(BEARER_CREDENTIALS)).unwrap())
+    });
+    group.bench_function("borrowed_bearer", |b| {
+        let mut buf = [0; 64];
+        b.iter(|| {
+            CredentialsRef::from_bytes(black_box(BEARER_CREDENTIALS), &mut buf)
+                .unwrap()
+                .as_bearer()
+                .map(|c| c.token.len())
+        })
+    });
+    group.finish();
+}
+
+fn bench_challenges(c: &mut Criterion) {
+    let mut group = c.benchmark_group("challenges");
+    group.bench_function("owned", |b| {
+        b.iter(|| black_box(CHALLENGES).parse::<Challenges>().unwrap())
+    });
+    group.bench_function("borrowed", |b| {
+        b.iter(|| {
+            ChallengeRefs::new(black_box(CHALLENGES))
+                .filter(Result::is_ok)
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_credentials, bench_challenges);
+criterion_main!(benches);
@@ -0,0 +1,422 @@
+//! API keys sent outside of `Authorization`, in a custom header, a query parameter or a cookie.
+//!
+//! Keys are stored as SHA-256 hashes, the lookup compares against every entry in constant time.
+
+use alloc::{borrow::Cow, boxed::Box, vec::Vec};
+
+use http::{header::COOKIE, HeaderMap};
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    credentials::Credentials,
+    verifier::{
+        verify_credentials, ApiKeyLocation, CredentialsSource, Rejection, Verifier, VerifyContext,
+    },
+};
+
+//
+pub const DEFAULT_HEADER_NAME: &str = "X-API-Key";
+pub const DEFAULT_QUERY_PARAM: &str = "api_key";
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiKeySource {
+    Header(Box<str>),
+    /// Read from the query of the request target, the value is percent-decoded.
+    Query(Box<str>),
+    Cookie(Box<str>),
+}
+
+impl Default for ApiKeySource {
+    fn default() -> Self {
+        Self::Header(DEFAULT_HEADER_NAME.into())
+    }
+}
+
+impl ApiKeySource {
+    pub fn header(name: impl AsRef<str>) -> Self {
+        Self::Header(name.as_ref().into())
+    }
+
+    pub fn query(param: impl AsRef<str>) -> Self {
+        Self::Query(param.as_ref().into())
+    }
+
+    pub fn location(&self) -> ApiKeyLocation {
+        match self {
+            Self::Header(_) => ApiKeyLocation::Header,
+            Self::Query(_) => ApiKeyLocation::Query,
+            Self::Cookie(_) => ApiKeyLocation::Cookie,
+        }
+    }
+
+    pub fn cookie(name: impl AsRef<str>) -> Self {
+        Self::Cookie(name.as_ref().into())
+    }
+
+    /// `Ok(None)` when the key is absent.
+    ///
+    /// `query` is the raw query of the request target, without the `?`.
+    pub fn extract<'a>(
+        &self,
+        header_map: &'a HeaderMap,
+        query: Option<&'a str>,
+    ) -> Result<Option<Cow<'a, str>>, ApiKeyExtractError> {
+        match self {
+            Self::Header(name) => match header_map.get(name.as_ref()) {
+                Some(value) => value
+                    .to_str()
+                    .map(|x| Some(Cow::Borrowed(x.trim())))
+                    .map_err(|_| ApiKeyExtractError::HeaderValueInvalid),
+                None => Ok(None),
+            },
+            Self::Query(param) => {
+                let Some(value) = query.and_then(|query| {
+                    query
+                        .split('&')
+                        .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+                        .find(|(k, _)| *k == param.as_ref())
+                        .map(|(_, v)| v)
+                }) else {
+                    return Ok(None);
+                };
+                crate::percent_decode::percent_decode(value)
+                    .map(Some)
+                    .map_err(ApiKeyExtractError::QueryValueDecodeFailed)
+            }
+            Self::Cookie(name) => {
+                for value in header_map.get_all(COOKIE) {
+                    let value = value
+                        .to_str()
+                        .map_err(|_| ApiKeyExtractError::HeaderValueInvalid)?;
+                    let found = value
+                        .split(';')
+                        .filter_map(|pair| pair.trim().split_once('='))
+                        .find(|(k, _)| *k == name.as_ref())
+                        .map(|(_, v)| v.trim_matches('"'));
+                    if let Some(v) = found {
+                        return Ok(Some(Cow::Borrowed(v)));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Same as [`ApiKeySource::extract`], with the query of `request_uri`.
+    #[cfg(feature = "http-request-uri")]
+    pub fn extract_from_request<'a>(
+        &self,
+        header_map: &'a HeaderMap,
+        request_uri: &http_request_uri::RequestUri<'a>,
+    ) -> Result<Option<Cow<'a, str>>, ApiKeyExtractError> {
+        let query = match request_uri {
+            http_request_uri::RequestUri::AbsoluteUri { query, .. } => *query,
+            http_request_uri::RequestUri::Origin { query, .. } => *query,
+            _ => None,
+        };
+        self.extract(header_map, query)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyExtractError {
+    HeaderValueInvalid,
+    QueryValueDecodeFailed(&'static str),
+}
+
+impl core::fmt::Display for ApiKeyExtractError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ApiKeyExtractError {}
+
+//
+pub type ApiKeyHash = [u8; 32];
+
+pub fn hash_api_key(key: impl AsRef<[u8]>) -> ApiKeyHash {
+    Sha256::digest(key.as_ref()).into()
+}
+
+/// Hashed API keys and the identity of each.
+#[derive(Debug, Clone)]
+pub struct ApiKeyStore<I> {
+    entries: Vec<(ApiKeyHash, I)>,
+}
+
+impl<I> Default for ApiKeyStore<I> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<I> ApiKeyStore<I> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, key: impl AsRef<[u8]>, identity: I) {
+        self.insert_hashed(hash_api_key(key), identity)
+    }
+
+    /// For keys stored as hashes, see [`hash_api_key`].
+    pub fn insert_hashed(&mut self, hash: ApiKeyHash, identity: I) {
+        self.entries.push((hash, identity))
+    }
+
+    pub fn remove_hashed(&mut self, hash: &ApiKeyHash) {
+        self.entries.retain(|(x, _)| x != hash)
+    }
+
+    /// Compares against every entry, the time does not depend on which one matches.
+    pub fn lookup(&self, key: impl AsRef<[u8]>) -> Option<&I> {
+        let hash = hash_api_key(key);
+        let mut found = None;
+        for (x, identity) in &self.entries {
+            if constant_time_eq(x, &hash) && found.is_none() {
+                found = Some(identity);
+            }
+        }
+        found
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn constant_time_eq(a: &ApiKeyHash, b: &ApiKeyHash) -> bool {
+    let diff = a.iter().zip(b).fold(0_u8, |acc, (x, y)| acc | (x ^ y));
+    core::hint::black_box(diff) == 0
+}
+
+//
+/// Extracts the key of `source` and verifies it as Bearer credentials,
+/// the same as [`verify_credentials`] does for `Authorization`.
+///
+/// Wrappers such as [`crate::LimitedVerifier`] or `Audited` see the key as a Bearer token.
+/// The source of `ctx` is replaced with [`CredentialsSource::ApiKey`].
+pub fn verify_api_key<V: Verifier + ?Sized>(
+    verifier: &V,
+    source: &ApiKeySource,
+    header_map: &HeaderMap,
+    query: Option<&str>,
+    ctx: &VerifyContext<'_>,
+) -> Result<V::Identity, Rejection> {
+    let ctx = &VerifyContext {
+        source: CredentialsSource::ApiKey(source.location()),
+        ..*ctx
+    };
+    let credentials = match source.extract(header_map, query) {
+        Ok(Some(key)) if !key.is_empty() => Some(Ok(Credentials::bearer(key))),
+        Ok(_) => None,
+        Err(_) => return Err(verifier.reject(Rejection::Other("api key malformed"), ctx)),
+    };
+    verify_credentials(verifier, credentials, ctx)
+}
+
+//
+/// Looks up the token of Bearer credentials, see [`verify_api_key`].
+#[derive(Debug, Clone)]
+pub struct ApiKeyVerifier<I> {
+    source: ApiKeySource,
+    store: ApiKeyStore<I>,
+}
+
+impl<I: Clone> ApiKeyVerifier<I> {
+    pub fn new(source: ApiKeySource, store: ApiKeyStore<I>) -> Self {
+        Self { source, store }
+    }
+
+    pub fn source(&self) -> &ApiKeySource {
+        &self.source
+    }
+
+    pub fn store_mut(&mut self) -> &mut ApiKeyStore<I> {
+        &mut self.store
+    }
+
+    /// [`verify_api_key`] with the source of this verifier.
+    pub fn verify_request(
+        &self,
+        header_map: &HeaderMap,
+        query: Option<&str>,
+        ctx: &VerifyContext<'_>,
+    ) -> Result<I, Rejection> {
+        verify_api_key(self, &self.source, header_map, query, ctx)
+    }
+}
+
+impl<I: Clone> Verifier for ApiKeyVerifier<I> {
+    type Identity = I;
+
+    fn verify(
+        &self,
+        credentials: &Credentials,
+        _ctx: &VerifyContext<'_>,
+    ) -> Result<Self::Identity, Rejection> {
+        match credentials {
+            Credentials::Bearer(c) => self
+                .store
+                .lookup(c.token.as_bytes())
+                .cloned()
+                .ok_or(Rejection::Invalid),
+            #[allow(unreachable_patterns)]
+            _ => Err(Rejection::SchemeUnsupported),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http::HeaderValue;
+
+    fn verifier(source: ApiKeySource) -> ApiKeyVerifier<&'static str> {
+        let mut store = ApiKeyStore::new();
+        store.insert("secret-1", "foo");
+        store.insert_hashed(hash_api_key("secret 2"), "bar");
+        ApiKeyVerifier::new(source, store)
+    }
+
+    #[test]
+    fn test_header_and_cookie() {
+        let ctx = VerifyContext::new(CredentialsSource::Authorization);
+        let verify = |verifier: &ApiKeyVerifier<_>, map: &HeaderMap| {
+            verifier.verify_request(map, None, &ctx)
+        };
+
+        let verifier_header = verifier(ApiKeySource::default());
+        let verifier_cookie = verifier(ApiKeySource::cookie("api_key"));
+
+        let mut map = HeaderMap::new();
+        match verify(&verifier_header, &map) {
+            Err(Rejection::Missing) => {}
+            x => panic!("{x:?}"),
+        }
+
+        map.insert("x-api-key", HeaderValue::from_static("secret-1"));
+        map.insert(
+            COOKIE,
+            HeaderValue::from_static("a=1; api_key=\"secret 2\""),
+        );
+        assert_eq!(verify(&verifier_header, &map).unwrap(), "foo");
+        assert_eq!(verify(&verifier_cookie, &map).unwrap(), "bar");
+
+        map.insert("x-api-key", HeaderValue::from_static("secret-3"));
+        match verify(&verifier_header, &map) {
+            Err(Rejection::Invalid) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_query() {
+        let ctx = VerifyContext::new(CredentialsSource::Authorization);
+        let verifier = verifier(ApiKeySource::query(DEFAULT_QUERY_PARAM));
+        let map = HeaderMap::new();
+
+        assert_eq!(
+            verifier
+                .verify_request(&map, Some("a=1&api_key=secret%202"), &ctx)
+                .unwrap(),
+            "bar"
+        );
+
+        match verifier.verify_request(&map, Some("api_key=secret%2"), &ctx) {
+            Err(Rejection::Other(_)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match verifier.verify_request(&map, None, &ctx) {
+            Err(Rejection::Missing) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_verifier() {
+        let ctx = VerifyContext::new(CredentialsSource::Authorization);
+        let verifier = verifier(ApiKeySource::default());
+
+        match verify_credentials(&verifier, Some(Ok(Credentials::bearer("secret-1"))), &ctx) {
+            Ok("foo") => {}
+            x => panic!("{x:?}"),
+        }
+
+        #[cfg(feature = "scheme-basic")]
+        match verifier.verify(&Credentials::basic("secret-1", ""), &ctx) {
+            Err(Rejection::SchemeUnsupported) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_source() {
+        struct SourceOf;
+
+        impl Verifier for SourceOf {
+            type Identity = CredentialsSource;
+
+            fn verify(
+                &self,
+                _credentials: &Credentials,
+                ctx: &VerifyContext<'_>,
+            ) -> Result<Self::Identity, Rejection> {
+                Ok(ctx.source)
+            }
+        }
+
+        let ctx = VerifyContext::new(CredentialsSource::Authorization);
+        let mut map = HeaderMap::new();
+        map.insert("x-api-key", HeaderValue::from_static("secret-1"));
+        map.insert(COOKIE, HeaderValue::from_static("api_key=secret-1"));
+
+        for (source, location) in [
+            (ApiKeySource::default(), ApiKeyLocation::Header),
+            (ApiKeySource::cookie("api_key"), ApiKeyLocation::Cookie),
+            (ApiKeySource::query("api_key"), ApiKeyLocation::Query),
+        ] {
+            assert_eq!(
+                verify_api_key(&SourceOf, &source, &map, Some("api_key=secret-1"), &ctx).unwrap(),
+                CredentialsSource::ApiKey(location)
+            );
+        }
+    }
+
+    #[cfg(feature = "http-request-uri")]
+    #[test]
+    fn test_extract_from_request() {
+        use http_request_uri::RequestUri;
+
+        let source = ApiKeySource::query(DEFAULT_QUERY_PARAM);
+        let map = HeaderMap::new();
+
+        let uri = RequestUri::Origin {
+            path: "/",
+            query: Some("a=1&api_key=secret%202"),
+            fragment: None,
+        };
+        assert_eq!(
+            source.extract_from_request(&map, &uri).unwrap().as_deref(),
+            Some("secret 2")
+        );
+
+        assert_eq!(
+            source
+                .extract_from_request(&map, &RequestUri::Asterisk)
+                .unwrap(),
+            None
+        );
+    }
+}
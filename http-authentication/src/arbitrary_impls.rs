@@ -0,0 +1,244 @@
+//! [`arbitrary::Arbitrary`] for fuzzing, the generated values survive a render and parse round trip.
+
+use alloc::{boxed::Box, string::String};
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest",
+    feature = "scheme-vapid",
+    feature = "scheme-concealed"
+))]
+use arbitrary::Arbitrary;
+use arbitrary::{Result, Unstructured};
+
+//
+const QUOTED_STRING_BYTES: &[u8] =
+    b" !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+#[cfg(any(feature = "scheme-bearer", feature = "scheme-vapid"))]
+const TOKEN68_BYTES: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~+/";
+
+#[allow(dead_code)]
+fn string_of(u: &mut Unstructured<'_>, alphabet: &[u8], min_len: usize) -> Result<Box<str>> {
+    let len = min_len + u.arbitrary_len::<u8>()?;
+    let mut s = String::with_capacity(len);
+    for _ in 0..len {
+        s.push(*u.choose(alphabet)? as char);
+    }
+    Ok(s.into())
+}
+
+#[allow(dead_code)]
+fn quoted_string(u: &mut Unstructured<'_>) -> Result<Box<str>> {
+    string_of(u, QUOTED_STRING_BYTES, 0)
+}
+
+#[allow(dead_code)]
+fn quoted_string_opt(u: &mut Unstructured<'_>) -> Result<Option<Box<str>>> {
+    if u.arbitrary()? {
+        quoted_string(u).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+//
+#[cfg(feature = "scheme-basic")]
+impl<'a> Arbitrary<'a> for crate::schemes::basic::Credentials {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let user_id = String::arbitrary(u)?.replace(':', "");
+        let password = String::arbitrary(u)?.replace(':', "");
+        Ok(Self::new(user_id, password))
+    }
+}
+
+#[cfg(feature = "scheme-basic")]
+impl<'a> Arbitrary<'a> for crate::schemes::basic::Challenge {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            realm: quoted_string(u)?,
+            charset: quoted_string_opt(u)?,
+        })
+    }
+}
+
+#[cfg(feature = "scheme-bearer")]
+impl<'a> Arbitrary<'a> for crate::schemes::bearer::Credentials {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::new(string_of(u, TOKEN68_BYTES, 1)?))
+    }
+}
+
+#[cfg(feature = "scheme-bearer")]
+impl<'a> Arbitrary<'a> for crate::schemes::bearer::Challenge {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            realm: quoted_string(u)?,
+            scope: quoted_string_opt(u)?,
+            error: quoted_string_opt(u)?,
+            error_description: quoted_string_opt(u)?,
+            error_uri: quoted_string_opt(u)?,
+        })
+    }
+}
+
+#[cfg(feature = "scheme-digest")]
+impl<'a> Arbitrary<'a> for crate::schemes::digest::Algorithm {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.choose(&[
+            Self::Md5,
+            Self::Md5Sess,
+            Self::Sha256,
+            Self::Sha256Sess,
+            Self::Sha512_256,
+            Self::Sha512_256Sess,
+        ])
+        .copied()
+    }
+}
+
+#[cfg(feature = "scheme-digest")]
+impl<'a> Arbitrary<'a> for crate::schemes::digest::Challenge {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self {
+            realm: quoted_string(u)?,
+            domain: quoted_string_opt(u)?,
+            nonce: quoted_string(u)?,
+            opaque: quoted_string_opt(u)?,
+            stale: u.arbitrary()?,
+            algorithm: u.arbitrary()?,
+            qop: quoted_string_opt(u)?,
+            charset: if u.arbitrary()? {
+                Some(string_of(u, b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789-", 1)?)
+            } else {
+                None
+            },
+            userhash: u.arbitrary()?,
+        })
+    }
+}
+
+#[cfg(feature = "scheme-vapid")]
+impl<'a> Arbitrary<'a> for crate::schemes::vapid::Credentials {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::new(
+            string_of(u, TOKEN68_BYTES, 1)?,
+            string_of(u, TOKEN68_BYTES, 1)?,
+        ))
+    }
+}
+
+#[cfg(feature = "scheme-concealed")]
+impl<'a> Arbitrary<'a> for crate::schemes::concealed::Credentials {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        // The byte sequences are not empty.
+        let mut bytes = || -> Result<alloc::vec::Vec<u8>> {
+            let mut v = alloc::vec![u.arbitrary::<u8>()?];
+            v.extend_from_slice(u.arbitrary::<&[u8]>()?);
+            Ok(v)
+        };
+        let (key_id, public_key, proof) = (bytes()?, bytes()?, bytes()?);
+        let verification = bytes()?;
+        Ok(Self::from_raw(
+            &key_id,
+            &public_key,
+            &proof,
+            u.arbitrary()?,
+            &verification,
+        ))
+    }
+}
+
+//
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-vapid",
+    feature = "scheme-concealed"
+))]
+impl<'a> Arbitrary<'a> for crate::Credentials {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        type Ctor<'a> = fn(&mut Unstructured<'a>) -> Result<crate::Credentials>;
+
+        let ctors: &[Ctor<'a>] = &[
+            #[cfg(feature = "scheme-basic")]
+            |u| u.arbitrary().map(crate::Credentials::Basic),
+            #[cfg(feature = "scheme-bearer")]
+            |u| u.arbitrary().map(crate::Credentials::Bearer),
+            #[cfg(feature = "scheme-vapid")]
+            |u| u.arbitrary().map(crate::Credentials::Vapid),
+            #[cfg(feature = "scheme-concealed")]
+            |u| u.arbitrary().map(crate::Credentials::Concealed),
+        ];
+        u.choose(ctors)?(u)
+    }
+}
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest"
+))]
+impl<'a> Arbitrary<'a> for crate::Challenge {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        type Ctor<'a> = fn(&mut Unstructured<'a>) -> Result<crate::Challenge>;
+
+        let ctors: &[Ctor<'a>] = &[
+            #[cfg(feature = "scheme-basic")]
+            |u| u.arbitrary().map(crate::Challenge::Basic),
+            #[cfg(feature = "scheme-bearer")]
+            |u| u.arbitrary().map(crate::Challenge::Bearer),
+            #[cfg(feature = "scheme-digest")]
+            |u| u.arbitrary().map(crate::Challenge::Digest),
+        ];
+        u.choose(ctors)?(u)
+    }
+}
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest"
+))]
+impl<'a> Arbitrary<'a> for crate::Challenges {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut inner = alloc::vec![u.arbitrary()?];
+        for _ in 0..u.arbitrary_len::<crate::Challenge>()?.min(3) {
+            inner.push(u.arbitrary()?);
+        }
+        Ok(Self::new(inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "scheme-basic",
+        feature = "scheme-bearer",
+        feature = "scheme-digest"
+    ))]
+    #[test]
+    fn test_round_trip() {
+        use alloc::{string::ToString as _, vec::Vec};
+
+        use crate::{Challenges, Credentials};
+
+        for seed in 0_u32..256 {
+            let data = (0..512_u32)
+                .map(|i| (seed.wrapping_mul(2654435761) ^ i.wrapping_mul(40503)) as u8)
+                .collect::<Vec<_>>();
+
+            let mut u = Unstructured::new(&data);
+            let c = Credentials::arbitrary(&mut u).unwrap();
+            assert_eq!(c.to_string().parse::<Credentials>().unwrap(), c);
+
+            let mut u = Unstructured::new(&data);
+            let c = Challenges::arbitrary(&mut u).unwrap();
+            assert_eq!(c.to_string().parse::<Challenges>().unwrap(), c);
+        }
+    }
+}
@@ -0,0 +1,606 @@
+//! In-memory limiter of failed authentication attempts.
+//!
+//! Failures are counted per user-id and client address in a sliding window,
+//! reaching the limit locks the key out, each further lockout doubles the duration.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    credentials::Credentials,
+    verifier::{Rejection, Verifier, VerifyContext},
+};
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttemptLimiterConfig {
+    pub window: Duration,
+    pub max_failures: usize,
+    pub lockout: Duration,
+    pub max_lockout: Duration,
+    /// The number of tracked keys, the oldest key that is not locked out is dropped to make room
+    /// for a new one. While every tracked key is locked out, new keys are refused.
+    pub max_entries: usize,
+}
+
+impl Default for AttemptLimiterConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(60 * 5),
+            max_failures: 5,
+            lockout: Duration::from_secs(30),
+            max_lockout: Duration::from_secs(60 * 60),
+            max_entries: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AttemptKey {
+    pub user_id: Box<str>,
+    pub client_addr: Option<IpAddr>,
+}
+
+impl AttemptKey {
+    pub fn new(user_id: impl AsRef<str>, client_addr: Option<IpAddr>) -> Self {
+        Self {
+            user_id: user_id.as_ref().into(),
+            client_addr,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    failures: VecDeque<Instant>,
+    lockouts: u32,
+    locked_until: Option<Instant>,
+    last_failure: Option<Instant>,
+}
+
+impl Entry {
+    // Returns the previous `locked_until` when the failure starts a lockout.
+    fn fail(&mut self, now: Instant, config: &AttemptLimiterConfig) -> Option<Option<Instant>> {
+        self.last_failure = Some(now);
+        while self
+            .failures
+            .front()
+            .map(|x| now.saturating_duration_since(*x) >= config.window)
+            .unwrap_or(false)
+        {
+            self.failures.pop_front();
+        }
+        self.failures.push_back(now);
+
+        if self.failures.len() < config.max_failures {
+            return None;
+        }
+        let lockout = config
+            .lockout
+            .checked_mul(2_u32.saturating_pow(self.lockouts))
+            .unwrap_or(config.max_lockout)
+            .min(config.max_lockout);
+        let previous = self.locked_until;
+        self.locked_until = Some(now.checked_add(lockout).unwrap_or(now));
+        self.lockouts = self.lockouts.saturating_add(1);
+        self.failures.clear();
+        Some(previous)
+    }
+
+    fn undo(&mut self, attempt: &Attempt) {
+        match attempt.lockout {
+            Some(previous) => {
+                self.locked_until = previous;
+                self.lockouts = self.lockouts.saturating_sub(1);
+            }
+            None => {
+                if let Some(i) = self.failures.iter().rposition(|x| *x == attempt.at) {
+                    self.failures.remove(i);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: HashMap<AttemptKey, Entry>,
+    // Keys in insertion order, for evicting the oldest one.
+    order: VecDeque<AttemptKey>,
+    last_prune: Option<Instant>,
+}
+
+impl State {
+    // Returns `Err(retry_after)` when the key is new and every tracked key is locked out,
+    // evicting a locked out key would lift its lockout.
+    fn entry(
+        &mut self,
+        key: &AttemptKey,
+        now: Instant,
+        config: &AttemptLimiterConfig,
+    ) -> Result<&mut Entry, Duration> {
+        if !self.entries.contains_key(key) {
+            if self
+                .last_prune
+                .map(|x| now.saturating_duration_since(x) >= config.window)
+                .unwrap_or(true)
+            {
+                self.prune(now, config);
+            }
+            while self.entries.len() >= config.max_entries.max(1) {
+                let entries = &self.entries;
+                let evictable = self.order.iter().position(|x| {
+                    entries
+                        .get(x)
+                        .and_then(|entry| entry.locked_until)
+                        .map(|locked_until| locked_until <= now)
+                        .unwrap_or(true)
+                });
+                match evictable.and_then(|i| self.order.remove(i)) {
+                    Some(oldest) => {
+                        self.entries.remove(&oldest);
+                    }
+                    None => {
+                        let retry_after = self
+                            .entries
+                            .values()
+                            .filter_map(|entry| entry.locked_until)
+                            .min()
+                            .map(|locked_until| locked_until.saturating_duration_since(now))
+                            .unwrap_or_default();
+                        return Err(retry_after);
+                    }
+                }
+            }
+            self.order.push_back(key.clone());
+        }
+        Ok(self.entries.entry(key.clone()).or_default())
+    }
+
+    fn prune(&mut self, now: Instant, config: &AttemptLimiterConfig) {
+        let keep_for = config.window.max(config.max_lockout);
+        self.entries.retain(|_, entry| {
+            entry.locked_until.map(|x| x > now).unwrap_or(false)
+                || entry
+                    .last_failure
+                    .map(|x| now.saturating_duration_since(x) < keep_for)
+                    .unwrap_or(false)
+        });
+        let entries = &self.entries;
+        self.order.retain(|x| entries.contains_key(x));
+        self.last_prune = Some(now);
+    }
+}
+
+//
+/// An attempt reserved by [`AttemptLimiter::reserve`], it is counted as a failure until
+/// [`AttemptLimiter::succeed`] or [`AttemptLimiter::release`] is called.
+#[derive(Debug)]
+#[must_use]
+pub struct Attempt {
+    at: Instant,
+    lockout: Option<Option<Instant>>,
+}
+
+//
+/// Tracks at most [`AttemptLimiterConfig::max_entries`] keys, stale keys are pruned once per window.
+#[derive(Debug, Default)]
+pub struct AttemptLimiter {
+    config: AttemptLimiterConfig,
+    state: Mutex<State>,
+}
+
+impl AttemptLimiter {
+    pub fn new(config: AttemptLimiterConfig) -> Self {
+        Self {
+            config,
+            state: Default::default(),
+        }
+    }
+
+    pub fn config(&self) -> &AttemptLimiterConfig {
+        &self.config
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Returns `Err(retry_after)` while the key is locked out.
+    pub fn check(&self, key: &AttemptKey) -> Result<(), Duration> {
+        self.check_at(key, Instant::now())
+    }
+
+    pub fn check_at(&self, key: &AttemptKey, now: Instant) -> Result<(), Duration> {
+        match self.state().entries.get(key).and_then(|x| x.locked_until) {
+            Some(locked_until) if locked_until > now => Err(locked_until - now),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the key and counts the attempt as a failure in one step,
+    /// so concurrent attempts cannot all pass the check before any of them fails.
+    pub fn reserve(&self, key: &AttemptKey) -> Result<Attempt, Duration> {
+        self.reserve_at(key, Instant::now())
+    }
+
+    pub fn reserve_at(&self, key: &AttemptKey, now: Instant) -> Result<Attempt, Duration> {
+        let mut state = self.state();
+        if let Some(locked_until) = state.entries.get(key).and_then(|x| x.locked_until) {
+            if locked_until > now {
+                return Err(locked_until - now);
+            }
+        }
+        let lockout = state.entry(key, now, &self.config)?.fail(now, &self.config);
+        Ok(Attempt { at: now, lockout })
+    }
+
+    /// The reserved attempt succeeded, same as [`AttemptLimiter::record_success`]
+    /// after undoing the reservation.
+    pub fn succeed(&self, key: &AttemptKey, attempt: Attempt) {
+        let mut state = self.state();
+        if let Some(entry) = state.entries.get_mut(key) {
+            entry.undo(&attempt);
+            entry.failures.clear();
+        }
+    }
+
+    /// The reserved attempt neither failed nor succeeded, e.g. the credentials were not checked.
+    pub fn release(&self, key: &AttemptKey, attempt: Attempt) {
+        let mut state = self.state();
+        if let Some(entry) = state.entries.get_mut(key) {
+            entry.undo(&attempt);
+        }
+    }
+
+    pub fn record_failure(&self, key: &AttemptKey) {
+        self.record_failure_at(key, Instant::now())
+    }
+
+    /// The failure is dropped when the key is new and every tracked key is locked out.
+    pub fn record_failure_at(&self, key: &AttemptKey, now: Instant) {
+        let mut state = self.state();
+        if let Ok(entry) = state.entry(key, now, &self.config) {
+            entry.fail(now, &self.config);
+        }
+    }
+
+    /// Clears the failures of the key, the lockout count is kept,
+    /// so the next lockout still doubles until the key is pruned.
+    pub fn record_success(&self, key: &AttemptKey) {
+        if let Some(entry) = self.state().entries.get_mut(key) {
+            entry.failures.clear();
+        }
+    }
+
+    /// Removes keys that are not locked out and have had no failure for `max_lockout`.
+    pub fn prune(&self) {
+        self.prune_at(Instant::now())
+    }
+
+    pub fn prune_at(&self, now: Instant) {
+        self.state().prune(now, &self.config);
+    }
+
+    pub fn len(&self) -> usize {
+        self.state().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+//
+/// Wraps a [`Verifier`], rejects locked out Basic credentials before the password is checked.
+///
+/// Only [`Rejection::Invalid`] counts as a failure, other credentials are passed through.
+#[derive(Debug)]
+pub struct LimitedVerifier<V> {
+    inner: V,
+    limiter: AttemptLimiter,
+}
+
+impl<V> LimitedVerifier<V> {
+    pub fn new(inner: V, limiter: AttemptLimiter) -> Self {
+        Self { inner, limiter }
+    }
+
+    pub fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    pub fn limiter(&self) -> &AttemptLimiter {
+        &self.limiter
+    }
+}
+
+#[allow(unused_variables)]
+fn attempt_key(credentials: &Credentials, ctx: &VerifyContext<'_>) -> Option<AttemptKey> {
+    match credentials {
+        #[cfg(feature = "scheme-basic")]
+        Credentials::Basic(c) => Some(AttemptKey::new(&c.user_id, ctx.client_addr)),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+impl<V: Verifier> Verifier for LimitedVerifier<V> {
+    type Identity = V::Identity;
+
+    fn verify(
+        &self,
+        credentials: &Credentials,
+        ctx: &VerifyContext<'_>,
+    ) -> Result<Self::Identity, Rejection> {
+        let Some(key) = attempt_key(credentials, ctx) else {
+            return self.inner.verify(credentials, ctx);
+        };
+
+        let attempt = self
+            .limiter
+            .reserve(&key)
+            .map_err(|retry_after| Rejection::TooManyAttempts { retry_after })?;
+
+        match self.inner.verify(credentials, ctx) {
+            Ok(identity) => {
+                self.limiter.succeed(&key, attempt);
+                Ok(identity)
+            }
+            // Already counted by the reservation.
+            Err(Rejection::Invalid) => Err(Rejection::Invalid),
+            Err(rejection) => {
+                self.limiter.release(&key, attempt);
+                Err(rejection)
+            }
+        }
+    }
+
+    fn reject(&self, rejection: Rejection, ctx: &VerifyContext<'_>) -> Rejection {
+        self.inner.reject(rejection, ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockout() {
+        let limiter = AttemptLimiter::new(AttemptLimiterConfig {
+            window: Duration::from_secs(60),
+            max_failures: 3,
+            lockout: Duration::from_secs(10),
+            max_lockout: Duration::from_secs(25),
+            ..Default::default()
+        });
+        let key = AttemptKey::new("aladdin", Some(IpAddr::from([127, 0, 0, 1])));
+        let other = AttemptKey::new("aladdin", None);
+        let now = Instant::now();
+
+        // sliding window
+        limiter.record_failure_at(&key, now);
+        limiter.record_failure_at(&key, now + Duration::from_secs(30));
+        limiter.record_failure_at(&key, now + Duration::from_secs(61));
+        assert!(limiter
+            .check_at(&key, now + Duration::from_secs(61))
+            .is_ok());
+
+        // 1st lockout
+        let now = now + Duration::from_secs(62);
+        limiter.record_failure_at(&key, now);
+        assert_eq!(limiter.check_at(&key, now), Err(Duration::from_secs(10)));
+        assert!(limiter.check_at(&other, now).is_ok());
+        let now = now + Duration::from_secs(10);
+        assert!(limiter.check_at(&key, now).is_ok());
+
+        // 2nd lockout doubles, 3rd is capped
+        for _ in 0..3 {
+            limiter.record_failure_at(&key, now);
+        }
+        assert_eq!(limiter.check_at(&key, now), Err(Duration::from_secs(20)));
+        let now = now + Duration::from_secs(20);
+        for _ in 0..3 {
+            limiter.record_failure_at(&key, now);
+        }
+        assert_eq!(limiter.check_at(&key, now), Err(Duration::from_secs(25)));
+
+        //
+        limiter.prune_at(now);
+        assert_eq!(limiter.len(), 1);
+        limiter.prune_at(now + Duration::from_secs(60));
+        assert!(limiter.is_empty());
+
+        limiter.record_failure_at(&key, now);
+        limiter.record_success(&key);
+        assert_eq!(limiter.len(), 1);
+    }
+
+    #[test]
+    fn test_success_keeps_backoff() {
+        let limiter = AttemptLimiter::new(AttemptLimiterConfig {
+            max_failures: 2,
+            lockout: Duration::from_secs(10),
+            ..Default::default()
+        });
+        let key = AttemptKey::new("aladdin", None);
+        let now = Instant::now();
+
+        limiter.record_failure_at(&key, now);
+        limiter.record_failure_at(&key, now);
+        assert_eq!(limiter.check_at(&key, now), Err(Duration::from_secs(10)));
+
+        let now = now + Duration::from_secs(10);
+        limiter.record_success(&key);
+        limiter.record_failure_at(&key, now);
+        assert!(limiter.check_at(&key, now).is_ok());
+        limiter.record_failure_at(&key, now);
+        assert_eq!(limiter.check_at(&key, now), Err(Duration::from_secs(20)));
+    }
+
+    #[test]
+    fn test_reserve() {
+        let limiter = AttemptLimiter::new(AttemptLimiterConfig {
+            max_failures: 3,
+            lockout: Duration::from_secs(10),
+            ..Default::default()
+        });
+        let key = AttemptKey::new("aladdin", None);
+        let now = Instant::now();
+
+        // Concurrent attempts are counted before any of them is verified.
+        let attempts = (0..5)
+            .map(|_| limiter.reserve_at(&key, now))
+            .collect::<Vec<_>>();
+        assert_eq!(attempts.iter().filter(|x| x.is_ok()).count(), 3);
+        assert_eq!(
+            attempts.last().unwrap().as_ref().unwrap_err(),
+            &Duration::from_secs(10)
+        );
+
+        // The attempt that started the lockout succeeded, the lockout is undone.
+        let mut attempts = attempts.into_iter().flatten();
+        let _ = attempts.next();
+        let _ = attempts.next();
+        limiter.succeed(&key, attempts.next().unwrap());
+        assert!(limiter.check_at(&key, now).is_ok());
+
+        // Released attempts are not counted.
+        for _ in 0..3 {
+            let attempt = limiter.reserve_at(&key, now).unwrap();
+            limiter.release(&key, attempt);
+        }
+        assert!(limiter.check_at(&key, now).is_ok());
+    }
+
+    #[test]
+    fn test_max_entries() {
+        let limiter = AttemptLimiter::new(AttemptLimiterConfig {
+            window: Duration::from_secs(60),
+            max_lockout: Duration::from_secs(60),
+            max_entries: 3,
+            ..Default::default()
+        });
+        let now = Instant::now();
+
+        for i in 0..10 {
+            limiter.record_failure_at(&AttemptKey::new(alloc::format!("user{i}"), None), now);
+        }
+        assert_eq!(limiter.len(), 3);
+        assert!(limiter
+            .state()
+            .entries
+            .contains_key(&AttemptKey::new("user9", None)));
+        assert!(!limiter
+            .state()
+            .entries
+            .contains_key(&AttemptKey::new("user0", None)));
+
+        // Stale keys are pruned without an explicit `prune`.
+        let now = now + Duration::from_secs(60);
+        limiter.record_failure_at(&AttemptKey::new("other", None), now);
+        assert_eq!(limiter.len(), 1);
+    }
+
+    #[test]
+    fn test_max_entries_keeps_lockouts() {
+        let limiter = AttemptLimiter::new(AttemptLimiterConfig {
+            window: Duration::from_secs(60),
+            max_failures: 2,
+            lockout: Duration::from_secs(10),
+            max_lockout: Duration::from_secs(60),
+            max_entries: 3,
+        });
+        let victim = AttemptKey::new("victim", None);
+        let now = Instant::now();
+
+        limiter.record_failure_at(&victim, now);
+        limiter.record_failure_at(&victim, now);
+        assert_eq!(limiter.check_at(&victim, now), Err(Duration::from_secs(10)));
+
+        // A flood of new keys evicts the other keys, not the locked out one.
+        for i in 0..100 {
+            let _ = limiter.reserve_at(&AttemptKey::new(alloc::format!("junk{i}"), None), now);
+        }
+        assert_eq!(limiter.len(), 3);
+        assert_eq!(limiter.check_at(&victim, now), Err(Duration::from_secs(10)));
+
+        // Once every key is locked out, new keys are refused.
+        for i in 0..2 {
+            let key = AttemptKey::new(alloc::format!("junk{}", 98 + i), None);
+            limiter.record_failure_at(&key, now);
+        }
+        let now = now + Duration::from_secs(1);
+        assert_eq!(
+            limiter
+                .reserve_at(&AttemptKey::new("new", None), now)
+                .unwrap_err(),
+            Duration::from_secs(9)
+        );
+        assert_eq!(limiter.check_at(&victim, now), Err(Duration::from_secs(9)));
+
+        // The lockout has passed, the victim key can be evicted again.
+        let now = now + Duration::from_secs(9);
+        assert!(limiter
+            .reserve_at(&AttemptKey::new("new", None), now)
+            .is_ok());
+    }
+
+    #[cfg(feature = "scheme-basic")]
+    #[test]
+    fn test_limited_verifier() {
+        use crate::verifier::{verify_credentials, CredentialsSource};
+
+        struct PasswordVerifier;
+
+        impl Verifier for PasswordVerifier {
+            type Identity = Box<str>;
+
+            fn verify(
+                &self,
+                credentials: &Credentials,
+                _ctx: &VerifyContext<'_>,
+            ) -> Result<Self::Identity, Rejection> {
+                match credentials.as_basic() {
+                    Some(c) if &*c.password == "opensesame" => Ok(c.user_id.clone()),
+                    Some(_) => Err(Rejection::Invalid),
+                    None => Err(Rejection::SchemeUnsupported),
+                }
+            }
+        }
+
+        let verifier = LimitedVerifier::new(
+            PasswordVerifier,
+            AttemptLimiter::new(AttemptLimiterConfig {
+                max_failures: 2,
+                ..Default::default()
+            }),
+        );
+        let ctx = VerifyContext::new(CredentialsSource::Authorization)
+            .with_client_addr(IpAddr::from([127, 0, 0, 1]));
+
+        let wrong = Credentials::basic("aladdin", "foo");
+        let right = Credentials::basic("aladdin", "opensesame");
+
+        match verify_credentials(&verifier, Some(Ok(right.clone())), &ctx) {
+            Ok(user_id) => assert_eq!(&*user_id, "aladdin"),
+            x => panic!("{x:?}"),
+        }
+        for _ in 0..2 {
+            match verify_credentials(&verifier, Some(Ok(wrong.clone())), &ctx) {
+                Err(Rejection::Invalid) => {}
+                x => panic!("{x:?}"),
+            }
+        }
+        match verify_credentials(&verifier, Some(Ok(right)), &ctx) {
+            Err(err @ Rejection::TooManyAttempts { .. }) => {
+                assert_eq!(err.retry_after_secs(), Some(30));
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+}
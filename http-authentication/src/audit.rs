@@ -0,0 +1,360 @@
+//! Audit events of server-side verification.
+//!
+//! Events never include secrets, Bearer tokens are reduced to a fingerprint.
+
+use alloc::{boxed::Box, string::String};
+use core::{fmt::Write as _, net::IpAddr};
+
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    credentials::{Credentials, CredentialsParseError},
+    verifier::{CredentialsSource, Rejection, Verifier, VerifyContext},
+};
+
+//
+const FINGERPRINT_LEN: usize = 8;
+
+//
+pub trait AuditObserver {
+    fn on_event(&self, event: &AuditEvent<'_>);
+}
+
+impl<F> AuditObserver for F
+where
+    F: Fn(&AuditEvent<'_>),
+{
+    fn on_event(&self, event: &AuditEvent<'_>) {
+        self(event)
+    }
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEvent<'a> {
+    pub scheme: Option<&'static str>,
+    pub realm: Option<&'a str>,
+    pub principal: Option<Principal>,
+    pub outcome: AuditOutcome,
+    /// [`Rejection::kind`].
+    pub failure: Option<&'static str>,
+    /// [`CredentialsParseError::kind`] when the credentials were malformed.
+    pub parse_error: Option<&'static str>,
+    pub source: CredentialsSource,
+    pub client_addr: Option<IpAddr>,
+}
+
+impl<'a> AuditEvent<'a> {
+    fn new(ctx: &VerifyContext<'a>, outcome: AuditOutcome) -> Self {
+        Self {
+            scheme: None,
+            realm: ctx.realm,
+            principal: None,
+            outcome,
+            failure: None,
+            parse_error: None,
+            source: ctx.source,
+            client_addr: ctx.client_addr,
+        }
+    }
+
+    fn with_credentials(mut self, credentials: &Credentials) -> Self {
+        self.scheme = Some(credentials.scheme());
+        self.principal = Principal::from_credentials(credentials);
+        self
+    }
+
+    fn with_rejection(mut self, rejection: &Rejection) -> Self {
+        self.failure = Some(rejection.kind());
+        if let Rejection::Malformed(err) = rejection {
+            self.parse_error = Some(err.kind());
+            self.scheme = self.scheme.or_else(|| parse_error_scheme(err));
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Principal {
+    UserId(Box<str>),
+    /// `sha256:` followed by the hex of the first bytes of the SHA-256 of the token.
+    TokenFingerprint(Box<str>),
+}
+
+impl Principal {
+    #[allow(unused_variables)]
+    pub fn from_credentials(credentials: &Credentials) -> Option<Self> {
+        match credentials {
+            #[cfg(feature = "scheme-basic")]
+            Credentials::Basic(c) => Some(Self::UserId(c.user_id.clone())),
+            #[cfg(feature = "scheme-bearer")]
+            Credentials::Bearer(c) => Some(Self::TokenFingerprint(token_fingerprint(
+                c.token.as_bytes(),
+            ))),
+            #[cfg(feature = "scheme-vapid")]
+            Credentials::Vapid(c) => Some(Self::TokenFingerprint(token_fingerprint(
+                c.public_key.as_bytes(),
+            ))),
+            #[cfg(feature = "scheme-concealed")]
+            Credentials::Concealed(c) => Some(Self::TokenFingerprint(token_fingerprint(
+                c.key_id.as_bytes(),
+            ))),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Display for Principal {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UserId(x) | Self::TokenFingerprint(x) => f.write_str(x),
+        }
+    }
+}
+
+pub fn token_fingerprint(token: impl AsRef<[u8]>) -> Box<str> {
+    let digest = Sha256::digest(token.as_ref());
+    let mut s = String::with_capacity(7 + FINGERPRINT_LEN * 2);
+    s.push_str("sha256:");
+    for b in &digest[..FINGERPRINT_LEN] {
+        let _ = write!(s, "{b:02x}");
+    }
+    s.into()
+}
+
+#[allow(unused_variables)]
+fn parse_error_scheme(err: &CredentialsParseError) -> Option<&'static str> {
+    match err {
+        #[cfg(feature = "scheme-basic")]
+        CredentialsParseError::Basic(_) => Some(crate::schemes::NAME_BASIC),
+        #[cfg(feature = "scheme-bearer")]
+        CredentialsParseError::Bearer(_) => Some(crate::schemes::NAME_BEARER),
+        #[cfg(feature = "scheme-vapid")]
+        CredentialsParseError::Vapid(_) => Some(crate::schemes::NAME_VAPID),
+        #[cfg(feature = "scheme-concealed")]
+        CredentialsParseError::Concealed(_) => Some(crate::schemes::NAME_CONCEALED),
+        _ => None,
+    }
+}
+
+//
+/// Wraps a [`Verifier`], reports every decision to an [`AuditObserver`].
+///
+/// With the `tracing` feature, verification runs inside a `verify` span
+/// whose `outcome` field is recorded once it is decided.
+#[derive(Debug)]
+pub struct Audited<V, O> {
+    inner: V,
+    observer: O,
+}
+
+impl<V, O> Audited<V, O> {
+    pub fn new(inner: V, observer: O) -> Self {
+        Self { inner, observer }
+    }
+
+    pub fn inner(&self) -> &V {
+        &self.inner
+    }
+}
+
+impl<V: Verifier, O: AuditObserver> Verifier for Audited<V, O> {
+    type Identity = V::Identity;
+
+    fn verify(
+        &self,
+        credentials: &Credentials,
+        ctx: &VerifyContext<'_>,
+    ) -> Result<Self::Identity, Rejection> {
+        #[cfg(feature = "tracing")]
+        let span = verify_span(ctx, Some(credentials.scheme()));
+        #[cfg(feature = "tracing")]
+        let _guard = span.enter();
+
+        let result = self.inner.verify(credentials, ctx);
+        #[cfg(feature = "tracing")]
+        span.record(
+            "outcome",
+            match &result {
+                Ok(_) => "success",
+                Err(_) => "failure",
+            },
+        );
+
+        let event = match &result {
+            Ok(_) => AuditEvent::new(ctx, AuditOutcome::Success),
+            Err(rejection) => AuditEvent::new(ctx, AuditOutcome::Failure).with_rejection(rejection),
+        }
+        .with_credentials(credentials);
+        self.observer.on_event(&event);
+
+        result
+    }
+
+    fn reject(&self, rejection: Rejection, ctx: &VerifyContext<'_>) -> Rejection {
+        #[cfg(feature = "tracing")]
+        let span = verify_span(ctx, None);
+        #[cfg(feature = "tracing")]
+        let _guard = span.enter();
+
+        let rejection = self.inner.reject(rejection, ctx);
+        #[cfg(feature = "tracing")]
+        span.record("outcome", "failure");
+
+        let event = AuditEvent::new(ctx, AuditOutcome::Failure).with_rejection(&rejection);
+        self.observer.on_event(&event);
+
+        rejection
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn verify_span(ctx: &VerifyContext<'_>, scheme: Option<&'static str>) -> tracing::Span {
+    tracing::info_span!(
+        target: "http_authentication::audit",
+        "verify",
+        scheme,
+        realm = ctx.realm,
+        source = ?ctx.source,
+        client_addr = ?ctx.client_addr,
+        outcome = tracing::field::Empty,
+    )
+}
+
+//
+/// Emits audit events as `tracing` events with target `http_authentication::audit`.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingObserver;
+
+#[cfg(feature = "tracing")]
+impl AuditObserver for TracingObserver {
+    fn on_event(&self, event: &AuditEvent<'_>) {
+        let principal = event.principal.as_ref().map(|x| alloc::format!("{x}"));
+        match event.outcome {
+            AuditOutcome::Success => tracing::info!(
+                target: "http_authentication::audit",
+                scheme = event.scheme,
+                realm = event.realm,
+                principal = principal.as_deref(),
+                source = ?event.source,
+                client_addr = ?event.client_addr,
+                "authentication succeeded"
+            ),
+            AuditOutcome::Failure => tracing::warn!(
+                target: "http_authentication::audit",
+                scheme = event.scheme,
+                realm = event.realm,
+                principal = principal.as_deref(),
+                failure = event.failure,
+                parse_error = event.parse_error,
+                source = ?event.source,
+                client_addr = ?event.client_addr,
+                "authentication failed"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::{format, vec::Vec};
+    use core::cell::RefCell;
+
+    use crate::verifier::verify_credentials;
+
+    struct PasswordVerifier;
+
+    impl Verifier for PasswordVerifier {
+        type Identity = ();
+
+        fn verify(
+            &self,
+            credentials: &Credentials,
+            _ctx: &VerifyContext<'_>,
+        ) -> Result<Self::Identity, Rejection> {
+            match credentials {
+                #[cfg(feature = "scheme-basic")]
+                Credentials::Basic(c) if &*c.password == "opensesame" => Ok(()),
+                _ => Err(Rejection::Invalid),
+            }
+        }
+    }
+
+    #[test]
+    fn test_audited() {
+        let events = RefCell::new(Vec::new());
+        let observer = |event: &AuditEvent<'_>| {
+            events.borrow_mut().push(format!(
+                "{:?} {:?} {:?} {:?} {:?} {:?}",
+                event.outcome,
+                event.scheme,
+                event.principal.as_ref().map(|x| format!("{x}")),
+                event.failure,
+                event.parse_error,
+                event.realm,
+            ))
+        };
+        let verifier = Audited::new(PasswordVerifier, observer);
+        let ctx = VerifyContext::new(CredentialsSource::Authorization).with_realm("foo");
+
+        let _ = verify_credentials(&verifier, None, &ctx);
+        let _ = verify_credentials(&verifier, Some(Credentials::from_bytes("Foo bar")), &ctx);
+
+        #[cfg(feature = "scheme-basic")]
+        {
+            let _ = verify_credentials(
+                &verifier,
+                Some(Ok(Credentials::basic("aladdin", "opensesame"))),
+                &ctx,
+            );
+            let _ = verify_credentials(
+                &verifier,
+                Some(Credentials::from_bytes("Basic  Zm9v")),
+                &ctx,
+            );
+        }
+        #[cfg(feature = "scheme-bearer")]
+        {
+            let _ = verify_credentials(&verifier, Some(Ok(Credentials::bearer("secret"))), &ctx);
+        }
+
+        let events = events.into_inner();
+        assert_eq!(
+            events[0],
+            r#"Failure None None Some("Missing") None Some("foo")"#
+        );
+        assert_eq!(
+            events[1],
+            r#"Failure None None Some("Malformed") Some("SchemeUnknown") Some("foo")"#
+        );
+        #[cfg(feature = "scheme-basic")]
+        {
+            assert_eq!(
+                events[2],
+                r#"Success Some("Basic") Some("aladdin") None None Some("foo")"#
+            );
+            assert_eq!(
+                events[3],
+                r#"Failure Some("Basic") None Some("Malformed") Some("Token68Invalid") Some("foo")"#
+            );
+        }
+        #[cfg(feature = "scheme-bearer")]
+        {
+            let event = events.last().unwrap();
+            assert!(event.starts_with(r#"Failure Some("Bearer") Some("sha256:"#));
+            assert!(!event.contains("secret"));
+        }
+
+        assert_eq!(&*token_fingerprint("secret"), "sha256:2bb80d537b1da3e3");
+    }
+}
@@ -0,0 +1,374 @@
+//! Complete `401`, `403` and `407` responses for failed verification.
+//!
+//! The body is an [RFC 9457](https://www.rfc-editor.org/rfc/rfc9457) `application/problem+json`
+//! document, responses are marked `Cache-Control: no-store`.
+
+use alloc::{borrow::ToOwned as _, boxed::Box, string::String, vec::Vec};
+use core::fmt::Write as _;
+
+use http::{
+    header::{CACHE_CONTROL, CONTENT_TYPE, RETRY_AFTER},
+    HeaderValue, Response, StatusCode,
+};
+
+use crate::{
+    challenge::Challenge,
+    header_utils::{
+        append_proxy_authenticate_with_multiple, append_www_authenticate_with_multiple,
+    },
+    verifier::{CredentialsSource, Rejection},
+};
+
+//
+pub const CONTENT_TYPE_PROBLEM_JSON: &str = "application/problem+json";
+
+//
+#[derive(Debug)]
+pub enum AuthFailure {
+    Rejected(Rejection),
+    /// The credentials are valid but lack `scope`, answered with `403 Forbidden`.
+    InsufficientScope {
+        scope: Box<str>,
+    },
+}
+
+impl From<Rejection> for AuthFailure {
+    fn from(rejection: Rejection) -> Self {
+        match rejection {
+            Rejection::InsufficientScope { scope } => Self::InsufficientScope { scope },
+            rejection => Self::Rejected(rejection),
+        }
+    }
+}
+
+impl AuthFailure {
+    fn insufficient_scope(&self) -> Option<&str> {
+        match self {
+            Self::InsufficientScope { scope }
+            | Self::Rejected(Rejection::InsufficientScope { scope }) => Some(scope),
+            Self::Rejected(_) => None,
+        }
+    }
+
+    fn detail(&self) -> &str {
+        match self {
+            Self::Rejected(Rejection::Missing) => "No credentials were provided",
+            Self::Rejected(Rejection::Malformed(_)) => "The credentials could not be parsed",
+            Self::Rejected(Rejection::SchemeUnsupported) => {
+                "The authentication scheme is not supported"
+            }
+            Self::Rejected(Rejection::Invalid) => "The credentials are invalid",
+            Self::Rejected(Rejection::TooManyAttempts { .. }) => {
+                "Too many failed authentication attempts"
+            }
+            Self::Rejected(Rejection::Other(detail)) => detail,
+            Self::InsufficientScope { .. }
+            | Self::Rejected(Rejection::InsufficientScope { .. }) => {
+                "The access token lacks the required scope"
+            }
+        }
+    }
+}
+
+//
+/// An RFC 9457 problem details object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub type_: Box<str>,
+    pub status: u16,
+    pub title: Box<str>,
+    pub detail: Option<Box<str>>,
+    pub instance: Option<Box<str>>,
+}
+
+impl Problem {
+    /// A problem for any status, e.g. to answer other failures in the same format.
+    pub fn new(status: StatusCode, detail: Option<&str>) -> Self {
+        Self {
+            type_: "about:blank".into(),
+            status: status.as_u16(),
+            title: status.canonical_reason().unwrap_or_default().into(),
+            detail: detail.map(Into::into),
+            instance: None,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        s.push_str("{\"type\":");
+        push_json_str(&mut s, &self.type_);
+        let _ = write!(s, ",\"status\":{}", self.status);
+        s.push_str(",\"title\":");
+        push_json_str(&mut s, &self.title);
+        if let Some(detail) = &self.detail {
+            s.push_str(",\"detail\":");
+            push_json_str(&mut s, detail);
+        }
+        if let Some(instance) = &self.instance {
+            s.push_str(",\"instance\":");
+            push_json_str(&mut s, instance);
+        }
+        s.push('}');
+        s
+    }
+}
+
+fn push_json_str(s: &mut String, value: &str) {
+    s.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            '\r' => s.push_str("\\r"),
+            '\t' => s.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(s, "\\u{:04x}", c as u32);
+            }
+            c => s.push(c),
+        }
+    }
+    s.push('"');
+}
+
+//
+/// Builds the response to a failed verification.
+///
+/// `Authorization` failures are answered with `401` and `WWW-Authenticate`,
+/// `Proxy-Authorization` failures with `407` and `Proxy-Authenticate`.
+#[derive(Debug, Clone)]
+pub struct AuthResponse {
+    source: CredentialsSource,
+    challenges: Vec<Challenge>,
+    problem_type: Option<Box<str>>,
+    instance: Option<Box<str>>,
+}
+
+impl AuthResponse {
+    pub fn new(source: CredentialsSource) -> Self {
+        Self {
+            source,
+            challenges: Vec::new(),
+            problem_type: None,
+            instance: None,
+        }
+    }
+
+    // `Challenge` has no variants when no scheme is enabled.
+    #[allow(unreachable_code)]
+    pub fn challenge(mut self, challenge: impl Into<Challenge>) -> Self {
+        self.challenges.push(challenge.into());
+        self
+    }
+
+    /// E.g. [`crate::RealmConfig::challenges`].
+    pub fn challenges(mut self, challenges: &[Challenge]) -> Self {
+        self.challenges.extend_from_slice(challenges);
+        self
+    }
+
+    /// The `type` member, `about:blank` when unset.
+    pub fn problem_type(mut self, uri: impl AsRef<str>) -> Self {
+        self.problem_type = Some(uri.as_ref().into());
+        self
+    }
+
+    /// The `instance` member, usually the request path.
+    pub fn instance(mut self, uri: impl AsRef<str>) -> Self {
+        self.instance = Some(uri.as_ref().into());
+        self
+    }
+
+    pub fn status(&self, failure: &AuthFailure) -> StatusCode {
+        match (failure.insufficient_scope(), self.source) {
+            (Some(_), _) => StatusCode::FORBIDDEN,
+            (None, CredentialsSource::Authorization | CredentialsSource::ApiKey(_)) => {
+                StatusCode::UNAUTHORIZED
+            }
+            (None, CredentialsSource::ProxyAuthorization) => {
+                StatusCode::PROXY_AUTHENTICATION_REQUIRED
+            }
+        }
+    }
+
+    pub fn problem(&self, failure: &AuthFailure) -> Problem {
+        let mut problem = Problem::new(self.status(failure), Some(failure.detail()));
+        if let Some(problem_type) = &self.problem_type {
+            problem.type_ = problem_type.clone();
+        }
+        problem.instance = self.instance.clone();
+        problem
+    }
+
+    /// The challenges sent for `failure`.
+    ///
+    /// For an insufficient scope this is a single Bearer challenge with `error="insufficient_scope"`,
+    /// in the realm of the first configured challenge, none without the `scheme-bearer` feature.
+    pub fn challenges_for(&self, failure: &AuthFailure) -> Vec<Challenge> {
+        match failure.insufficient_scope() {
+            #[cfg(feature = "scheme-bearer")]
+            Some(scope) => {
+                let realm = self
+                    .challenges
+                    .iter()
+                    .find_map(crate::selection::realm_of)
+                    .unwrap_or_default();
+                let mut c = crate::schemes::bearer::Challenge::new(realm);
+                c.scope = Some(scope.into());
+                c.error = Some(crate::schemes::bearer::ERROR_INSUFFICIENT_SCOPE.into());
+                alloc::vec![c.into()]
+            }
+            #[cfg(not(feature = "scheme-bearer"))]
+            Some(_) => Vec::new(),
+            None => self.challenges.to_owned(),
+        }
+    }
+
+    pub fn build(&self, failure: &AuthFailure) -> Response<Vec<u8>> {
+        let mut response = Response::new(self.problem(failure).to_json().into_bytes());
+        *response.status_mut() = self.status(failure);
+
+        let headers = response.headers_mut();
+        let challenges = self.challenges_for(failure);
+        if !challenges.is_empty() {
+            // Challenges are built from the parsed or typed structs, they are valid header values.
+            let _ = match (failure.insufficient_scope(), self.source) {
+                (Some(_), _)
+                | (None, CredentialsSource::Authorization | CredentialsSource::ApiKey(_)) => {
+                    append_www_authenticate_with_multiple(headers, &challenges)
+                }
+                (None, CredentialsSource::ProxyAuthorization) => {
+                    append_proxy_authenticate_with_multiple(headers, &challenges)
+                }
+            };
+        }
+        if let AuthFailure::Rejected(rejection) = failure {
+            if let Some(secs) = rejection.retry_after_secs() {
+                headers.insert(RETRY_AFTER, secs.into());
+            }
+        }
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static(CONTENT_TYPE_PROBLEM_JSON),
+        );
+        response
+    }
+
+    /// Same as [`AuthResponse::build`] for a [`Rejection`].
+    pub fn reject(&self, rejection: Rejection) -> Response<Vec<u8>> {
+        self.build(&rejection.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "scheme-basic")]
+    #[test]
+    fn test_build() {
+        let builder = AuthResponse::new(CredentialsSource::Authorization)
+            .challenge(crate::schemes::basic::Challenge::new("api"))
+            .instance("/v1/items");
+
+        let response = builder.reject(Rejection::Missing);
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()["www-authenticate"],
+            r#"Basic realm="api""#
+        );
+        assert_eq!(response.headers()["cache-control"], "no-store");
+        assert_eq!(
+            response.headers()["content-type"],
+            CONTENT_TYPE_PROBLEM_JSON
+        );
+        assert_eq!(
+            core::str::from_utf8(response.body()).unwrap(),
+            r#"{"type":"about:blank","status":401,"title":"Unauthorized","detail":"No credentials were provided","instance":"/v1/items"}"#
+        );
+
+        let response = AuthResponse::new(CredentialsSource::ProxyAuthorization)
+            .challenge(crate::schemes::basic::Challenge::new("proxy"))
+            .reject(Rejection::TooManyAttempts {
+                retry_after: core::time::Duration::from_millis(1500),
+            });
+        assert_eq!(response.status(), StatusCode::PROXY_AUTHENTICATION_REQUIRED);
+        assert_eq!(
+            response.headers()["proxy-authenticate"],
+            r#"Basic realm="proxy""#
+        );
+        assert_eq!(response.headers()["retry-after"], "2");
+        assert!(response.headers().get("www-authenticate").is_none());
+    }
+
+    #[cfg(feature = "scheme-bearer")]
+    #[test]
+    fn test_build_insufficient_scope() {
+        let response = AuthResponse::new(CredentialsSource::Authorization)
+            .challenge(crate::schemes::bearer::Challenge::new("api"))
+            .problem_type("https://example.com/problems/scope")
+            .build(&AuthFailure::InsufficientScope {
+                scope: "write".into(),
+            });
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.headers()["www-authenticate"],
+            r#"Bearer realm="api", scope="write", error="insufficient_scope""#
+        );
+        assert!(core::str::from_utf8(response.body()).unwrap().starts_with(
+            r#"{"type":"https://example.com/problems/scope","status":403,"title":"Forbidden""#
+        ));
+    }
+
+    #[test]
+    fn test_reject_insufficient_scope() {
+        let builder = AuthResponse::new(CredentialsSource::ProxyAuthorization);
+        let rejection = || Rejection::InsufficientScope {
+            scope: "read admin".into(),
+        };
+
+        let response = builder.reject(rejection());
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            builder.status(&AuthFailure::Rejected(rejection())),
+            StatusCode::FORBIDDEN
+        );
+        #[cfg(feature = "scheme-bearer")]
+        assert_eq!(
+            response.headers()["www-authenticate"],
+            r#"Bearer realm="", scope="read admin", error="insufficient_scope""#
+        );
+        assert!(response.headers().get("proxy-authenticate").is_none());
+    }
+
+    #[cfg(feature = "introspection")]
+    #[test]
+    fn test_reject_introspection_insufficient_scope() {
+        use crate::schemes::bearer::introspection::IntrospectionRejection;
+
+        let response = AuthResponse::new(CredentialsSource::Authorization)
+            .challenge(crate::schemes::bearer::Challenge::new("api"))
+            .reject(
+                IntrospectionRejection::InsufficientScope {
+                    scope: "admin".into(),
+                }
+                .into(),
+            );
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.headers()["www-authenticate"],
+            r#"Bearer realm="api", scope="admin", error="insufficient_scope""#
+        );
+    }
+
+    #[test]
+    fn test_problem_to_json() {
+        let problem = Problem::new(StatusCode::UNAUTHORIZED, Some("a \"b\"\n\u{1}"));
+        assert_eq!(
+            problem.to_json(),
+            r#"{"type":"about:blank","status":401,"title":"Unauthorized","detail":"a \"b\"\n\u0001"}"#
+        );
+        serde_json::from_str::<serde_json::Value>(&problem.to_json()).unwrap();
+    }
+}
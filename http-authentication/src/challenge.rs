@@ -1,10 +1,43 @@
 //
-#[derive(Debug, Clone)]
+/// Appends `value` as a quoted-string, escaping `"` and `\`.
+#[allow(dead_code)]
+pub(crate) fn push_quoted_string(s: &mut alloc::string::String, value: &str) {
+    s.push(crate::D_Q_M);
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            s.push('\\');
+        }
+        s.push(c);
+    }
+    s.push(crate::D_Q_M);
+}
+
+/// The value of an escaped quoted-string, without the quotes.
+#[allow(dead_code)]
+pub(crate) fn unescape_quoted_string(value: &str) -> alloc::boxed::Box<str> {
+    if !value.contains('\\') {
+        return value.into();
+    }
+    let mut s = alloc::string::String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => s.extend(chars.next()),
+            c => s.push(c),
+        }
+    }
+    s.into()
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Challenge {
     #[cfg(feature = "scheme-basic")]
     Basic(crate::schemes::basic::Challenge),
     #[cfg(feature = "scheme-bearer")]
     Bearer(crate::schemes::bearer::Challenge),
+    #[cfg(feature = "scheme-digest")]
+    Digest(crate::schemes::digest::Challenge),
 }
 
 impl Challenge {
@@ -37,6 +70,34 @@ impl Challenge {
             _ => None,
         }
     }
+
+    //
+    #[cfg(feature = "scheme-digest")]
+    pub fn digest(challenge: crate::schemes::digest::Challenge) -> Self {
+        Self::Digest(challenge)
+    }
+
+    #[cfg(feature = "scheme-digest")]
+    pub fn as_digest(&self) -> Option<&crate::schemes::digest::Challenge> {
+        match self {
+            Self::Digest(c) => Some(c),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    //
+    // Exhaustive without a fallback arm, there are no variants when no scheme is enabled.
+    pub fn scheme(&self) -> &'static str {
+        match *self {
+            #[cfg(feature = "scheme-basic")]
+            Self::Basic(_) => crate::schemes::NAME_BASIC,
+            #[cfg(feature = "scheme-bearer")]
+            Self::Bearer(_) => crate::schemes::NAME_BEARER,
+            #[cfg(feature = "scheme-digest")]
+            Self::Digest(_) => crate::schemes::NAME_DIGEST,
+        }
+    }
 }
 
 #[cfg(feature = "scheme-basic")]
@@ -53,6 +114,13 @@ impl From<crate::schemes::bearer::Challenge> for Challenge {
     }
 }
 
+#[cfg(feature = "scheme-digest")]
+impl From<crate::schemes::digest::Challenge> for Challenge {
+    fn from(c: crate::schemes::digest::Challenge) -> Self {
+        Self::digest(c)
+    }
+}
+
 //
 #[allow(unused_variables)]
 impl core::fmt::Display for Challenge {
@@ -62,6 +130,8 @@ impl core::fmt::Display for Challenge {
             Self::Basic(c) => c.fmt(f),
             #[cfg(feature = "scheme-bearer")]
             Self::Bearer(c) => c.fmt(f),
+            #[cfg(feature = "scheme-digest")]
+            Self::Digest(c) => c.fmt(f),
             #[allow(unreachable_patterns)]
             _ => unimplemented!(),
         }
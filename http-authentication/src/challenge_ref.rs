@@ -0,0 +1,631 @@
+use crate::{
+    challenge::Challenge,
+    challenges::ChallengesParseError,
+    parse_options::{is_whitespace, ParseErrorPosition, ParseOptions},
+    schemes::{NAME_BASIC, NAME_BEARER, NAME_DIGEST},
+};
+
+//
+/// Borrowed [`Challenge`], the values point into the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeRef<'a> {
+    #[cfg(feature = "scheme-basic")]
+    Basic(crate::schemes::basic::ChallengeRef<'a>),
+    #[cfg(feature = "scheme-bearer")]
+    Bearer(crate::schemes::bearer::ChallengeRef<'a>),
+    #[cfg(feature = "scheme-digest")]
+    Digest(crate::schemes::digest::ChallengeRef<'a>),
+    #[cfg(not(any(
+        feature = "scheme-basic",
+        feature = "scheme-bearer",
+        feature = "scheme-digest"
+    )))]
+    #[doc(hidden)]
+    _Phantom(core::convert::Infallible, core::marker::PhantomData<&'a ()>),
+}
+
+impl<'a> ChallengeRef<'a> {
+    //
+    #[cfg(feature = "scheme-basic")]
+    pub fn as_basic(&self) -> Option<&crate::schemes::basic::ChallengeRef<'a>> {
+        match self {
+            Self::Basic(c) => Some(c),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    //
+    #[cfg(feature = "scheme-bearer")]
+    pub fn as_bearer(&self) -> Option<&crate::schemes::bearer::ChallengeRef<'a>> {
+        match self {
+            Self::Bearer(c) => Some(c),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    //
+    #[cfg(feature = "scheme-digest")]
+    pub fn as_digest(&self) -> Option<&crate::schemes::digest::ChallengeRef<'a>> {
+        match self {
+            Self::Digest(c) => Some(c),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    //
+    pub fn into_owned(self) -> Challenge {
+        match self {
+            #[cfg(feature = "scheme-basic")]
+            Self::Basic(c) => Challenge::Basic(c.into_owned()),
+            #[cfg(feature = "scheme-bearer")]
+            Self::Bearer(c) => Challenge::Bearer(c.into_owned()),
+            #[cfg(feature = "scheme-digest")]
+            Self::Digest(c) => Challenge::Digest(c.into_owned()),
+            #[cfg(not(any(
+                feature = "scheme-basic",
+                feature = "scheme-bearer",
+                feature = "scheme-digest"
+            )))]
+            Self::_Phantom(x, _) => match x {},
+        }
+    }
+}
+
+impl From<ChallengeRef<'_>> for Challenge {
+    fn from(c: ChallengeRef<'_>) -> Self {
+        c.into_owned()
+    }
+}
+
+/// The position of the errors is relative to the scheme of `c`.
+impl<'a> TryFrom<&http_auth::ChallengeRef<'a>> for ChallengeRef<'a> {
+    type Error = ChallengesParseError;
+
+    fn try_from(c: &http_auth::ChallengeRef<'a>) -> Result<Self, Self::Error> {
+        Self::from_parts(c.scheme, |name| {
+            c.params
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_escaped())
+        })
+    }
+}
+
+impl<'a> ChallengeRef<'a> {
+    /// `find` returns the escaped value of a param by its case-insensitive name.
+    ///
+    /// The position of the errors is relative to `scheme`.
+    #[allow(unused_variables)]
+    pub(crate) fn from_parts(
+        scheme: &'a str,
+        find: impl Fn(&str) -> Option<&'a str>,
+    ) -> Result<Self, ChallengesParseError> {
+        let pos = || ParseErrorPosition::from_input(scheme.as_bytes(), 0, scheme.len());
+
+        match scheme {
+            x if x.eq_ignore_ascii_case(NAME_BASIC) => {
+                #[cfg(feature = "scheme-basic")]
+                {
+                    crate::schemes::basic::ChallengeRef::from_params(find)
+                        .map(Self::Basic)
+                        .map_err(|err| ChallengesParseError::Basic(err, pos()))
+                }
+                #[cfg(not(feature = "scheme-basic"))]
+                {
+                    Err(ChallengesParseError::SchemeUnsupported(
+                        "Require feature scheme-basic",
+                        pos(),
+                    ))
+                }
+            }
+            x if x.eq_ignore_ascii_case(NAME_BEARER) => {
+                #[cfg(feature = "scheme-bearer")]
+                {
+                    crate::schemes::bearer::ChallengeRef::from_params(find)
+                        .map(Self::Bearer)
+                        .map_err(|err| ChallengesParseError::Bearer(err, pos()))
+                }
+                #[cfg(not(feature = "scheme-bearer"))]
+                {
+                    Err(ChallengesParseError::SchemeUnsupported(
+                        "Require feature scheme-bearer",
+                        pos(),
+                    ))
+                }
+            }
+            x if x.eq_ignore_ascii_case(NAME_DIGEST) => {
+                #[cfg(feature = "scheme-digest")]
+                {
+                    crate::schemes::digest::ChallengeRef::from_params(find)
+                        .map(Self::Digest)
+                        .map_err(|err| ChallengesParseError::Digest(err, pos()))
+                }
+                #[cfg(not(feature = "scheme-digest"))]
+                {
+                    Err(ChallengesParseError::SchemeUnsupported(
+                        "Require feature scheme-digest",
+                        pos(),
+                    ))
+                }
+            }
+            _ => Err(ChallengesParseError::SchemeUnknown(pos())),
+        }
+    }
+}
+
+//
+/// The auth-params of a challenge, a slice of the header parsed on access without allocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParamsRef<'a>(&'a str);
+
+impl<'a> ParamsRef<'a> {
+    /// The raw params, e.g. `realm="foo", charset="UTF-8"`.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// The name and the escaped value of each param, in order.
+    pub fn iter(&self) -> ParamsIter<'a> {
+        ParamsIter { s: self.0, i: 0 }
+    }
+
+    /// The escaped value of the first param named `name`, case-insensitively.
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        self.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ParamsIter<'a> {
+    s: &'a str,
+    i: usize,
+}
+
+impl<'a> Iterator for ParamsIter<'a> {
+    type Item = (&'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.i >= self.s.len() {
+            return None;
+        }
+        // Validated when the challenge was parsed.
+        let (name, value, end) = param(self.s, self.i).ok()?;
+        self.i = skip_list_separators(self.s.as_bytes(), end);
+        Some((name, value))
+    }
+}
+
+//
+/// Iterator over the challenges of a `WWW-Authenticate` or `Proxy-Authenticate` value.
+///
+/// The challenges and their params borrow from the input, nothing is allocated unless parsing fails.
+pub struct ChallengeRefs<'a> {
+    input: &'a str,
+    offset: usize,
+    trimmed: &'a str,
+    i: usize,
+    done: bool,
+    options: ParseOptions,
+}
+
+impl<'a> ChallengeRefs<'a> {
+    pub fn new(s: &'a str) -> Self {
+        Self::with_options(s, ParseOptions::default())
+    }
+
+    pub fn with_options(s: &'a str, options: ParseOptions) -> Self {
+        let offset = if options.allow_surrounding_whitespace {
+            s.bytes().position(|x| !is_whitespace(x)).unwrap_or(s.len())
+        } else {
+            0
+        };
+        let trimmed = if options.allow_surrounding_whitespace {
+            s[offset..].trim_end_matches(|x: char| x.is_ascii() && is_whitespace(x as u8))
+        } else {
+            s
+        };
+
+        Self {
+            input: s,
+            offset,
+            trimmed,
+            i: 0,
+            done: false,
+            options,
+        }
+    }
+}
+
+impl<'a> Iterator for ChallengeRefs<'a> {
+    type Item = Result<ChallengeRef<'a>, ChallengesParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.done {
+                return None;
+            }
+            let bytes = self.trimmed.as_bytes();
+            let start = skip_list_separators(bytes, self.i);
+
+            // Without allow_surrounding_whitespace nothing was trimmed.
+            let surrounding_whitespace = if self.i == 0 {
+                bytes
+                    .first()
+                    .copied()
+                    .filter(|x| is_whitespace(*x))
+                    .map(|_| 0)
+            } else if start >= bytes.len() {
+                bytes
+                    .iter()
+                    .rposition(|x| !is_whitespace(*x))
+                    .map(|x| x + 1)
+                    .filter(|x| *x < bytes.len())
+            } else {
+                None
+            };
+            let parsed = match surrounding_whitespace {
+                Some(at) => Err(("unexpected whitespace", at)),
+                None if start >= bytes.len() => {
+                    self.done = true;
+                    return None;
+                }
+                None => challenge(self.trimmed, start),
+            };
+
+            let (scheme, params, end) = match parsed {
+                Ok(x) => x,
+                Err((msg, at)) => {
+                    self.done = true;
+                    let pos =
+                        ParseErrorPosition::from_input(self.input.as_bytes(), self.offset + at, 1);
+                    return Some(Err(ChallengesParseError::ChallengeParserError(
+                        msg.into(),
+                        pos,
+                    )));
+                }
+            };
+            self.i = end;
+
+            match ChallengeRef::from_parts(scheme, |name| params.get(name)) {
+                Ok(c) => return Some(Ok(c)),
+                Err(ChallengesParseError::SchemeUnknown(_))
+                | Err(ChallengesParseError::SchemeUnsupported(_, _))
+                    if self.options.skip_unsupported_challenges =>
+                {
+                    continue
+                }
+                // e.g. `SHA-1` or a future algorithm, the other challenges are still usable.
+                #[cfg(feature = "scheme-digest")]
+                Err(ChallengesParseError::Digest(
+                    crate::schemes::digest::ChallengeParseError::AlgorithmUnknown,
+                    _,
+                )) if self.options.skip_unsupported_challenges => continue,
+                Err(mut err) => {
+                    if let Some(pos) = err.position_mut() {
+                        pos.offset += self.offset + start;
+                    }
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+//
+// Ref https://www.rfc-editor.org/rfc/rfc7235#section-2.1
+//
+type SyntaxError = (&'static str, usize);
+
+fn is_tchar(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+fn is_token68_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"-._~+/".contains(&b)
+}
+
+fn is_qdtext_or_quoted(b: u8) -> bool {
+    b == b'\t' || (b >= 0x20 && b != 0x7f)
+}
+
+fn skip_ows(b: &[u8], mut i: usize) -> usize {
+    while matches!(b.get(i), Some(b' ' | b'\t')) {
+        i += 1;
+    }
+    i
+}
+
+// Empty list elements are allowed, see https://www.rfc-editor.org/rfc/rfc7230#section-7
+fn skip_list_separators(b: &[u8], mut i: usize) -> usize {
+    while matches!(b.get(i), Some(b' ' | b'\t' | b',')) {
+        i += 1;
+    }
+    i
+}
+
+fn token_end(b: &[u8], mut i: usize) -> usize {
+    while b.get(i).copied().map(is_tchar).unwrap_or(false) {
+        i += 1;
+    }
+    i
+}
+
+// Whether `i` starts an auth-param, rather than a token68 or the scheme of the next challenge.
+fn starts_param(b: &[u8], i: usize) -> bool {
+    let name_end = token_end(b, i);
+    if name_end == i {
+        return false;
+    }
+    let j = skip_ows(b, name_end);
+    if b.get(j) != Some(&b'=') {
+        return false;
+    }
+    // `abc=` and `abc==` are token68.
+    let k = skip_ows(b, j + 1);
+    !matches!(b.get(k), None | Some(b',' | b'='))
+}
+
+/// `token BWS "=" BWS ( token / quoted-string )` at `i`, the value of a quoted-string is returned escaped.
+fn param(s: &str, i: usize) -> Result<(&str, &str, usize), SyntaxError> {
+    let b = s.as_bytes();
+    let name_end = token_end(b, i);
+    if name_end == i {
+        return Err(("expected token", i));
+    }
+    let j = skip_ows(b, name_end);
+    if b.get(j) != Some(&b'=') {
+        return Err(("expected '='", j));
+    }
+    let j = skip_ows(b, j + 1);
+
+    if b.get(j) != Some(&b'"') {
+        let value_end = token_end(b, j);
+        if value_end == j {
+            return Err(("expected token or quoted-string", j));
+        }
+        return Ok((&s[i..name_end], &s[j..value_end], value_end));
+    }
+
+    let mut k = j + 1;
+    loop {
+        match b.get(k) {
+            None => return Err(("unterminated quoted-string", j)),
+            Some(b'"') => return Ok((&s[i..name_end], &s[j + 1..k], k + 1)),
+            Some(b'\\') => match b.get(k + 1) {
+                Some(&x) if is_qdtext_or_quoted(x) => k += 2,
+                _ => return Err(("invalid quoted-pair", k)),
+            },
+            Some(&x) if is_qdtext_or_quoted(x) => k += 1,
+            Some(_) => return Err(("invalid character in quoted-string", k)),
+        }
+    }
+}
+
+/// `auth-scheme [ 1*SP ( token68 / #auth-param ) ]` at `i`, returns the scheme, the params and the end.
+fn challenge(s: &str, i: usize) -> Result<(&str, ParamsRef<'_>, usize), SyntaxError> {
+    let b = s.as_bytes();
+    let scheme_end = token_end(b, i);
+    if scheme_end == i {
+        return Err(("expected token", i));
+    }
+    let scheme = &s[i..scheme_end];
+
+    let j = skip_ows(b, scheme_end);
+    if matches!(b.get(j), None | Some(b',')) {
+        return Ok((scheme, ParamsRef::default(), j));
+    }
+    if j == scheme_end {
+        return Err(("expected SP", j));
+    }
+
+    if !starts_param(b, j) {
+        // The token68 is not used by the supported schemes, it is skipped.
+        let mut k = j;
+        while b.get(k).copied().map(is_token68_char).unwrap_or(false) {
+            k += 1;
+        }
+        if k == j {
+            return Err(("expected token68 or auth-param", j));
+        }
+        while b.get(k) == Some(&b'=') {
+            k += 1;
+        }
+        let k = skip_ows(b, k);
+        return match b.get(k) {
+            None | Some(b',') => Ok((scheme, ParamsRef::default(), k)),
+            Some(_) => Err(("expected ','", k)),
+        };
+    }
+
+    let mut k = j;
+    loop {
+        let (_, _, end) = param(s, k)?;
+        let next = skip_ows(b, end);
+        match b.get(next) {
+            None => return Ok((scheme, ParamsRef(&s[j..end]), next)),
+            Some(b',') => {
+                let m = skip_list_separators(b, next);
+                if !starts_param(b, m) {
+                    return Ok((scheme, ParamsRef(&s[j..end]), next));
+                }
+                k = m;
+            }
+            Some(_) => return Err(("expected ','", next)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_challenge_refs() {
+        #[cfg(all(feature = "scheme-basic", feature = "scheme-bearer"))]
+        {
+            let s = r#"Basic realm="foo", charset="UTF-8", Bearer realm="bar", scope="openid""#;
+            let list = ChallengeRefs::new(s)
+                .collect::<Result<alloc::vec::Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(list.len(), 2);
+
+            let c = list[0].as_basic().unwrap();
+            assert_eq!(c.realm, "foo");
+            assert_eq!(c.charset, Some("UTF-8"));
+
+            let c = list[1].as_bearer().unwrap();
+            assert_eq!(c.realm, "bar");
+            assert_eq!(c.scope, Some("openid"));
+
+            let c = list[1].into_owned();
+            assert_eq!(c.as_bearer().unwrap().realm, "bar".into());
+        }
+
+        //
+        match ChallengeRefs::new("Foo").next() {
+            Some(Err(ChallengesParseError::SchemeUnknown(pos))) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "Foo"));
+            }
+            x => panic!("{x:?}"),
+        }
+
+        #[cfg(not(feature = "scheme-digest"))]
+        match ChallengeRefs::new("Digest realm=\"foo\"").next() {
+            Some(Err(ChallengesParseError::SchemeUnsupported(_, pos))) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "Digest"));
+            }
+            x => panic!("{x:?}"),
+        }
+        #[cfg(feature = "scheme-digest")]
+        {
+            use alloc::string::ToString as _;
+
+            use crate::schemes::digest::{DEMO_CHALLENGE_REALM_STR, DEMO_CHALLENGE_STR};
+
+            match ChallengeRefs::new(DEMO_CHALLENGE_STR).next() {
+                Some(Ok(c)) => {
+                    let c = c.as_digest().unwrap();
+                    assert_eq!(c.realm, DEMO_CHALLENGE_REALM_STR);
+                    assert_eq!(c.into_owned().to_string(), DEMO_CHALLENGE_STR);
+                }
+                x => panic!("{x:?}"),
+            }
+
+            match ChallengeRefs::new("Digest realm=\"foo\"").next() {
+                Some(Err(ChallengesParseError::Digest(_, pos))) => {
+                    assert_eq!(pos, ParseErrorPosition::new(0, "Digest"));
+                }
+                x => panic!("{x:?}"),
+            }
+        }
+    }
+
+    #[cfg(all(feature = "scheme-basic", feature = "scheme-digest"))]
+    #[test]
+    fn test_challenge_refs_algorithm_unknown() {
+        use crate::schemes::digest::ChallengeParseError;
+
+        let s = r#"Digest realm="foo", nonce="abc", algorithm=SHA-1, Basic realm="bar""#;
+        match ChallengeRefs::new(s).next() {
+            Some(Err(ChallengesParseError::Digest(ChallengeParseError::AlgorithmUnknown, pos))) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "Digest"));
+            }
+            x => panic!("{x:?}"),
+        }
+
+        let list = ChallengeRefs::with_options(s, ParseOptions::lenient())
+            .collect::<Result<alloc::vec::Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].as_basic().unwrap().realm, "bar");
+    }
+
+    #[test]
+    fn test_challenge_refs_with_options() {
+        let s = r#"Foo realm="foo", Bar realm="bar" x"#;
+        match ChallengeRefs::new(s).next() {
+            Some(Err(ChallengesParseError::SchemeUnknown(pos))) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "Foo"));
+            }
+            x => panic!("{x:?}"),
+        }
+        match ChallengeRefs::with_options(s, ParseOptions::lenient()).next() {
+            Some(Err(ChallengesParseError::ChallengeParserError(_, pos))) => {
+                assert_eq!(pos, ParseErrorPosition::new(33, "x"));
+            }
+            x => panic!("{x:?}"),
+        }
+
+        #[cfg(feature = "scheme-basic")]
+        {
+            let s = r#"  Negotiate realm="foo", Basic realm="bar"  "#;
+            match ChallengeRefs::new(s).next() {
+                Some(Err(err)) => {
+                    assert_eq!(err.position().offset, 0);
+                }
+                x => panic!("{x:?}"),
+            }
+
+            let list = ChallengeRefs::with_options(s, ParseOptions::lenient())
+                .collect::<Result<alloc::vec::Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(list.len(), 1);
+            assert_eq!(list[0].as_basic().unwrap().realm, "bar");
+
+            match ChallengeRefs::new(r#"Basic realm="foo" "#).nth(1) {
+                Some(Err(ChallengesParseError::ChallengeParserError(_, pos))) => {
+                    assert_eq!(pos, ParseErrorPosition::new(17, " "));
+                }
+                x => panic!("{x:?}"),
+            }
+
+            match ChallengeRefs::new(r#"Basic realm="foo", Foo"#).nth(1) {
+                Some(Err(ChallengesParseError::SchemeUnknown(pos))) => {
+                    assert_eq!(pos, ParseErrorPosition::new(19, "Foo"));
+                }
+                x => panic!("{x:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_params_ref() {
+        let s = r#"Foo realm="a \"b\"", ,charset = UTF-8, Bar abc==, Baz"#;
+        let (scheme, params, end) = challenge(s, 0).unwrap();
+        assert_eq!(scheme, "Foo");
+        assert_eq!(params.as_str(), r#"realm="a \"b\"", ,charset = UTF-8"#);
+        assert_eq!(
+            params.iter().collect::<alloc::vec::Vec<_>>(),
+            [("realm", r#"a \"b\""#), ("charset", "UTF-8")]
+        );
+        assert_eq!(params.get("CHARSET"), Some("UTF-8"));
+
+        let i = skip_list_separators(s.as_bytes(), end);
+        let (scheme, params, end) = challenge(s, i).unwrap();
+        assert_eq!(scheme, "Bar");
+        assert_eq!(params.get("abc"), None);
+
+        let i = skip_list_separators(s.as_bytes(), end);
+        let (scheme, params, end) = challenge(s, i).unwrap();
+        assert_eq!(scheme, "Baz");
+        assert_eq!(params.iter().count(), 0);
+        assert_eq!(end, s.len());
+
+        //
+        assert_eq!(
+            challenge(r#"Foo realm="a"#, 0).unwrap_err(),
+            ("unterminated quoted-string", 10)
+        );
+        assert_eq!(
+            challenge("Foo a=b, c=@", 0).unwrap_err(),
+            ("expected token or quoted-string", 11)
+        );
+        assert_eq!(challenge("Foo a=b c", 0).unwrap_err(), ("expected ','", 8));
+        assert_eq!(challenge("Foo\"", 0).unwrap_err(), ("expected SP", 3));
+    }
+}
@@ -1,7 +1,6 @@
 use alloc::{
     format,
     string::{String, ToString as _},
-    vec,
     vec::Vec,
 };
 use core::{
@@ -9,16 +8,15 @@ use core::{
     str::{self, FromStr},
 };
 
-use http_auth::ChallengeParser;
-
 use crate::{
     challenge::Challenge,
-    schemes::{NAME_BASIC, NAME_BEARER, NAME_DIGEST},
+    challenge_ref::ChallengeRefs,
+    parse_options::{ParseErrorPosition, ParseOptions},
     COMMA, SP,
 };
 
 //
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Challenges(pub Vec<Challenge>);
 
 impl Deref for Challenges {
@@ -35,63 +33,39 @@ impl Challenges {
     }
 
     pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, ChallengesParseError> {
+        Self::from_bytes_with_options(bytes, &ParseOptions::default())
+    }
+
+    pub fn from_bytes_with_options(
+        bytes: impl AsRef<[u8]>,
+        options: &ParseOptions,
+    ) -> Result<Self, ChallengesParseError> {
         let bytes = bytes.as_ref();
-        let s = str::from_utf8(bytes).map_err(ChallengesParseError::ChallengesToStrFailed)?;
-        Self::internal_from_str(s)
+        let s = str::from_utf8(bytes).map_err(|err| {
+            ChallengesParseError::ChallengesToStrFailed(
+                err,
+                ParseErrorPosition::from_input(bytes, err.valid_up_to(), 1),
+            )
+        })?;
+        Self::internal_from_str(s, options)
     }
 
-    fn internal_from_str(s: impl AsRef<str>) -> Result<Self, ChallengesParseError> {
+    fn internal_from_str(
+        s: impl AsRef<str>,
+        options: &ParseOptions,
+    ) -> Result<Self, ChallengesParseError> {
         let s = s.as_ref();
 
         if s.is_empty() {
-            return Err(ChallengesParseError::Other("empty"));
+            return Err(ChallengesParseError::Other(
+                "empty",
+                ParseErrorPosition::new(0, ""),
+            ));
         }
 
-        let challenge_parser = ChallengeParser::new(s);
-
-        #[allow(unused_mut)]
-        let mut inner = vec![];
-        for c in challenge_parser {
-            let c = c.map_err(|err| ChallengesParseError::ChallengeParserError(err.to_string()))?;
-            match c.scheme {
-                x if x.eq_ignore_ascii_case(NAME_BASIC) => {
-                    #[cfg(feature = "scheme-basic")]
-                    {
-                        let c = crate::schemes::basic::Challenge::try_from(&c)
-                            .map(Challenge::Basic)
-                            .map_err(ChallengesParseError::Basic)?;
-
-                        inner.push(c)
-                    }
-                    #[cfg(not(feature = "scheme-basic"))]
-                    {
-                        return Err(ChallengesParseError::SchemeUnsupported(
-                            "Require feature scheme-basic",
-                        ));
-                    }
-                }
-                x if x.eq_ignore_ascii_case(NAME_BEARER) => {
-                    #[cfg(feature = "scheme-bearer")]
-                    {
-                        let c = crate::schemes::bearer::Challenge::try_from(&c)
-                            .map(Challenge::Bearer)
-                            .map_err(ChallengesParseError::Bearer)?;
-
-                        inner.push(c)
-                    }
-                    #[cfg(not(feature = "scheme-bearer"))]
-                    {
-                        return Err(ChallengesParseError::SchemeUnsupported(
-                            "Require feature scheme-bearer",
-                        ));
-                    }
-                }
-                x if x.eq_ignore_ascii_case(NAME_DIGEST) => {
-                    return Err(ChallengesParseError::SchemeUnsupported("Unimplemented"))
-                }
-                _ => return Err(ChallengesParseError::SchemeUnknown),
-            }
-        }
+        let inner = ChallengeRefs::with_options(s, *options)
+            .map(|c| c.map(Challenge::from))
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(Self::new(inner))
     }
 }
@@ -99,15 +73,60 @@ impl Challenges {
 //
 #[derive(Debug)]
 pub enum ChallengesParseError {
-    ChallengesToStrFailed(str::Utf8Error),
-    ChallengeParserError(String),
+    ChallengesToStrFailed(str::Utf8Error, ParseErrorPosition),
+    ChallengeParserError(String, ParseErrorPosition),
     #[cfg(feature = "scheme-basic")]
-    Basic(crate::schemes::basic::ChallengeParseError),
+    Basic(
+        crate::schemes::basic::ChallengeParseError,
+        ParseErrorPosition,
+    ),
     #[cfg(feature = "scheme-bearer")]
-    Bearer(crate::schemes::bearer::ChallengeParseError),
-    SchemeUnknown,
-    SchemeUnsupported(&'static str),
-    Other(&'static str),
+    Bearer(
+        crate::schemes::bearer::ChallengeParseError,
+        ParseErrorPosition,
+    ),
+    #[cfg(feature = "scheme-digest")]
+    Digest(
+        crate::schemes::digest::ChallengeParseError,
+        ParseErrorPosition,
+    ),
+    SchemeUnknown(ParseErrorPosition),
+    SchemeUnsupported(&'static str, ParseErrorPosition),
+    Other(&'static str, ParseErrorPosition),
+}
+
+impl ChallengesParseError {
+    pub fn position(&self) -> &ParseErrorPosition {
+        match self {
+            Self::ChallengesToStrFailed(_, pos)
+            | Self::ChallengeParserError(_, pos)
+            | Self::SchemeUnknown(pos)
+            | Self::SchemeUnsupported(_, pos)
+            | Self::Other(_, pos) => pos,
+            #[cfg(feature = "scheme-basic")]
+            Self::Basic(_, pos) => pos,
+            #[cfg(feature = "scheme-bearer")]
+            Self::Bearer(_, pos) => pos,
+            #[cfg(feature = "scheme-digest")]
+            Self::Digest(_, pos) => pos,
+        }
+    }
+
+    pub(crate) fn position_mut(&mut self) -> Option<&mut ParseErrorPosition> {
+        match self {
+            Self::ChallengesToStrFailed(_, pos)
+            | Self::ChallengeParserError(_, pos)
+            | Self::SchemeUnknown(pos)
+            | Self::SchemeUnsupported(_, pos)
+            | Self::Other(_, pos) => Some(pos),
+            #[cfg(feature = "scheme-basic")]
+            Self::Basic(_, pos) => Some(pos),
+            #[cfg(feature = "scheme-bearer")]
+            Self::Bearer(_, pos) => Some(pos),
+            #[cfg(feature = "scheme-digest")]
+            Self::Digest(_, pos) => Some(pos),
+        }
+    }
 }
 
 impl core::fmt::Display for ChallengesParseError {
@@ -130,7 +149,7 @@ impl FromStr for Challenges {
     type Err = ChallengesParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::internal_from_str(s)
+        Self::internal_from_str(s, &ParseOptions::default())
     }
 }
 
@@ -202,7 +221,7 @@ mod tests {
         #[cfg(not(feature = "scheme-basic"))]
         {
             match "Basic".parse::<Challenges>() {
-                Err(ChallengesParseError::SchemeUnsupported(_)) => {}
+                Err(ChallengesParseError::SchemeUnsupported(_, _)) => {}
                 x => panic!("{x:?}"),
             }
         }
@@ -251,7 +270,7 @@ mod tests {
         #[cfg(not(feature = "scheme-bearer"))]
         {
             match "Bearer".parse::<Challenges>() {
-                Err(ChallengesParseError::SchemeUnsupported(_)) => {}
+                Err(ChallengesParseError::SchemeUnsupported(_, _)) => {}
                 x => panic!("{x:?}"),
             }
         }
@@ -290,12 +309,26 @@ mod tests {
 
         //
         match Challenges::from_str("") {
-            Err(ChallengesParseError::Other(_)) => {}
+            Err(ChallengesParseError::Other(_, _)) => {}
             x => panic!("{x:?}"),
         }
 
         match Challenges::from_str("Foo") {
-            Err(ChallengesParseError::SchemeUnknown) => {}
+            Err(ChallengesParseError::SchemeUnknown(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "Foo"));
+            }
+            x => panic!("{x:?}"),
+        }
+
+        match Challenges::from_bytes_with_options(" Foo, Bar", &ParseOptions::lenient()) {
+            Ok(c) => assert!(c.is_empty()),
+            x => panic!("{x:?}"),
+        }
+
+        match Challenges::from_bytes(b"Basic realm=\"\xff\"") {
+            Err(ChallengesParseError::ChallengesToStrFailed(_, pos)) => {
+                assert_eq!(pos.offset, 13);
+            }
             x => panic!("{x:?}"),
         }
     }
@@ -0,0 +1,336 @@
+//! Client-side cache of the credentials that succeeded in a protection space.
+//!
+//! Ref https://www.rfc-editor.org/rfc/rfc7235.html#section-2.2 and
+//! https://www.rfc-editor.org/rfc/rfc7617.html#section-2.2, credentials are reused
+//! preemptively for paths at or below the directory of the request that succeeded.
+
+use std::sync::Mutex;
+
+use http_request_uri::RequestUri;
+
+use crate::{challenge::Challenge, credentials::Credentials, selection::realm_of};
+
+//
+/// The canonical root URI and the realm.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProtectionSpace {
+    /// Lowercase.
+    pub scheme: Box<str>,
+    /// Lowercase.
+    pub host: Box<str>,
+    pub port: u16,
+    pub realm: Box<str>,
+}
+
+impl ProtectionSpace {
+    /// `None` unless `request_uri` is an absolute URI with a `http`, `https`, `ws` or `wss` scheme.
+    pub fn new(request_uri: &RequestUri<'_>, realm: impl AsRef<str>) -> Option<Self> {
+        let (scheme, host, port, _) = origin(request_uri)?;
+        Some(Self {
+            scheme,
+            host,
+            port,
+            realm: realm.as_ref().into(),
+        })
+    }
+
+    fn same_origin(&self, scheme: &str, host: &str, port: u16) -> bool {
+        *self.scheme == *scheme && *self.host == *host && self.port == port
+    }
+}
+
+fn origin<'a>(request_uri: &RequestUri<'a>) -> Option<(Box<str>, Box<str>, u16, &'a str)> {
+    match request_uri {
+        RequestUri::AbsoluteUri {
+            scheme,
+            host,
+            port,
+            path,
+            ..
+        } => {
+            let scheme = scheme.to_ascii_lowercase();
+            let default_port = match scheme.as_str() {
+                "http" | "ws" => 80,
+                "https" | "wss" => 443,
+                _ => return None,
+            };
+            Some((
+                scheme.into(),
+                host.to_ascii_lowercase().into(),
+                port.unwrap_or(default_port),
+                path,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// `/a/b` is under `/a/`, the request to `/a/b` covers `/a/`.
+fn directory(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(i) => &path[..=i],
+        None => "/",
+    }
+}
+
+fn common_directory<'a>(a: &'a str, b: &str) -> &'a str {
+    let mut n = a.bytes().zip(b.bytes()).take_while(|(x, y)| x == y).count();
+    while !a.is_char_boundary(n) {
+        n -= 1;
+    }
+    directory(&a[..n])
+}
+
+//
+#[derive(Debug, Clone)]
+struct Entry {
+    space: ProtectionSpace,
+    path_prefix: Box<str>,
+    credentials: Credentials,
+}
+
+#[derive(Debug, Default)]
+pub struct CredentialCache {
+    entries: Mutex<Vec<Entry>>,
+}
+
+impl CredentialCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers `credentials` after a request to `request_uri` succeeded with them in `realm`.
+    ///
+    /// Storing the same credentials again widens the path prefix to the common directory.
+    /// Returns `false` when `request_uri` has no protection space.
+    pub fn store(
+        &self,
+        request_uri: &RequestUri<'_>,
+        realm: impl AsRef<str>,
+        credentials: Credentials,
+    ) -> bool {
+        let Some(space) = ProtectionSpace::new(request_uri, realm) else {
+            return false;
+        };
+        let Some((_, _, _, path)) = origin(request_uri) else {
+            return false;
+        };
+        let path_prefix = directory(path);
+
+        let mut entries = self.entries.lock().expect("lock");
+        match entries.iter_mut().find(|x| x.space == space) {
+            Some(entry) if entry.credentials == credentials => {
+                entry.path_prefix = common_directory(&entry.path_prefix, path_prefix).into();
+            }
+            Some(entry) => {
+                entry.path_prefix = path_prefix.into();
+                entry.credentials = credentials;
+            }
+            None => entries.push(Entry {
+                space,
+                path_prefix: path_prefix.into(),
+                credentials,
+            }),
+        }
+        true
+    }
+
+    /// The credentials to send before being challenged, the longest matching path prefix wins.
+    pub fn preemptive(&self, request_uri: &RequestUri<'_>) -> Option<Credentials> {
+        let entries = self.entries.lock().expect("lock");
+        Self::lookup(&entries, request_uri).map(|i| entries[i].credentials.clone())
+    }
+
+    /// Sets `Authorization` to [`CredentialCache::preemptive`], returns whether it was set.
+    #[cfg(feature = "http")]
+    pub fn apply(
+        &self,
+        header_map: &mut http::HeaderMap,
+        request_uri: &RequestUri<'_>,
+    ) -> Result<bool, http::header::InvalidHeaderValue> {
+        match self.preemptive(request_uri) {
+            Some(credentials) => {
+                crate::header_utils::set_authorization(header_map, &credentials)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Call on a 401 to `request_uri` with the challenges of the response.
+    ///
+    /// Removes the entry that was applied to the request, and the entries of the same origin
+    /// whose realm is challenged again, whatever the challenge parameters. That includes a Digest
+    /// challenge with `stale=true`, the response cached for it was computed with the previous nonce.
+    ///
+    /// Returns the number of removed entries.
+    pub fn on_unauthorized(&self, request_uri: &RequestUri<'_>, challenges: &[Challenge]) -> usize {
+        let Some((scheme, host, port, _)) = origin(request_uri) else {
+            return 0;
+        };
+
+        let mut entries = self.entries.lock().expect("lock");
+        let applied = Self::lookup(&entries, request_uri);
+        let len = entries.len();
+        let mut i = 0;
+        entries.retain(|entry| {
+            let remove = applied == Some(i)
+                || (entry.space.same_origin(&scheme, &host, port)
+                    && challenges
+                        .iter()
+                        .any(|c| realm_of(c) == Some(&entry.space.realm)));
+            i += 1;
+            !remove
+        });
+        len - entries.len()
+    }
+
+    pub fn remove(&self, space: &ProtectionSpace) {
+        self.entries
+            .lock()
+            .expect("lock")
+            .retain(|x| x.space != *space)
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().expect("lock").clear()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("lock").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lookup(entries: &[Entry], request_uri: &RequestUri<'_>) -> Option<usize> {
+        let (scheme, host, port, path) = origin(request_uri)?;
+        entries
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| x.space.same_origin(&scheme, &host, port))
+            .filter(|(_, x)| path.starts_with(x.path_prefix.as_ref()))
+            .max_by_key(|(_, x)| x.path_prefix.len())
+            .map(|(i, _)| i)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(dead_code)]
+    fn uri(scheme: &'static str, port: Option<u16>, path: &'static str) -> RequestUri<'static> {
+        RequestUri::AbsoluteUri {
+            scheme,
+            username: None,
+            password: None,
+            host: "Example.com",
+            port,
+            path,
+            query: None,
+            fragment: None,
+        }
+    }
+
+    #[test]
+    fn test_common_directory() {
+        assert_eq!(common_directory("/docs/a/x", "/docs/b/y"), "/docs/");
+        assert_eq!(common_directory("/docs/a/", "/docs/a/"), "/docs/a/");
+        // "é" and "è" share their first byte.
+        assert_eq!(common_directory("/caf\u{e9}/a", "/caf\u{e8}/b"), "/");
+        assert_eq!(
+            common_directory("/\u{65e5}/\u{672c}/a", "/\u{65e5}/\u{672c}/b"),
+            "/\u{65e5}/\u{672c}/"
+        );
+        assert_eq!(
+            common_directory("/\u{65e5}/\u{672c}", "/\u{65e5}/\u{672a}"),
+            "/\u{65e5}/"
+        );
+    }
+
+    #[cfg(all(feature = "scheme-basic", feature = "scheme-bearer"))]
+    #[test]
+    fn test_store_and_preemptive() {
+        use crate::schemes::basic;
+
+        let cache = CredentialCache::new();
+        let foo = Credentials::basic("foo", "bar");
+
+        assert!(!cache.store(
+            &RequestUri::Origin {
+                path: "/",
+                query: None,
+                fragment: None
+            },
+            "a",
+            foo.clone()
+        ));
+        assert!(cache.store(&uri("https", None, "/docs/a/index.html"), "a", foo.clone()));
+        assert!(cache.store(&uri("HTTPS", Some(443), "/docs/b/"), "a", foo.clone()));
+        assert!(cache.store(&uri("https", None, "/api/x"), "b", Credentials::bearer("t")));
+        assert_eq!(cache.len(), 2);
+
+        assert_eq!(
+            cache.preemptive(&uri("https", None, "/docs/c")),
+            Some(foo.clone())
+        );
+        assert_eq!(cache.preemptive(&uri("https", None, "/doc")), None);
+        assert_eq!(cache.preemptive(&uri("http", None, "/docs/c")), None);
+        assert_eq!(cache.preemptive(&uri("https", Some(8443), "/docs/c")), None);
+        assert_eq!(
+            cache.preemptive(&uri("https", None, "/api/y")),
+            Some(Credentials::bearer("t"))
+        );
+
+        // 401 to a request sent with the cached credentials.
+        let n = cache.on_unauthorized(
+            &uri("https", None, "/docs/a/"),
+            &[basic::Challenge::new("a").into()],
+        );
+        assert_eq!(n, 1);
+        assert_eq!(cache.preemptive(&uri("https", None, "/docs/a/")), None);
+        assert_eq!(cache.len(), 1);
+
+        #[cfg(feature = "http")]
+        {
+            let mut map = http::HeaderMap::new();
+            assert!(cache
+                .apply(&mut map, &uri("https", None, "/api/y"))
+                .unwrap());
+            assert_eq!(map[http::header::AUTHORIZATION], "Bearer t");
+            assert!(!cache
+                .apply(&mut map, &uri("https", None, "/other"))
+                .unwrap());
+        }
+    }
+
+    #[cfg(all(feature = "scheme-basic", feature = "scheme-digest"))]
+    #[test]
+    fn test_on_unauthorized_digest_stale() {
+        use crate::schemes::digest;
+
+        let cache = CredentialCache::new();
+        cache.store(
+            &uri("https", None, "/a/"),
+            "r",
+            Credentials::basic("foo", "bar"),
+        );
+        cache.store(
+            &uri("https", None, "/b/"),
+            "s",
+            Credentials::basic("foo", "baz"),
+        );
+
+        let mut c = digest::Challenge::new("r", "nonce");
+        c.stale = true;
+        assert_eq!(
+            cache.on_unauthorized(&uri("https", None, "/c"), &[c.into()]),
+            1
+        );
+        assert_eq!(cache.preemptive(&uri("https", None, "/a/x")), None);
+        assert!(cache.preemptive(&uri("https", None, "/b/x")).is_some());
+    }
+}
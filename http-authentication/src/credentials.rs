@@ -1,18 +1,22 @@
-use alloc::vec::Vec;
 use core::str::FromStr;
 
 use crate::{
-    schemes::{NAME_BASIC, NAME_BEARER, NAME_DIGEST},
+    parse_options::{is_whitespace, ParseErrorPosition, ParseOptions},
+    schemes::{NAME_BASIC, NAME_BEARER, NAME_CONCEALED, NAME_DIGEST, NAME_VAPID},
     SP,
 };
 
 //
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Credentials {
     #[cfg(feature = "scheme-basic")]
     Basic(crate::schemes::basic::Credentials),
     #[cfg(feature = "scheme-bearer")]
     Bearer(crate::schemes::bearer::Credentials),
+    #[cfg(feature = "scheme-vapid")]
+    Vapid(crate::schemes::vapid::Credentials),
+    #[cfg(feature = "scheme-concealed")]
+    Concealed(crate::schemes::concealed::Credentials),
 }
 
 impl Credentials {
@@ -46,20 +50,63 @@ impl Credentials {
         }
     }
 
+    //
+    #[cfg(feature = "scheme-vapid")]
+    pub fn vapid(token: impl AsRef<str>, public_key: impl AsRef<str>) -> Self {
+        Self::Vapid(crate::schemes::vapid::Credentials::new(token, public_key))
+    }
+
+    #[cfg(feature = "scheme-vapid")]
+    pub fn as_vapid(&self) -> Option<&crate::schemes::vapid::Credentials> {
+        match self {
+            Self::Vapid(c) => Some(c),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    //
+    #[cfg(feature = "scheme-concealed")]
+    pub fn as_concealed(&self) -> Option<&crate::schemes::concealed::Credentials> {
+        match self {
+            Self::Concealed(c) => Some(c),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    //
+    // Exhaustive without a fallback arm, there are no variants when no scheme is enabled.
+    pub fn scheme(&self) -> &'static str {
+        match *self {
+            #[cfg(feature = "scheme-basic")]
+            Self::Basic(_) => NAME_BASIC,
+            #[cfg(feature = "scheme-bearer")]
+            Self::Bearer(_) => NAME_BEARER,
+            #[cfg(feature = "scheme-vapid")]
+            Self::Vapid(_) => NAME_VAPID,
+            #[cfg(feature = "scheme-concealed")]
+            Self::Concealed(_) => NAME_CONCEALED,
+        }
+    }
+
     //
     pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, CredentialsParseError> {
+        Self::from_bytes_with_options(bytes, &ParseOptions::default())
+    }
+
+    pub fn from_bytes_with_options(
+        bytes: impl AsRef<[u8]>,
+        options: &ParseOptions,
+    ) -> Result<Self, CredentialsParseError> {
         let bytes = bytes.as_ref();
 
-        let scheme = bytes
-            .iter()
-            .take_while(|x| **x != SP as u8)
-            .cloned()
-            .collect::<Vec<_>>();
+        let (scheme_offset, scheme) = scheme(bytes, options);
         match scheme {
             x if x.eq_ignore_ascii_case(NAME_BASIC.as_bytes()) => {
                 #[cfg(feature = "scheme-basic")]
                 {
-                    crate::schemes::basic::Credentials::from_bytes(bytes)
+                    crate::schemes::basic::Credentials::from_bytes_with_options(bytes, options)
                         .map(Self::Basic)
                         .map_err(CredentialsParseError::Basic)
                 }
@@ -67,13 +114,14 @@ impl Credentials {
                 {
                     Err(CredentialsParseError::SchemeUnsupported(
                         "Require feature scheme-basic",
+                        ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
                     ))
                 }
             }
             x if x.eq_ignore_ascii_case(NAME_BEARER.as_bytes()) => {
                 #[cfg(feature = "scheme-bearer")]
                 {
-                    crate::schemes::bearer::Credentials::from_bytes(bytes)
+                    crate::schemes::bearer::Credentials::from_bytes_with_options(bytes, options)
                         .map(Self::Bearer)
                         .map_err(CredentialsParseError::Bearer)
                 }
@@ -81,17 +129,71 @@ impl Credentials {
                 {
                     Err(CredentialsParseError::SchemeUnsupported(
                         "Require feature scheme-bearer",
+                        ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
                     ))
                 }
             }
             x if x.eq_ignore_ascii_case(NAME_DIGEST.as_bytes()) => {
-                Err(CredentialsParseError::SchemeUnsupported("Unimplemented"))
+                Err(CredentialsParseError::SchemeUnsupported(
+                    "Unimplemented",
+                    ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
+                ))
+            }
+            x if x.eq_ignore_ascii_case(NAME_VAPID.as_bytes()) => {
+                #[cfg(feature = "scheme-vapid")]
+                {
+                    crate::schemes::vapid::Credentials::from_bytes_with_options(bytes, options)
+                        .map(Self::Vapid)
+                        .map_err(CredentialsParseError::Vapid)
+                }
+                #[cfg(not(feature = "scheme-vapid"))]
+                {
+                    Err(CredentialsParseError::SchemeUnsupported(
+                        "Require feature scheme-vapid",
+                        ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
+                    ))
+                }
+            }
+            x if x.eq_ignore_ascii_case(NAME_CONCEALED.as_bytes()) => {
+                #[cfg(feature = "scheme-concealed")]
+                {
+                    crate::schemes::concealed::Credentials::from_bytes_with_options(bytes, options)
+                        .map(Self::Concealed)
+                        .map_err(CredentialsParseError::Concealed)
+                }
+                #[cfg(not(feature = "scheme-concealed"))]
+                {
+                    Err(CredentialsParseError::SchemeUnsupported(
+                        "Require feature scheme-concealed",
+                        ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
+                    ))
+                }
             }
-            _ => Err(CredentialsParseError::SchemeUnknown),
+            _ => Err(CredentialsParseError::SchemeUnknown(
+                ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
+            )),
         }
     }
 }
 
+//
+/// Returns the offset of the scheme and the scheme.
+pub(crate) fn scheme<'a>(bytes: &'a [u8], options: &ParseOptions) -> (usize, &'a [u8]) {
+    let offset = if options.allow_surrounding_whitespace {
+        bytes
+            .iter()
+            .position(|x| !is_whitespace(*x))
+            .unwrap_or(bytes.len())
+    } else {
+        0
+    };
+    let n = bytes[offset..]
+        .iter()
+        .position(|x| *x == SP as u8)
+        .unwrap_or(bytes.len() - offset);
+    (offset, &bytes[offset..offset + n])
+}
+
 //
 #[derive(Debug)]
 pub enum CredentialsParseError {
@@ -99,8 +201,44 @@ pub enum CredentialsParseError {
     Basic(crate::schemes::basic::CredentialsParseError),
     #[cfg(feature = "scheme-bearer")]
     Bearer(crate::schemes::bearer::CredentialsParseError),
-    SchemeUnknown,
-    SchemeUnsupported(&'static str),
+    #[cfg(feature = "scheme-vapid")]
+    Vapid(crate::schemes::vapid::CredentialsParseError),
+    #[cfg(feature = "scheme-concealed")]
+    Concealed(crate::schemes::concealed::CredentialsParseError),
+    SchemeUnknown(ParseErrorPosition),
+    SchemeUnsupported(&'static str, ParseErrorPosition),
+}
+
+impl CredentialsParseError {
+    pub fn position(&self) -> &ParseErrorPosition {
+        match self {
+            #[cfg(feature = "scheme-basic")]
+            Self::Basic(err) => err.position(),
+            #[cfg(feature = "scheme-bearer")]
+            Self::Bearer(err) => err.position(),
+            #[cfg(feature = "scheme-vapid")]
+            Self::Vapid(err) => err.position(),
+            #[cfg(feature = "scheme-concealed")]
+            Self::Concealed(err) => err.position(),
+            Self::SchemeUnknown(pos) | Self::SchemeUnsupported(_, pos) => pos,
+        }
+    }
+
+    /// Delegates to the scheme error, so `Basic` failures are not all reported as one label.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "scheme-basic")]
+            Self::Basic(err) => err.kind(),
+            #[cfg(feature = "scheme-bearer")]
+            Self::Bearer(err) => err.kind(),
+            #[cfg(feature = "scheme-vapid")]
+            Self::Vapid(err) => err.kind(),
+            #[cfg(feature = "scheme-concealed")]
+            Self::Concealed(err) => err.kind(),
+            Self::SchemeUnknown(_) => "SchemeUnknown",
+            Self::SchemeUnsupported(_, _) => "SchemeUnsupported",
+        }
+    }
 }
 
 impl core::fmt::Display for CredentialsParseError {
@@ -121,6 +259,10 @@ impl core::fmt::Display for Credentials {
             Self::Basic(c) => c.fmt(f),
             #[cfg(feature = "scheme-bearer")]
             Self::Bearer(c) => c.fmt(f),
+            #[cfg(feature = "scheme-vapid")]
+            Self::Vapid(c) => c.fmt(f),
+            #[cfg(feature = "scheme-concealed")]
+            Self::Concealed(c) => c.fmt(f),
             #[allow(unreachable_patterns)]
             _ => unimplemented!(),
         }
@@ -165,7 +307,7 @@ mod tests {
         #[cfg(not(feature = "scheme-basic"))]
         {
             match "Basic bar".parse::<Credentials>() {
-                Err(CredentialsParseError::SchemeUnsupported(_)) => {}
+                Err(CredentialsParseError::SchemeUnsupported(_, _)) => {}
                 x => panic!("{x:?}"),
             }
         }
@@ -187,20 +329,105 @@ mod tests {
         #[cfg(not(feature = "scheme-bearer"))]
         {
             match "Bearer bar".parse::<Credentials>() {
-                Err(CredentialsParseError::SchemeUnsupported(_)) => {}
+                Err(CredentialsParseError::SchemeUnsupported(_, _)) => {}
+                x => panic!("{x:?}"),
+            }
+        }
+
+        //
+        #[cfg(feature = "scheme-vapid")]
+        {
+            use crate::schemes::vapid::DEMO_CREDENTIALS_STR;
+
+            match DEMO_CREDENTIALS_STR.parse::<Credentials>() {
+                Ok(c) => {
+                    let c = c.as_vapid().unwrap();
+                    assert!(c.public_key.starts_with("BA1Hxzyi1RUM"));
+                    assert_eq!(c.to_string(), DEMO_CREDENTIALS_STR);
+                }
+                x => panic!("{x:?}"),
+            }
+        }
+        #[cfg(not(feature = "scheme-vapid"))]
+        {
+            match "vapid t=a, k=b".parse::<Credentials>() {
+                Err(CredentialsParseError::SchemeUnsupported(_, _)) => {}
+                x => panic!("{x:?}"),
+            }
+        }
+
+        //
+        #[cfg(feature = "scheme-concealed")]
+        {
+            use crate::schemes::concealed::DEMO_CREDENTIALS_STR;
+
+            match DEMO_CREDENTIALS_STR.parse::<Credentials>() {
+                Ok(c) => {
+                    let c = c.as_concealed().unwrap();
+                    assert_eq!(c.key_id_bytes(), b"basement");
+                    assert_eq!(c.to_string(), DEMO_CREDENTIALS_STR);
+                }
+                x => panic!("{x:?}"),
+            }
+        }
+        #[cfg(not(feature = "scheme-concealed"))]
+        {
+            match "Concealed k=aw".parse::<Credentials>() {
+                Err(CredentialsParseError::SchemeUnsupported(_, _)) => {}
                 x => panic!("{x:?}"),
             }
         }
 
         //
         match Credentials::from_str("") {
-            Err(CredentialsParseError::SchemeUnknown) => {}
+            Err(CredentialsParseError::SchemeUnknown(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, ""));
+            }
             x => panic!("{x:?}"),
         }
 
         match Credentials::from_str("Foo bar") {
-            Err(CredentialsParseError::SchemeUnknown) => {}
+            Err(CredentialsParseError::SchemeUnknown(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "Foo"));
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_with_options() {
+        match Credentials::from_str(" Foo bar") {
+            Err(CredentialsParseError::SchemeUnknown(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, ""));
+            }
+            x => panic!("{x:?}"),
+        }
+
+        match Credentials::from_bytes_with_options(" Foo bar", &ParseOptions::lenient()) {
+            Err(CredentialsParseError::SchemeUnknown(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(1, "Foo"));
+            }
             x => panic!("{x:?}"),
         }
+
+        #[cfg(feature = "scheme-bearer")]
+        {
+            use crate::schemes::bearer::DEMO_CREDENTIALS_TOKEN_STR;
+
+            let s = " Bearer  mF_9.B5f-4.1JqM ";
+            match Credentials::from_str(s) {
+                Err(err) => {
+                    assert_eq!(err.position(), &ParseErrorPosition::new(0, ""));
+                }
+                x => panic!("{x:?}"),
+            }
+            match Credentials::from_bytes_with_options(s, &ParseOptions::lenient()) {
+                Ok(c) => {
+                    let c = c.as_bearer().unwrap();
+                    assert_eq!(c.token, DEMO_CREDENTIALS_TOKEN_STR.into());
+                }
+                x => panic!("{x:?}"),
+            }
+        }
     }
 }
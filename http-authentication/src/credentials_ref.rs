@@ -0,0 +1,257 @@
+use crate::{
+    credentials::{scheme, Credentials, CredentialsParseError},
+    parse_options::{ParseErrorPosition, ParseOptions},
+    schemes::{NAME_BASIC, NAME_BEARER, NAME_CONCEALED, NAME_DIGEST, NAME_VAPID},
+};
+
+//
+/// Borrowed [`Credentials`], parsed without allocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialsRef<'a> {
+    #[cfg(feature = "scheme-basic")]
+    Basic(crate::schemes::basic::CredentialsRef<'a>),
+    #[cfg(feature = "scheme-bearer")]
+    Bearer(crate::schemes::bearer::CredentialsRef<'a>),
+    #[cfg(feature = "scheme-vapid")]
+    Vapid(crate::schemes::vapid::CredentialsRef<'a>),
+    #[cfg(feature = "scheme-concealed")]
+    Concealed(crate::schemes::concealed::CredentialsRef<'a>),
+    #[cfg(not(any(
+        feature = "scheme-basic",
+        feature = "scheme-bearer",
+        feature = "scheme-vapid",
+        feature = "scheme-concealed"
+    )))]
+    #[doc(hidden)]
+    _Phantom(core::convert::Infallible, core::marker::PhantomData<&'a ()>),
+}
+
+impl<'a> CredentialsRef<'a> {
+    //
+    #[cfg(feature = "scheme-basic")]
+    pub fn as_basic(&self) -> Option<&crate::schemes::basic::CredentialsRef<'a>> {
+        match self {
+            Self::Basic(c) => Some(c),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    //
+    #[cfg(feature = "scheme-bearer")]
+    pub fn as_bearer(&self) -> Option<&crate::schemes::bearer::CredentialsRef<'a>> {
+        match self {
+            Self::Bearer(c) => Some(c),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    //
+    #[cfg(feature = "scheme-vapid")]
+    pub fn as_vapid(&self) -> Option<&crate::schemes::vapid::CredentialsRef<'a>> {
+        match self {
+            Self::Vapid(c) => Some(c),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    //
+    #[cfg(feature = "scheme-concealed")]
+    pub fn as_concealed(&self) -> Option<&crate::schemes::concealed::CredentialsRef<'a>> {
+        match self {
+            Self::Concealed(c) => Some(c),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    //
+    /// `buf` receives the decoded token68 of Basic, see [`crate::schemes::basic::CredentialsRef::from_bytes`].
+    pub fn from_bytes(bytes: &'a [u8], buf: &'a mut [u8]) -> Result<Self, CredentialsParseError> {
+        Self::from_bytes_with_options(bytes, buf, &ParseOptions::default())
+    }
+
+    #[allow(unused_variables)]
+    pub fn from_bytes_with_options(
+        bytes: &'a [u8],
+        buf: &'a mut [u8],
+        options: &ParseOptions,
+    ) -> Result<Self, CredentialsParseError> {
+        let (scheme_offset, scheme) = scheme(bytes, options);
+        match scheme {
+            x if x.eq_ignore_ascii_case(NAME_BASIC.as_bytes()) => {
+                #[cfg(feature = "scheme-basic")]
+                {
+                    crate::schemes::basic::CredentialsRef::from_bytes_with_options(
+                        bytes, buf, options,
+                    )
+                    .map(Self::Basic)
+                    .map_err(CredentialsParseError::Basic)
+                }
+                #[cfg(not(feature = "scheme-basic"))]
+                {
+                    Err(CredentialsParseError::SchemeUnsupported(
+                        "Require feature scheme-basic",
+                        ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
+                    ))
+                }
+            }
+            x if x.eq_ignore_ascii_case(NAME_BEARER.as_bytes()) => {
+                #[cfg(feature = "scheme-bearer")]
+                {
+                    crate::schemes::bearer::CredentialsRef::from_bytes_with_options(bytes, options)
+                        .map(Self::Bearer)
+                        .map_err(CredentialsParseError::Bearer)
+                }
+                #[cfg(not(feature = "scheme-bearer"))]
+                {
+                    Err(CredentialsParseError::SchemeUnsupported(
+                        "Require feature scheme-bearer",
+                        ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
+                    ))
+                }
+            }
+            x if x.eq_ignore_ascii_case(NAME_DIGEST.as_bytes()) => {
+                Err(CredentialsParseError::SchemeUnsupported(
+                    "Unimplemented",
+                    ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
+                ))
+            }
+            x if x.eq_ignore_ascii_case(NAME_VAPID.as_bytes()) => {
+                #[cfg(feature = "scheme-vapid")]
+                {
+                    crate::schemes::vapid::CredentialsRef::from_bytes_with_options(bytes, options)
+                        .map(Self::Vapid)
+                        .map_err(CredentialsParseError::Vapid)
+                }
+                #[cfg(not(feature = "scheme-vapid"))]
+                {
+                    Err(CredentialsParseError::SchemeUnsupported(
+                        "Require feature scheme-vapid",
+                        ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
+                    ))
+                }
+            }
+            x if x.eq_ignore_ascii_case(NAME_CONCEALED.as_bytes()) => {
+                #[cfg(feature = "scheme-concealed")]
+                {
+                    crate::schemes::concealed::CredentialsRef::from_bytes_with_options(
+                        bytes, options,
+                    )
+                    .map(Self::Concealed)
+                    .map_err(CredentialsParseError::Concealed)
+                }
+                #[cfg(not(feature = "scheme-concealed"))]
+                {
+                    Err(CredentialsParseError::SchemeUnsupported(
+                        "Require feature scheme-concealed",
+                        ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
+                    ))
+                }
+            }
+            _ => Err(CredentialsParseError::SchemeUnknown(
+                ParseErrorPosition::from_input(bytes, scheme_offset, scheme.len()),
+            )),
+        }
+    }
+
+    pub fn into_owned(self) -> Credentials {
+        match self {
+            #[cfg(feature = "scheme-basic")]
+            Self::Basic(c) => Credentials::Basic(c.into_owned()),
+            #[cfg(feature = "scheme-bearer")]
+            Self::Bearer(c) => Credentials::Bearer(c.into_owned()),
+            #[cfg(feature = "scheme-vapid")]
+            Self::Vapid(c) => Credentials::Vapid(c.into_owned()),
+            #[cfg(feature = "scheme-concealed")]
+            Self::Concealed(c) => Credentials::Concealed(c.into_owned()),
+            #[cfg(not(any(
+                feature = "scheme-basic",
+                feature = "scheme-bearer",
+                feature = "scheme-vapid",
+                feature = "scheme-concealed"
+            )))]
+            Self::_Phantom(x, _) => match x {},
+        }
+    }
+}
+
+impl From<CredentialsRef<'_>> for Credentials {
+    fn from(c: CredentialsRef<'_>) -> Self {
+        c.into_owned()
+    }
+}
+
+impl<'a> From<&'a Credentials> for CredentialsRef<'a> {
+    fn from(c: &'a Credentials) -> Self {
+        match *c {
+            #[cfg(feature = "scheme-basic")]
+            Credentials::Basic(ref c) => Self::Basic(c.into()),
+            #[cfg(feature = "scheme-bearer")]
+            Credentials::Bearer(ref c) => Self::Bearer(c.into()),
+            #[cfg(feature = "scheme-vapid")]
+            Credentials::Vapid(ref c) => Self::Vapid(c.into()),
+            #[cfg(feature = "scheme-concealed")]
+            Credentials::Concealed(ref c) => Self::Concealed(c.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let mut buf = [0; 64];
+
+        //
+        #[cfg(feature = "scheme-basic")]
+        {
+            use crate::schemes::basic::{
+                DEMO_CREDENTIALS_PASSWORD_STR, DEMO_CREDENTIALS_STR, DEMO_CREDENTIALS_USER_ID_STR,
+            };
+
+            match CredentialsRef::from_bytes(DEMO_CREDENTIALS_STR.as_bytes(), &mut buf) {
+                Ok(c) => {
+                    let c = c.as_basic().unwrap();
+                    assert_eq!(c.user_id, DEMO_CREDENTIALS_USER_ID_STR);
+                    assert_eq!(c.password, DEMO_CREDENTIALS_PASSWORD_STR);
+                }
+                x => panic!("{x:?}"),
+            }
+        }
+
+        //
+        #[cfg(feature = "scheme-bearer")]
+        {
+            use crate::schemes::bearer::{DEMO_CREDENTIALS_STR, DEMO_CREDENTIALS_TOKEN_STR};
+
+            match CredentialsRef::from_bytes(DEMO_CREDENTIALS_STR.as_bytes(), &mut buf) {
+                Ok(c) => {
+                    let c = c.as_bearer().unwrap();
+                    assert_eq!(c.token, DEMO_CREDENTIALS_TOKEN_STR);
+                    assert_eq!(
+                        c.into_owned().token,
+                        Credentials::from_bytes(DEMO_CREDENTIALS_STR)
+                            .unwrap()
+                            .as_bearer()
+                            .unwrap()
+                            .token
+                    );
+                }
+                x => panic!("{x:?}"),
+            }
+        }
+
+        //
+        match CredentialsRef::from_bytes(b"Foo bar", &mut buf) {
+            Err(CredentialsParseError::SchemeUnknown(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "Foo"));
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+}
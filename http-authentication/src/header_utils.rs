@@ -1,179 +1,453 @@
-use alloc::vec::Vec;
+// Shared by the http 0.2 and the http 1.x functions, `$http` is the crate of either.
+macro_rules! header_utils {
+    ($http:ident) => {
+        use alloc::vec::Vec;
+
+        use $http::{
+            header::{AUTHORIZATION, PROXY_AUTHENTICATE, PROXY_AUTHORIZATION, WWW_AUTHENTICATE},
+            HeaderMap,
+        };
 
-use http::{
-    header::{AUTHORIZATION, PROXY_AUTHENTICATE, PROXY_AUTHORIZATION, WWW_AUTHENTICATE},
-    HeaderMap,
-};
+        use crate::{
+            challenge::Challenge,
+            challenges::{Challenges, ChallengesParseError, ChallengesWithSlice},
+            credentials::{Credentials, CredentialsParseError},
+        };
 
-use crate::{
-    challenge::Challenge,
-    challenges::{Challenges, ChallengesParseError, ChallengesWithSlice},
-    credentials::{Credentials, CredentialsParseError},
-};
+        //
+        //
+        //
+        pub fn get_authorization(
+            header_map: &HeaderMap,
+        ) -> Option<Result<Credentials, CredentialsParseError>> {
+            header_map
+                .get(AUTHORIZATION)
+                .map(|x| Credentials::from_bytes(x.as_bytes()))
+        }
 
-//
-//
-//
-pub fn get_authorization(
-    header_map: &HeaderMap,
-) -> Option<Result<Credentials, CredentialsParseError>> {
-    header_map
-        .get(AUTHORIZATION)
-        .map(|x| Credentials::from_bytes(x.as_bytes()))
-}
+        pub fn get_proxy_authorization(
+            header_map: &HeaderMap,
+        ) -> Option<Result<Credentials, CredentialsParseError>> {
+            header_map
+                .get(PROXY_AUTHORIZATION)
+                .map(|x| Credentials::from_bytes(x.as_bytes()))
+        }
 
-pub fn get_proxy_authorization(
-    header_map: &HeaderMap,
-) -> Option<Result<Credentials, CredentialsParseError>> {
-    header_map
-        .get(PROXY_AUTHORIZATION)
-        .map(|x| Credentials::from_bytes(x.as_bytes()))
-}
+        //
+        pub fn set_authorization(
+            header_map: &mut HeaderMap,
+            credentials: &Credentials,
+        ) -> Result<(), $http::header::InvalidHeaderValue> {
+            use alloc::string::ToString as _;
+
+            header_map.remove(AUTHORIZATION);
+            header_map.append(
+                AUTHORIZATION,
+                $http::HeaderValue::from_str(credentials.to_string().as_str())?,
+            );
+            Ok(())
+        }
 
-//
-pub fn set_authorization(
-    header_map: &mut HeaderMap,
-    credentials: &Credentials,
-) -> Result<(), http::header::InvalidHeaderValue> {
-    use alloc::string::ToString as _;
-
-    header_map.remove(AUTHORIZATION);
-    header_map.append(
-        AUTHORIZATION,
-        http::HeaderValue::from_str(credentials.to_string().as_str())?,
-    );
-    Ok(())
-}
+        pub fn set_proxy_authorization(
+            header_map: &mut HeaderMap,
+            credentials: &Credentials,
+        ) -> Result<(), $http::header::InvalidHeaderValue> {
+            use alloc::string::ToString as _;
+
+            header_map.remove(PROXY_AUTHORIZATION);
+            header_map.append(
+                PROXY_AUTHORIZATION,
+                $http::HeaderValue::from_str(credentials.to_string().as_str())?,
+            );
+            Ok(())
+        }
 
-pub fn set_proxy_authorization(
-    header_map: &mut HeaderMap,
-    credentials: &Credentials,
-) -> Result<(), http::header::InvalidHeaderValue> {
-    use alloc::string::ToString as _;
-
-    header_map.remove(PROXY_AUTHORIZATION);
-    header_map.append(
-        PROXY_AUTHORIZATION,
-        http::HeaderValue::from_str(credentials.to_string().as_str())?,
-    );
-    Ok(())
-}
+        #[cfg(feature = "scheme-basic")]
+        pub fn set_authorization_with_basic(
+            header_map: &mut HeaderMap,
+            user_id: impl AsRef<str>,
+            password: impl AsRef<str>,
+        ) -> Result<(), $http::header::InvalidHeaderValue> {
+            set_authorization(header_map, &Credentials::basic(user_id, password))
+        }
 
-#[cfg(feature = "scheme-basic")]
-pub fn set_authorization_with_basic(
-    header_map: &mut HeaderMap,
-    user_id: impl AsRef<str>,
-    password: impl AsRef<str>,
-) -> Result<(), http::header::InvalidHeaderValue> {
-    set_authorization(header_map, &Credentials::basic(user_id, password))
-}
+        #[cfg(feature = "scheme-basic")]
+        pub fn set_proxy_authorization_with_basic(
+            header_map: &mut HeaderMap,
+            user_id: impl AsRef<str>,
+            password: impl AsRef<str>,
+        ) -> Result<(), $http::header::InvalidHeaderValue> {
+            set_proxy_authorization(header_map, &Credentials::basic(user_id, password))
+        }
 
-#[cfg(feature = "scheme-basic")]
-pub fn set_proxy_authorization_with_basic(
-    header_map: &mut HeaderMap,
-    user_id: impl AsRef<str>,
-    password: impl AsRef<str>,
-) -> Result<(), http::header::InvalidHeaderValue> {
-    set_proxy_authorization(header_map, &Credentials::basic(user_id, password))
-}
+        //
+        #[cfg(feature = "scheme-bearer")]
+        pub fn set_authorization_with_bearer(
+            header_map: &mut HeaderMap,
+            token: impl AsRef<str>,
+        ) -> Result<(), $http::header::InvalidHeaderValue> {
+            set_authorization(header_map, &Credentials::bearer(token))
+        }
 
-//
-#[cfg(feature = "scheme-bearer")]
-pub fn set_authorization_with_bearer(
-    header_map: &mut HeaderMap,
-    token: impl AsRef<str>,
-) -> Result<(), http::header::InvalidHeaderValue> {
-    set_authorization(header_map, &Credentials::bearer(token))
-}
+        #[cfg(feature = "scheme-bearer")]
+        pub fn set_proxy_authorization_with_bearer(
+            header_map: &mut HeaderMap,
+            token: impl AsRef<str>,
+        ) -> Result<(), $http::header::InvalidHeaderValue> {
+            set_proxy_authorization(header_map, &Credentials::bearer(token))
+        }
 
-#[cfg(feature = "scheme-bearer")]
-pub fn set_proxy_authorization_with_bearer(
-    header_map: &mut HeaderMap,
-    token: impl AsRef<str>,
-) -> Result<(), http::header::InvalidHeaderValue> {
-    set_proxy_authorization(header_map, &Credentials::bearer(token))
-}
+        //
+        /// Sets the percent-decoded userinfo of `request_uri` as Basic credentials,
+        /// returns the URI without the userinfo.
+        #[cfg(all(feature = "scheme-basic", feature = "http-request-uri"))]
+        pub fn set_authorization_from_userinfo(
+            header_map: &mut HeaderMap,
+            request_uri: &http_request_uri::RequestUri<'_>,
+        ) -> Result<$http::Uri, crate::header_utils::UserinfoError> {
+            use crate::header_utils::{userinfo, UserinfoError};
+
+            let (credentials, uri) = userinfo(request_uri)?;
+            if let Some((user_id, password)) = credentials {
+                set_authorization_with_basic(header_map, user_id, password)
+                    .map_err(|_| UserinfoError::HeaderValueInvalid)?;
+            }
+            uri.parse().map_err(|_| UserinfoError::UriInvalid)
+        }
 
-//
-//
-//
-pub fn get_www_authenticate(header_map: &HeaderMap) -> Result<Challenges, ChallengesParseError> {
-    let list = header_map
-        .get_all(WWW_AUTHENTICATE)
-        .into_iter()
-        .map(|x| Challenges::from_bytes(x.as_bytes()))
-        .collect::<Result<Vec<_>, _>>()?;
-    let list = list.into_iter().flat_map(|x| x.0).collect::<Vec<_>>();
-    Ok(Challenges::new(list))
-}
+        /// Same as `set_authorization_from_userinfo`, for the URI of a proxy.
+        #[cfg(all(feature = "scheme-basic", feature = "http-request-uri"))]
+        pub fn set_proxy_authorization_from_userinfo(
+            header_map: &mut HeaderMap,
+            request_uri: &http_request_uri::RequestUri<'_>,
+        ) -> Result<$http::Uri, crate::header_utils::UserinfoError> {
+            use crate::header_utils::{userinfo, UserinfoError};
+
+            let (credentials, uri) = userinfo(request_uri)?;
+            if let Some((user_id, password)) = credentials {
+                set_proxy_authorization_with_basic(header_map, user_id, password)
+                    .map_err(|_| UserinfoError::HeaderValueInvalid)?;
+            }
+            uri.parse().map_err(|_| UserinfoError::UriInvalid)
+        }
 
-pub fn get_proxy_authenticate(header_map: &HeaderMap) -> Result<Challenges, ChallengesParseError> {
-    let list = header_map
-        .get_all(PROXY_AUTHENTICATE)
-        .into_iter()
-        .map(|x| Challenges::from_bytes(x.as_bytes()))
-        .collect::<Result<Vec<_>, _>>()?;
-    let list = list.into_iter().flat_map(|x| x.0).collect::<Vec<_>>();
-    Ok(Challenges::new(list))
+        //
+        //
+        //
+        pub fn get_www_authenticate(
+            header_map: &HeaderMap,
+        ) -> Result<Challenges, ChallengesParseError> {
+            let list = header_map
+                .get_all(WWW_AUTHENTICATE)
+                .into_iter()
+                .map(|x| Challenges::from_bytes(x.as_bytes()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let list = list.into_iter().flat_map(|x| x.0).collect::<Vec<_>>();
+            Ok(Challenges::new(list))
+        }
+
+        pub fn get_proxy_authenticate(
+            header_map: &HeaderMap,
+        ) -> Result<Challenges, ChallengesParseError> {
+            let list = header_map
+                .get_all(PROXY_AUTHENTICATE)
+                .into_iter()
+                .map(|x| Challenges::from_bytes(x.as_bytes()))
+                .collect::<Result<Vec<_>, _>>()?;
+            let list = list.into_iter().flat_map(|x| x.0).collect::<Vec<_>>();
+            Ok(Challenges::new(list))
+        }
+
+        //
+        pub fn append_www_authenticate(
+            header_map: &mut HeaderMap,
+            challenge: &Challenge,
+        ) -> Result<(), $http::header::InvalidHeaderValue> {
+            use alloc::string::ToString as _;
+
+            header_map.append(
+                WWW_AUTHENTICATE,
+                $http::HeaderValue::from_str(challenge.to_string().as_str())?,
+            );
+            Ok(())
+        }
+
+        pub fn append_www_authenticate_with_multiple(
+            header_map: &mut HeaderMap,
+            challenges: &[Challenge],
+        ) -> Result<(), $http::header::InvalidHeaderValue> {
+            use alloc::string::ToString as _;
+
+            header_map.append(
+                WWW_AUTHENTICATE,
+                $http::HeaderValue::from_str(
+                    ChallengesWithSlice::new(challenges).to_string().as_str(),
+                )?,
+            );
+            Ok(())
+        }
+
+        pub fn append_proxy_authenticate(
+            header_map: &mut HeaderMap,
+            challenge: &Challenge,
+        ) -> Result<(), $http::header::InvalidHeaderValue> {
+            use alloc::string::ToString as _;
+
+            header_map.append(
+                PROXY_AUTHENTICATE,
+                $http::HeaderValue::from_str(challenge.to_string().as_str())?,
+            );
+            Ok(())
+        }
+
+        pub fn append_proxy_authenticate_with_multiple(
+            header_map: &mut HeaderMap,
+            challenges: &[Challenge],
+        ) -> Result<(), $http::header::InvalidHeaderValue> {
+            use alloc::string::ToString as _;
+
+            header_map.append(
+                PROXY_AUTHENTICATE,
+                $http::HeaderValue::from_str(
+                    ChallengesWithSlice::new(challenges).to_string().as_str(),
+                )?,
+            );
+            Ok(())
+        }
+    };
 }
 
+#[cfg(feature = "http")]
+header_utils!(http);
+
 //
-pub fn append_www_authenticate(
-    header_map: &mut HeaderMap,
-    challenge: &Challenge,
-) -> Result<(), http::header::InvalidHeaderValue> {
-    use alloc::string::ToString as _;
-
-    header_map.append(
-        WWW_AUTHENTICATE,
-        http::HeaderValue::from_str(challenge.to_string().as_str())?,
-    );
-    Ok(())
+#[cfg(all(feature = "scheme-basic", feature = "http-request-uri"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserinfoError {
+    UriNotAbsolute,
+    UserIdDecodeFailed(&'static str),
+    /// A decoded `:` in the user-id, Basic credentials could not be split back.
+    UserIdContainsColon,
+    PasswordDecodeFailed(&'static str),
+    HeaderValueInvalid,
+    UriInvalid,
 }
 
-pub fn append_www_authenticate_with_multiple(
-    header_map: &mut HeaderMap,
-    challenges: &[Challenge],
-) -> Result<(), http::header::InvalidHeaderValue> {
-    use alloc::string::ToString as _;
-
-    header_map.append(
-        WWW_AUTHENTICATE,
-        http::HeaderValue::from_str(ChallengesWithSlice::new(challenges).to_string().as_str())?,
-    );
-    Ok(())
+#[cfg(all(feature = "scheme-basic", feature = "http-request-uri"))]
+impl core::fmt::Display for UserinfoError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
 }
 
-pub fn append_proxy_authenticate(
-    header_map: &mut HeaderMap,
-    challenge: &Challenge,
-) -> Result<(), http::header::InvalidHeaderValue> {
-    use alloc::string::ToString as _;
-
-    header_map.append(
-        PROXY_AUTHENTICATE,
-        http::HeaderValue::from_str(challenge.to_string().as_str())?,
-    );
-    Ok(())
+#[cfg(all(
+    feature = "scheme-basic",
+    feature = "std",
+    feature = "http-request-uri"
+))]
+impl std::error::Error for UserinfoError {}
+
+/// Returns the decoded user-id and password if any, and the URI without the userinfo.
+#[cfg(all(feature = "scheme-basic", feature = "http-request-uri"))]
+#[allow(clippy::type_complexity)]
+fn userinfo(
+    request_uri: &http_request_uri::RequestUri<'_>,
+) -> Result<
+    (
+        Option<(alloc::string::String, alloc::string::String)>,
+        alloc::string::String,
+    ),
+    UserinfoError,
+> {
+    use alloc::{format, string::ToString as _};
+
+    use crate::percent_decode::percent_decode;
+
+    let http_request_uri::RequestUri::AbsoluteUri {
+        scheme,
+        username,
+        password,
+        host,
+        port,
+        path,
+        query,
+        fragment: _,
+    } = request_uri
+    else {
+        return Err(UserinfoError::UriNotAbsolute);
+    };
+
+    let credentials = if username.is_some() || password.is_some() {
+        let user_id = percent_decode(username.unwrap_or_default())
+            .map_err(UserinfoError::UserIdDecodeFailed)?;
+        if user_id.contains(':') {
+            return Err(UserinfoError::UserIdContainsColon);
+        }
+        let password = percent_decode(password.unwrap_or_default())
+            .map_err(UserinfoError::PasswordDecodeFailed)?;
+        Some((user_id.to_string(), password.to_string()))
+    } else {
+        None
+    };
+
+    let mut uri = format!("{scheme}://{host}");
+    if let Some(port) = port {
+        uri.push_str(&format!(":{port}"));
+    }
+    uri.push_str(path);
+    if let Some(query) = query {
+        uri.push('?');
+        uri.push_str(query);
+    }
+
+    Ok((credentials, uri))
 }
 
-pub fn append_proxy_authenticate_with_multiple(
-    header_map: &mut HeaderMap,
-    challenges: &[Challenge],
-) -> Result<(), http::header::InvalidHeaderValue> {
-    use alloc::string::ToString as _;
-
-    header_map.append(
-        PROXY_AUTHENTICATE,
-        http::HeaderValue::from_str(ChallengesWithSlice::new(challenges).to_string().as_str())?,
-    );
-    Ok(())
+/// Same as the functions of the parent module, but for the `HeaderMap` of http 1.x.
+#[cfg(feature = "http1")]
+pub mod http1 {
+    header_utils!(http1);
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        use http1::HeaderValue;
+
+        #[cfg(feature = "scheme-basic")]
+        #[test]
+        fn test_get_set_authorization() {
+            use crate::schemes::basic::{
+                DEMO_CREDENTIALS_PASSWORD_STR, DEMO_CREDENTIALS_STR, DEMO_CREDENTIALS_USER_ID_STR,
+            };
+
+            let mut map = HeaderMap::new();
+            assert!(get_authorization(&map).is_none());
+            set_authorization_with_basic(
+                &mut map,
+                DEMO_CREDENTIALS_USER_ID_STR,
+                DEMO_CREDENTIALS_PASSWORD_STR,
+            )
+            .unwrap();
+            assert_eq!(map.get(AUTHORIZATION).unwrap(), DEMO_CREDENTIALS_STR);
+            let c = get_authorization(&map).map(|x| x.unwrap()).unwrap();
+            let c = c.as_basic().unwrap();
+            assert_eq!(c.user_id, DEMO_CREDENTIALS_USER_ID_STR.into());
+            assert_eq!(c.password, DEMO_CREDENTIALS_PASSWORD_STR.into());
+        }
+
+        #[cfg(all(feature = "scheme-basic", feature = "scheme-bearer"))]
+        #[test]
+        fn test_get_append_www_authenticate() {
+            let mut map = HeaderMap::new();
+            assert!(get_www_authenticate(&map).unwrap().is_empty());
+
+            append_www_authenticate(
+                &mut map,
+                &crate::schemes::basic::Challenge::new("foo").into(),
+            )
+            .unwrap();
+            map.append(
+                WWW_AUTHENTICATE,
+                HeaderValue::from_static(r#"Bearer realm="bar""#),
+            );
+            let c = get_www_authenticate(&map).unwrap();
+            assert_eq!(c.len(), 2);
+            assert_eq!(c[0].as_basic().unwrap().realm, "foo".into());
+            assert_eq!(c[1].as_bearer().unwrap().realm, "bar".into());
+
+            map.clear();
+            append_proxy_authenticate_with_multiple(&mut map, &c).unwrap();
+            assert_eq!(
+                map.get(PROXY_AUTHENTICATE).unwrap(),
+                r#"Basic realm="foo", Bearer realm="bar""#
+            );
+        }
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "http"))]
 mod tests {
     use super::*;
 
+    #[cfg(all(feature = "scheme-basic", feature = "http-request-uri"))]
+    #[test]
+    fn test_set_authorization_from_userinfo() {
+        use http_request_uri::RequestUri;
+
+        let request_uri = RequestUri::AbsoluteUri {
+            scheme: "http",
+            username: Some("aladdin"),
+            password: Some("open%20sesame"),
+            host: "example.com",
+            port: Some(8080),
+            path: "/foo",
+            query: Some("bar=1"),
+            fragment: None,
+        };
+
+        let mut map = HeaderMap::new();
+        let uri = set_authorization_from_userinfo(&mut map, &request_uri).unwrap();
+        assert_eq!(uri, "http://example.com:8080/foo?bar=1");
+        let c = get_authorization(&map).unwrap().unwrap();
+        let c = c.as_basic().unwrap();
+        assert_eq!(c.user_id, "aladdin".into());
+        assert_eq!(c.password, "open sesame".into());
+
+        //
+        let request_uri = RequestUri::AbsoluteUri {
+            scheme: "http",
+            username: None,
+            password: None,
+            host: "proxy.example.com",
+            port: None,
+            path: "/",
+            query: None,
+            fragment: None,
+        };
+        let mut map = HeaderMap::new();
+        let uri = set_proxy_authorization_from_userinfo(&mut map, &request_uri).unwrap();
+        assert_eq!(uri, "http://proxy.example.com/");
+        assert!(map.is_empty());
+
+        //
+        let request_uri = RequestUri::Asterisk;
+        assert_eq!(
+            set_authorization_from_userinfo(&mut map, &request_uri).unwrap_err(),
+            UserinfoError::UriNotAbsolute
+        );
+
+        let request_uri = RequestUri::AbsoluteUri {
+            scheme: "http",
+            username: Some("ala%3Addin"),
+            password: Some("open%3Asesame"),
+            host: "example.com",
+            port: None,
+            path: "/",
+            query: None,
+            fragment: None,
+        };
+        assert_eq!(
+            set_authorization_from_userinfo(&mut map, &request_uri).unwrap_err(),
+            UserinfoError::UserIdContainsColon
+        );
+
+        let request_uri = RequestUri::AbsoluteUri {
+            scheme: "http",
+            username: Some("aladdin"),
+            password: Some("open%+1"),
+            host: "example.com",
+            port: None,
+            path: "/",
+            query: None,
+            fragment: None,
+        };
+        assert_eq!(
+            set_authorization_from_userinfo(&mut map, &request_uri).unwrap_err(),
+            UserinfoError::PasswordDecodeFailed("escape invalid")
+        );
+    }
+
     #[cfg(feature = "scheme-basic")]
     #[test]
     fn test_get_set_authorization() {
@@ -205,6 +479,20 @@ mod tests {
         assert_eq!(map.get(AUTHORIZATION).unwrap(), DEMO_CREDENTIALS_STR);
     }
 
+    #[cfg(feature = "scheme-vapid")]
+    #[test]
+    fn test_get_set_authorization_vapid() {
+        use crate::schemes::vapid::DEMO_CREDENTIALS_STR;
+
+        let mut map = HeaderMap::new();
+        let c = DEMO_CREDENTIALS_STR.parse::<Credentials>().unwrap();
+        set_authorization(&mut map, &c).unwrap();
+        assert_eq!(map.get(AUTHORIZATION).unwrap(), DEMO_CREDENTIALS_STR);
+
+        let c = get_authorization(&map).map(|x| x.unwrap()).unwrap();
+        assert!(c.as_vapid().is_some());
+    }
+
     #[cfg(feature = "scheme-basic")]
     #[test]
     fn test_get_set_proxy_authorization() {
@@ -17,16 +17,100 @@ pub(crate) const D_Q_M: char = '"';
 
 //
 pub mod challenge;
+pub mod challenge_ref;
 pub mod challenges;
 pub mod credentials;
+pub mod credentials_ref;
 
 pub use challenge::Challenge;
+pub use challenge_ref::{ChallengeRef, ChallengeRefs};
 pub use challenges::Challenges;
 pub use credentials::Credentials;
+pub use credentials_ref::CredentialsRef;
+
+//
+pub mod parse_options;
+
+pub use parse_options::{ParseErrorPosition, ParseOptions};
 
 //
 pub mod schemes;
 
 //
+pub mod selection;
+
+pub use selection::ChallengeSelector;
+
+#[cfg(all(feature = "std", feature = "http-request-uri"))]
+pub mod credential_cache;
+
+#[cfg(all(feature = "std", feature = "http-request-uri"))]
+pub use credential_cache::{CredentialCache, ProtectionSpace};
+
+#[cfg(feature = "http-request-uri")]
+pub mod realm_router;
+
+#[cfg(feature = "http-request-uri")]
+pub use realm_router::{RealmConfig, RealmRouter};
+
+#[cfg(feature = "http")]
+pub mod auth_response;
+
 #[cfg(feature = "http")]
+pub use auth_response::{AuthFailure, AuthResponse};
+
+#[cfg(all(feature = "http", feature = "http-request-uri"))]
+pub mod proxy_auth;
+
+#[cfg(all(feature = "http", feature = "http-request-uri"))]
+pub use proxy_auth::ProxyAuth;
+
+//
+pub mod verifier;
+
+pub use verifier::{Rejection, Verifier, VerifyContext};
+
+#[cfg(feature = "std")]
+pub mod attempt_limiter;
+
+#[cfg(feature = "std")]
+pub use attempt_limiter::{AttemptLimiter, LimitedVerifier};
+
+#[cfg(feature = "audit")]
+pub mod audit;
+
+#[cfg(feature = "api-key")]
+pub mod api_key;
+
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+//
+#[cfg(any(feature = "http", feature = "http1"))]
 pub mod header_utils;
+
+#[cfg(any(
+    all(
+        feature = "scheme-basic",
+        feature = "http-request-uri",
+        any(feature = "http", feature = "http1")
+    ),
+    feature = "api-key"
+))]
+pub(crate) mod percent_decode;
+
+#[cfg(feature = "headers")]
+pub mod typed_headers;
+
+//
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+#[cfg(feature = "serde")]
+pub use serde_impls::{Structured, StructuredSerialize};
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+
+#[cfg(feature = "proptest")]
+pub mod proptest_strategies;
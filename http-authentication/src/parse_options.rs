@@ -0,0 +1,250 @@
+use alloc::{boxed::Box, string::String};
+
+use crate::SP;
+
+//
+const HTAB: u8 = b'\t';
+const FRAGMENT_MAX_LEN: usize = 16;
+
+//
+/// Controls how strictly credentials and challenges are parsed.
+///
+/// [`ParseOptions::strict`] follows the RFC grammar exactly,
+/// [`ParseOptions::lenient`] accepts the deviations commonly seen in the wild.
+/// The default is strict, except that any UTF-8 Bearer token is accepted as it always was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    /// Accept more SP or HTAB after the single SP between the scheme and the token68.
+    pub allow_multiple_sp: bool,
+    /// Ignore whitespace before the scheme and after the token68 or the last challenge.
+    pub allow_surrounding_whitespace: bool,
+    /// Accept a Basic token68 whose base64 padding is missing.
+    pub allow_unpadded_base64: bool,
+    /// Skip challenges with an unknown or unsupported scheme, or an unknown Digest `algorithm`,
+    /// instead of failing.
+    pub skip_unsupported_challenges: bool,
+    /// Accept any UTF-8 Bearer token, not only the token68 grammar.
+    pub allow_invalid_bearer_token: bool,
+}
+
+impl ParseOptions {
+    pub const fn strict() -> Self {
+        Self {
+            allow_multiple_sp: false,
+            allow_surrounding_whitespace: false,
+            allow_unpadded_base64: false,
+            skip_unsupported_challenges: false,
+            allow_invalid_bearer_token: false,
+        }
+    }
+
+    pub const fn lenient() -> Self {
+        Self {
+            allow_multiple_sp: true,
+            allow_surrounding_whitespace: true,
+            allow_unpadded_base64: true,
+            skip_unsupported_challenges: true,
+            allow_invalid_bearer_token: true,
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            allow_invalid_bearer_token: true,
+            ..Self::strict()
+        }
+    }
+}
+
+//
+/// Where the input failed.
+///
+/// `offset` is the byte offset into the parsed header value,
+/// `fragment` is the offending part of it, truncated to a few bytes.
+/// Errors found inside a decoded Basic token68 point at the token68 and leave `fragment` empty,
+/// so that no part of the secret ends up in logs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseErrorPosition {
+    pub offset: usize,
+    pub fragment: Box<str>,
+}
+
+impl ParseErrorPosition {
+    pub fn new(offset: usize, fragment: impl AsRef<str>) -> Self {
+        Self {
+            offset,
+            fragment: fragment.as_ref().into(),
+        }
+    }
+
+    pub(crate) fn from_input(input: &[u8], offset: usize, len: usize) -> Self {
+        let start = offset.min(input.len());
+        let end = (offset + len.min(FRAGMENT_MAX_LEN)).min(input.len());
+        Self::new(offset, String::from_utf8_lossy(&input[start..end]))
+    }
+
+    #[cfg(any(feature = "scheme-basic", feature = "scheme-bearer"))]
+    pub(crate) fn redacted(offset: usize) -> Self {
+        Self::new(offset, "")
+    }
+}
+
+impl core::fmt::Display for ParseErrorPosition {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "at byte {}: {:?}", self.offset, self.fragment)
+    }
+}
+
+//
+//
+//
+#[cfg(any(feature = "scheme-basic", feature = "scheme-bearer"))]
+pub(crate) enum Token68Error {
+    TooShort,
+    SchemeMismatch,
+    OneSPMismatch,
+    Token68Invalid,
+}
+
+/// Splits `<scheme> SP token68`, returns the offset of the token68 and the token68.
+///
+/// The token68 grammar is only checked when `check_token68`.
+#[cfg(any(feature = "scheme-basic", feature = "scheme-bearer"))]
+pub(crate) fn split_token68<'a>(
+    bytes: &'a [u8],
+    name: &str,
+    options: &ParseOptions,
+    check_token68: bool,
+) -> Result<(usize, &'a [u8]), (Token68Error, ParseErrorPosition)> {
+    let (start, end) = if options.allow_surrounding_whitespace {
+        let start = bytes
+            .iter()
+            .position(|x| !is_whitespace(*x))
+            .unwrap_or(bytes.len());
+        let end = bytes
+            .iter()
+            .rposition(|x| !is_whitespace(*x))
+            .map(|x| x + 1)
+            .unwrap_or(start);
+        (start, end)
+    } else {
+        (0, bytes.len())
+    };
+
+    if end - start < name.len() + 1 {
+        return Err((
+            Token68Error::TooShort,
+            ParseErrorPosition::from_input(bytes, start, end - start),
+        ));
+    }
+
+    if !bytes[start..start + name.len()].eq_ignore_ascii_case(name.as_bytes()) {
+        let scheme_len = bytes[start..end]
+            .iter()
+            .position(|x| *x == SP as u8)
+            .unwrap_or(end - start);
+        return Err((
+            Token68Error::SchemeMismatch,
+            ParseErrorPosition::from_input(bytes, start, scheme_len.max(1)),
+        ));
+    }
+
+    let mut offset = start + name.len();
+    if bytes[offset] != SP as u8 {
+        return Err((
+            Token68Error::OneSPMismatch,
+            ParseErrorPosition::from_input(bytes, offset, 1),
+        ));
+    }
+    offset += 1;
+    if options.allow_multiple_sp {
+        while offset < end && is_whitespace(bytes[offset]) {
+            offset += 1;
+        }
+    }
+
+    let token68 = &bytes[offset..end];
+    if let Some(n) = token68_invalid_at(token68).filter(|_| check_token68) {
+        return Err((
+            Token68Error::Token68Invalid,
+            ParseErrorPosition::from_input(bytes, offset + n, 1),
+        ));
+    }
+
+    Ok((offset, token68))
+}
+
+pub(crate) fn is_whitespace(b: u8) -> bool {
+    b == SP as u8 || b == HTAB
+}
+
+// token68 = 1*( ALPHA / DIGIT / "-" / "." / "_" / "~" / "+" / "/" ) *"="
+// Ref https://www.rfc-editor.org/rfc/rfc7235#section-2.1
+#[cfg(any(feature = "scheme-basic", feature = "scheme-bearer"))]
+fn token68_invalid_at(token68: &[u8]) -> Option<usize> {
+    let n = token68
+        .iter()
+        .position(|x| !(x.is_ascii_alphanumeric() || b"-._~+/".contains(x)))
+        .unwrap_or(token68.len());
+    if n == 0 {
+        return Some(0);
+    }
+    token68[n..].iter().position(|x| *x != b'=').map(|x| n + x)
+}
+
+#[cfg(all(test, any(feature = "scheme-basic", feature = "scheme-bearer")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_token68() {
+        let strict = ParseOptions::strict();
+        let lenient = ParseOptions::lenient();
+
+        match split_token68(b"Basic Zm9v", "Basic", &strict, true) {
+            Ok((6, b"Zm9v")) => {}
+            Ok(x) => panic!("{x:?}"),
+            Err((_, pos)) => panic!("{pos}"),
+        }
+
+        match split_token68(b"Basic  Zm9v ", "Basic", &strict, true) {
+            Err((Token68Error::Token68Invalid, pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(6, " "));
+            }
+            Ok(x) => panic!("{x:?}"),
+            Err((_, pos)) => panic!("{pos}"),
+        }
+
+        match split_token68(b" Basic \t Zm9v= ", "Basic", &lenient, true) {
+            Ok((9, b"Zm9v=")) => {}
+            Ok(x) => panic!("{x:?}"),
+            Err((_, pos)) => panic!("{pos}"),
+        }
+
+        match split_token68(b"Basic Zm9v=x", "Basic", &lenient, true) {
+            Err((Token68Error::Token68Invalid, pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(11, "x"));
+            }
+            Ok(x) => panic!("{x:?}"),
+            Err((_, pos)) => panic!("{pos}"),
+        }
+
+        match split_token68(b"Bearer abc", "Basic", &strict, true) {
+            Err((Token68Error::SchemeMismatch, pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "Bearer"));
+            }
+            Ok(x) => panic!("{x:?}"),
+            Err((_, pos)) => panic!("{pos}"),
+        }
+
+        match split_token68(b"Basic", "Basic", &strict, true) {
+            Err((Token68Error::TooShort, pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "Basic"));
+            }
+            Ok(x) => panic!("{x:?}"),
+            Err((_, pos)) => panic!("{pos}"),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use alloc::{borrow::Cow, vec::Vec};
+use core::str;
+
+//
+/// Decodes `%XX` escapes, the result must be UTF-8.
+pub(crate) fn percent_decode(s: &str) -> Result<Cow<'_, str>, &'static str> {
+    if !s.contains('%') {
+        return Ok(Cow::Borrowed(s));
+    }
+
+    let bytes = s.as_bytes();
+    let mut buf = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = bytes.get(i + 1..i + 3).ok_or("escape incomplete")?;
+            // `from_str_radix` alone would accept a sign, e.g. `%+1`.
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return Err("escape invalid");
+            }
+            let hex = str::from_utf8(hex).map_err(|_| "escape invalid")?;
+            buf.push(u8::from_str_radix(hex, 16).map_err(|_| "escape invalid")?);
+            i += 3;
+        } else {
+            buf.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    alloc::string::String::from_utf8(buf)
+        .map(Cow::Owned)
+        .map_err(|_| "not utf-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("foo").unwrap(), "foo");
+        assert_eq!(percent_decode("a%40b%3Ac%25").unwrap(), "a@b:c%");
+        assert_eq!(percent_decode("%E4%BD%A0").unwrap(), "你");
+        assert!(percent_decode("a%4").is_err());
+        assert!(percent_decode("a%zz").is_err());
+        assert!(percent_decode("a%+1").is_err());
+        assert!(percent_decode("a%-1").is_err());
+        assert!(percent_decode("%ff").is_err());
+    }
+}
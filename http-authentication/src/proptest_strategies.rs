@@ -0,0 +1,347 @@
+//! [`proptest`] strategies producing values that survive a render and parse round trip.
+//!
+//! `Credentials`, `Challenge` and `Challenges` also implement [`proptest::arbitrary::Arbitrary`],
+//! so `any::<Credentials>()` works in downstream tests.
+
+use alloc::boxed::Box;
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest",
+    feature = "scheme-concealed"
+))]
+use proptest::collection::vec;
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest"
+))]
+use proptest::option;
+use proptest::strategy::{BoxedStrategy, Strategy};
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest",
+    feature = "scheme-vapid",
+    feature = "scheme-concealed"
+))]
+use proptest::{arbitrary::Arbitrary, strategy::Union};
+
+//
+/// Printable ASCII, the renderers escape `"` and `\`.
+pub const QUOTED_STRING_REGEX: &str = r#"[ -~]*"#;
+pub const TOKEN_REGEX: &str = "[A-Za-z0-9!#$%&'*+.^_`|~-]+";
+pub const TOKEN68_REGEX: &str = "[A-Za-z0-9._~+/-]+=*";
+
+pub fn quoted_string() -> BoxedStrategy<Box<str>> {
+    QUOTED_STRING_REGEX.prop_map(Into::into).boxed()
+}
+
+pub fn token() -> BoxedStrategy<Box<str>> {
+    TOKEN_REGEX.prop_map(Into::into).boxed()
+}
+
+pub fn token68() -> BoxedStrategy<Box<str>> {
+    TOKEN68_REGEX.prop_map(Into::into).boxed()
+}
+
+//
+/// Neither part contains `:`, the parser rejects a second colon.
+#[cfg(feature = "scheme-basic")]
+pub fn basic_credentials() -> BoxedStrategy<crate::schemes::basic::Credentials> {
+    ("[^:]*", "[^:]*")
+        .prop_map(|(user_id, password)| crate::schemes::basic::Credentials::new(user_id, password))
+        .boxed()
+}
+
+#[cfg(feature = "scheme-basic")]
+pub fn basic_challenge() -> BoxedStrategy<crate::schemes::basic::Challenge> {
+    (quoted_string(), option::of(quoted_string()))
+        .prop_map(|(realm, charset)| crate::schemes::basic::Challenge { realm, charset })
+        .boxed()
+}
+
+#[cfg(feature = "scheme-bearer")]
+pub fn bearer_credentials() -> BoxedStrategy<crate::schemes::bearer::Credentials> {
+    token68()
+        .prop_map(crate::schemes::bearer::Credentials::new)
+        .boxed()
+}
+
+#[cfg(feature = "scheme-bearer")]
+pub fn bearer_challenge() -> BoxedStrategy<crate::schemes::bearer::Challenge> {
+    (
+        quoted_string(),
+        option::of(quoted_string()),
+        option::of(quoted_string()),
+        option::of(quoted_string()),
+        option::of(quoted_string()),
+    )
+        .prop_map(|(realm, scope, error, error_description, error_uri)| {
+            crate::schemes::bearer::Challenge {
+                realm,
+                scope,
+                error,
+                error_description,
+                error_uri,
+            }
+        })
+        .boxed()
+}
+
+#[cfg(feature = "scheme-digest")]
+pub fn digest_algorithm() -> BoxedStrategy<crate::schemes::digest::Algorithm> {
+    use crate::schemes::digest::Algorithm;
+
+    proptest::sample::select(&[
+        Algorithm::Md5,
+        Algorithm::Md5Sess,
+        Algorithm::Sha256,
+        Algorithm::Sha256Sess,
+        Algorithm::Sha512_256,
+        Algorithm::Sha512_256Sess,
+    ])
+    .boxed()
+}
+
+#[cfg(feature = "scheme-digest")]
+pub fn digest_challenge() -> BoxedStrategy<crate::schemes::digest::Challenge> {
+    (
+        (
+            quoted_string(),
+            option::of(quoted_string()),
+            quoted_string(),
+        ),
+        (option::of(quoted_string()), proptest::bool::ANY),
+        (
+            option::of(digest_algorithm()),
+            option::of(quoted_string()),
+            option::of(token()),
+            proptest::bool::ANY,
+        ),
+    )
+        .prop_map(
+            |((realm, domain, nonce), (opaque, stale), (algorithm, qop, charset, userhash))| {
+                crate::schemes::digest::Challenge {
+                    realm,
+                    domain,
+                    nonce,
+                    opaque,
+                    stale,
+                    algorithm,
+                    qop,
+                    charset,
+                    userhash,
+                }
+            },
+        )
+        .boxed()
+}
+
+#[cfg(feature = "scheme-vapid")]
+pub fn vapid_credentials() -> BoxedStrategy<crate::schemes::vapid::Credentials> {
+    ("[A-Za-z0-9._~+/-]+", "[A-Za-z0-9._~+/-]+")
+        .prop_map(|(token, public_key)| crate::schemes::vapid::Credentials::new(token, public_key))
+        .boxed()
+}
+
+#[cfg(feature = "scheme-concealed")]
+pub fn concealed_credentials() -> BoxedStrategy<crate::schemes::concealed::Credentials> {
+    let bytes = || vec(proptest::num::u8::ANY, 1..64);
+    (bytes(), bytes(), bytes(), proptest::num::u16::ANY, bytes())
+        .prop_map(
+            |(key_id, public_key, proof, signature_scheme, verification)| {
+                crate::schemes::concealed::Credentials::from_raw(
+                    &key_id,
+                    &public_key,
+                    &proof,
+                    signature_scheme,
+                    &verification,
+                )
+            },
+        )
+        .boxed()
+}
+
+//
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-vapid",
+    feature = "scheme-concealed"
+))]
+pub fn credentials() -> BoxedStrategy<crate::Credentials> {
+    let list: alloc::vec::Vec<BoxedStrategy<crate::Credentials>> = alloc::vec![
+        #[cfg(feature = "scheme-basic")]
+        basic_credentials()
+            .prop_map(crate::Credentials::Basic)
+            .boxed(),
+        #[cfg(feature = "scheme-bearer")]
+        bearer_credentials()
+            .prop_map(crate::Credentials::Bearer)
+            .boxed(),
+        #[cfg(feature = "scheme-vapid")]
+        vapid_credentials()
+            .prop_map(crate::Credentials::Vapid)
+            .boxed(),
+        #[cfg(feature = "scheme-concealed")]
+        concealed_credentials()
+            .prop_map(crate::Credentials::Concealed)
+            .boxed(),
+    ];
+    Union::new(list).boxed()
+}
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest"
+))]
+pub fn challenge() -> BoxedStrategy<crate::Challenge> {
+    let list: alloc::vec::Vec<BoxedStrategy<crate::Challenge>> = alloc::vec![
+        #[cfg(feature = "scheme-basic")]
+        basic_challenge().prop_map(crate::Challenge::Basic).boxed(),
+        #[cfg(feature = "scheme-bearer")]
+        bearer_challenge()
+            .prop_map(crate::Challenge::Bearer)
+            .boxed(),
+        #[cfg(feature = "scheme-digest")]
+        digest_challenge()
+            .prop_map(crate::Challenge::Digest)
+            .boxed(),
+    ];
+    Union::new(list).boxed()
+}
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest"
+))]
+pub fn challenges() -> BoxedStrategy<crate::Challenges> {
+    vec(challenge(), 1..4)
+        .prop_map(crate::Challenges::new)
+        .boxed()
+}
+
+//
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-vapid",
+    feature = "scheme-concealed"
+))]
+impl Arbitrary for crate::Credentials {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        credentials()
+    }
+}
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest"
+))]
+impl Arbitrary for crate::Challenge {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        challenge()
+    }
+}
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest"
+))]
+impl Arbitrary for crate::Challenges {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        challenges()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(any(
+        feature = "scheme-basic",
+        feature = "scheme-bearer",
+        feature = "scheme-digest",
+        feature = "scheme-vapid",
+        feature = "scheme-concealed"
+    ))]
+    use alloc::string::ToString as _;
+    use alloc::vec::Vec;
+
+    #[cfg(any(
+        feature = "scheme-basic",
+        feature = "scheme-bearer",
+        feature = "scheme-digest",
+        feature = "scheme-vapid",
+        feature = "scheme-concealed"
+    ))]
+    use proptest::prop_assert_eq;
+    use proptest::{prelude::any, proptest};
+
+    use crate::{ChallengeRefs, Challenges, Credentials, CredentialsRef};
+
+    #[cfg(any(
+        feature = "scheme-basic",
+        feature = "scheme-bearer",
+        feature = "scheme-vapid",
+        feature = "scheme-concealed"
+    ))]
+    proptest! {
+        #[test]
+        fn test_credentials_round_trip(c in any::<Credentials>()) {
+            prop_assert_eq!(c.to_string().parse::<Credentials>().unwrap(), c);
+        }
+    }
+
+    #[cfg(any(
+        feature = "scheme-basic",
+        feature = "scheme-bearer",
+        feature = "scheme-digest"
+    ))]
+    proptest! {
+        #[test]
+        fn test_challenge_round_trip(c in any::<crate::Challenge>()) {
+            let parsed = c.to_string().parse::<Challenges>().unwrap();
+            prop_assert_eq!(parsed.0, [c]);
+        }
+
+        #[test]
+        fn test_challenges_round_trip(c in any::<Challenges>()) {
+            prop_assert_eq!(c.to_string().parse::<Challenges>().unwrap(), c);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_parse_arbitrary_bytes(bytes in any::<Vec<u8>>()) {
+            let _ = Credentials::from_bytes(&bytes);
+            let _ = Credentials::from_bytes_with_options(&bytes, &crate::ParseOptions::lenient());
+            let mut buf = [0_u8; 256];
+            let _ = CredentialsRef::from_bytes(&bytes, &mut buf);
+            let _ = Challenges::from_bytes(&bytes);
+            let _ = Challenges::from_bytes_with_options(&bytes, &crate::ParseOptions::lenient());
+        }
+
+        #[test]
+        fn test_parse_arbitrary_str(s in ".*") {
+            let _ = s.parse::<Credentials>();
+            let _ = ChallengeRefs::with_options(&s, crate::ParseOptions::lenient()).count();
+        }
+    }
+}
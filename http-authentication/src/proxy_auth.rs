@@ -0,0 +1,369 @@
+//! Forward proxy authentication, `Proxy-Authorization` is checked and 407 responses carry
+//! `Proxy-Authenticate`.
+//!
+//! `CONNECT host:port` targets the authority form, other requests the absolute form.
+//! Destinations are checked against allow rules once the client is authenticated.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use http::{header::PROXY_AUTHORIZATION, HeaderMap, Method, Response, StatusCode};
+use http_request_uri::RequestUri;
+
+use crate::{
+    header_utils::{append_proxy_authenticate_with_multiple, get_proxy_authorization},
+    realm_router::RealmConfig,
+    verifier::{CredentialsSource, Rejection, Verifier, VerifyContext},
+};
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostPattern {
+    Any,
+    Exact(Box<str>),
+    /// `*.example.com`, matches subdomains but not `example.com`.
+    Subdomains(Box<str>),
+}
+
+impl HostPattern {
+    /// `*` is any host, a leading `*.` matches subdomains.
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "*" => Self::Any,
+            _ => match s.strip_prefix("*.") {
+                Some(domain) => Self::Subdomains(domain.to_ascii_lowercase().into()),
+                None => Self::Exact(s.to_ascii_lowercase().into()),
+            },
+        }
+    }
+
+    pub fn matches(&self, host: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Exact(x) => x.eq_ignore_ascii_case(host),
+            Self::Subdomains(domain) => {
+                host.len() > domain.len() + 1 && {
+                    let (sub, rest) = host.as_bytes().split_at(host.len() - domain.len());
+                    sub.ends_with(b".") && rest.eq_ignore_ascii_case(domain.as_bytes())
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DestinationRule {
+    pub host: HostPattern,
+    /// `None` allows every port.
+    pub ports: Option<Vec<u16>>,
+}
+
+impl DestinationRule {
+    pub fn new(host: &str) -> Self {
+        Self {
+            host: HostPattern::parse(host),
+            ports: None,
+        }
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.ports.get_or_insert_with(Vec::new).push(port);
+        self
+    }
+
+    pub fn matches(&self, host: &str, port: u16) -> bool {
+        self.host.matches(host)
+            && self
+                .ports
+                .as_ref()
+                .map(|ports| ports.contains(&port))
+                .unwrap_or(true)
+    }
+}
+
+/// The host and port a request is forwarded to.
+///
+/// `CONNECT` requires the authority form with a port, other methods the absolute form,
+/// the port defaults to 80 or 443 by scheme.
+pub fn destination<'a>(method: &Method, request_uri: &RequestUri<'a>) -> Option<(&'a str, u16)> {
+    match request_uri {
+        RequestUri::Authority {
+            host,
+            port: Some(port),
+        } if method == Method::CONNECT => Some((host, *port)),
+        RequestUri::AbsoluteUri {
+            scheme, host, port, ..
+        } if method != Method::CONNECT => {
+            let port = match port {
+                Some(port) => *port,
+                None if scheme.eq_ignore_ascii_case("http") => 80,
+                None if scheme.eq_ignore_ascii_case("https") => 443,
+                None => return None,
+            };
+            Some((host, port))
+        }
+        _ => None,
+    }
+}
+
+/// Removes `Proxy-Authorization` before a non-CONNECT request is forwarded, it is meant for this proxy only.
+///
+/// Returns whether the header was present.
+pub fn strip_proxy_authorization(method: &Method, header_map: &mut HeaderMap) -> bool {
+    if method == Method::CONNECT {
+        return false;
+    }
+    let mut removed = false;
+    while header_map.remove(PROXY_AUTHORIZATION).is_some() {
+        removed = true;
+    }
+    removed
+}
+
+//
+#[derive(Debug)]
+pub enum ProxyAuthError {
+    Rejected(Rejection),
+    DestinationInvalid,
+    DestinationDenied,
+}
+
+impl core::fmt::Display for ProxyAuthError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProxyAuthError {}
+
+//
+#[derive(Debug, Clone)]
+pub struct ProxyAuth<V> {
+    config: RealmConfig<V>,
+    rules: Vec<DestinationRule>,
+}
+
+impl<V> ProxyAuth<V> {
+    /// The challenges of `config` are sent in `Proxy-Authenticate`.
+    pub fn new(config: RealmConfig<V>) -> Self {
+        Self {
+            config,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Once called, only destinations matching a rule are allowed.
+    pub fn allow(mut self, rule: DestinationRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn config(&self) -> &RealmConfig<V> {
+        &self.config
+    }
+
+    pub fn allows_destination(&self, host: &str, port: u16) -> bool {
+        self.rules.is_empty() || self.rules.iter().any(|x| x.matches(host, port))
+    }
+
+    /// 407 for [`ProxyAuthError::Rejected`], with `Retry-After` when rate limited,
+    /// 403 for a denied destination and 400 for an invalid one.
+    pub fn error_response(&self, err: &ProxyAuthError) -> Response<()> {
+        let mut response = Response::new(());
+        match err {
+            ProxyAuthError::Rejected(rejection) => {
+                *response.status_mut() = StatusCode::PROXY_AUTHENTICATION_REQUIRED;
+                // The challenges are built from the config, they are valid header values.
+                let _ = append_proxy_authenticate_with_multiple(
+                    response.headers_mut(),
+                    self.config.challenges(),
+                );
+                if let Some(secs) = rejection.retry_after_secs() {
+                    response
+                        .headers_mut()
+                        .insert(http::header::RETRY_AFTER, secs.into());
+                }
+            }
+            ProxyAuthError::DestinationInvalid => {
+                *response.status_mut() = StatusCode::BAD_REQUEST;
+            }
+            ProxyAuthError::DestinationDenied => {
+                *response.status_mut() = StatusCode::FORBIDDEN;
+            }
+        }
+        response
+    }
+}
+
+impl<V: Verifier> ProxyAuth<V> {
+    /// Verifies `Proxy-Authorization`, then the destination. The source and realm of `ctx` are replaced.
+    pub fn authorize(
+        &self,
+        method: &Method,
+        request_uri: &RequestUri<'_>,
+        header_map: &HeaderMap,
+        ctx: &VerifyContext<'_>,
+    ) -> Result<V::Identity, ProxyAuthError> {
+        let ctx = VerifyContext {
+            source: CredentialsSource::ProxyAuthorization,
+            ..*ctx
+        };
+        let identity = self
+            .config
+            .verify(get_proxy_authorization(header_map), &ctx)
+            .map_err(ProxyAuthError::Rejected)?;
+
+        let (host, port) =
+            destination(method, request_uri).ok_or(ProxyAuthError::DestinationInvalid)?;
+        if !self.allows_destination(host, port) {
+            return Err(ProxyAuthError::DestinationDenied);
+        }
+        Ok(identity)
+    }
+
+    /// Same as [`ProxyAuth::authorize`], `Proxy-Authorization` is stripped from non-CONNECT requests
+    /// once authorized, a failure is turned into the response to send.
+    pub fn handle(
+        &self,
+        method: &Method,
+        request_uri: &RequestUri<'_>,
+        header_map: &mut HeaderMap,
+        ctx: &VerifyContext<'_>,
+    ) -> Result<V::Identity, Response<()>> {
+        match self.authorize(method, request_uri, header_map, ctx) {
+            Ok(identity) => {
+                strip_proxy_authorization(method, header_map);
+                Ok(identity)
+            }
+            Err(err) => Err(self.error_response(&err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_destination_rule() {
+        let rule = DestinationRule::new("*.Example.com").port(443);
+        assert!(rule.matches("api.example.com", 443));
+        assert!(!rule.matches("example.com", 443));
+        assert!(!rule.matches("badexample.com", 443));
+        assert!(!rule.matches("api.example.com", 80));
+
+        assert!(DestinationRule::new("*").matches("foo", 1));
+        assert!(DestinationRule::new("Foo").matches("foo", 1));
+        assert!(!DestinationRule::new("*.b.com").matches("aé.com", 1));
+    }
+
+    #[test]
+    fn test_destination() {
+        let connect = RequestUri::Authority {
+            host: "example.com",
+            port: Some(443),
+        };
+        assert_eq!(
+            destination(&Method::CONNECT, &connect),
+            Some(("example.com", 443))
+        );
+        assert_eq!(destination(&Method::GET, &connect), None);
+        assert_eq!(
+            destination(
+                &Method::CONNECT,
+                &RequestUri::Authority {
+                    host: "example.com",
+                    port: None
+                }
+            ),
+            None
+        );
+
+        let absolute = RequestUri::AbsoluteUri {
+            scheme: "http",
+            username: None,
+            password: None,
+            host: "example.com",
+            port: None,
+            path: "/",
+            query: None,
+            fragment: None,
+        };
+        assert_eq!(
+            destination(&Method::GET, &absolute),
+            Some(("example.com", 80))
+        );
+    }
+
+    #[cfg(feature = "scheme-basic")]
+    #[test]
+    fn test_handle() {
+        use http::HeaderValue;
+
+        struct V;
+        impl Verifier for V {
+            type Identity = Box<str>;
+
+            fn verify(
+                &self,
+                credentials: &crate::Credentials,
+                _ctx: &VerifyContext<'_>,
+            ) -> Result<Self::Identity, Rejection> {
+                match credentials.as_basic() {
+                    Some(c) if &*c.password == "pass" => Ok(c.user_id.clone()),
+                    _ => Err(Rejection::Invalid),
+                }
+            }
+        }
+
+        let auth = ProxyAuth::new(RealmConfig::new("proxy", V).basic(None))
+            .allow(DestinationRule::new("*.example.com").port(443));
+        let ctx = VerifyContext::new(CredentialsSource::Authorization);
+        let connect = |host| RequestUri::Authority {
+            host,
+            port: Some(443),
+        };
+
+        let mut map = HeaderMap::new();
+        let response = auth
+            .handle(&Method::CONNECT, &connect("a.example.com"), &mut map, &ctx)
+            .unwrap_err();
+        assert_eq!(response.status(), StatusCode::PROXY_AUTHENTICATION_REQUIRED);
+        assert_eq!(
+            response.headers()[http::header::PROXY_AUTHENTICATE],
+            r#"Basic realm="proxy""#
+        );
+
+        // foo:pass
+        map.insert(
+            PROXY_AUTHORIZATION,
+            HeaderValue::from_static("Basic Zm9vOnBhc3M="),
+        );
+        assert_eq!(
+            auth.handle(&Method::CONNECT, &connect("a.example.com"), &mut map, &ctx)
+                .unwrap(),
+            "foo".into()
+        );
+        assert!(map.contains_key(PROXY_AUTHORIZATION));
+
+        let response = auth
+            .handle(&Method::CONNECT, &connect("example.org"), &mut map, &ctx)
+            .unwrap_err();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        //
+        let absolute = RequestUri::AbsoluteUri {
+            scheme: "https",
+            username: None,
+            password: None,
+            host: "b.example.com",
+            port: None,
+            path: "/",
+            query: None,
+            fragment: None,
+        };
+        auth.handle(&Method::GET, &absolute, &mut map, &ctx)
+            .unwrap();
+        assert!(!map.contains_key(PROXY_AUTHORIZATION));
+    }
+}
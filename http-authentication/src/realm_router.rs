@@ -0,0 +1,437 @@
+//! Multi-tenant configuration, requests are mapped to a realm by host and path prefix.
+//!
+//! Each realm has its own accepted schemes, challenge parameters and verifier.
+//! Send [`RealmConfig::challenges`] with `header_utils::append_www_authenticate_with_multiple`.
+
+use alloc::{borrow::Cow, boxed::Box, string::String, vec::Vec};
+
+use http_request_uri::RequestUri;
+
+use crate::{
+    challenge::Challenge,
+    credentials::{Credentials, CredentialsParseError},
+    verifier::{verify_credentials, Rejection, Verifier, VerifyContext},
+};
+
+//
+#[derive(Debug, Clone)]
+pub struct RealmConfig<V> {
+    realm: Box<str>,
+    challenges: Vec<Challenge>,
+    verifier: V,
+}
+
+impl<V> RealmConfig<V> {
+    pub fn new(realm: impl AsRef<str>, verifier: V) -> Self {
+        Self {
+            realm: realm.as_ref().into(),
+            challenges: Vec::new(),
+            verifier,
+        }
+    }
+
+    /// Accepts Basic.
+    #[cfg(feature = "scheme-basic")]
+    pub fn basic(mut self, charset: Option<&str>) -> Self {
+        let mut c = crate::schemes::basic::Challenge::new(&self.realm);
+        c.charset = charset.map(Into::into);
+        self.challenges.push(c.into());
+        self
+    }
+
+    /// Accepts Bearer.
+    #[cfg(feature = "scheme-bearer")]
+    pub fn bearer(mut self, scope: Option<&str>) -> Self {
+        let mut c = crate::schemes::bearer::Challenge::new(&self.realm);
+        c.scope = scope.map(Into::into);
+        self.challenges.push(c.into());
+        self
+    }
+
+    /// Accepts the scheme of `challenge`, sent as is.
+    // `Challenge` has no variants when no scheme is enabled.
+    #[allow(unreachable_code)]
+    pub fn challenge(mut self, challenge: impl Into<Challenge>) -> Self {
+        self.challenges.push(challenge.into());
+        self
+    }
+
+    pub fn realm(&self) -> &str {
+        &self.realm
+    }
+
+    pub fn verifier(&self) -> &V {
+        &self.verifier
+    }
+
+    /// The `WWW-Authenticate` challenges, in the order they were added.
+    pub fn challenges(&self) -> &[Challenge] {
+        &self.challenges
+    }
+
+    pub fn allows(&self, scheme: &str) -> bool {
+        self.challenges
+            .iter()
+            .any(|c| c.scheme().eq_ignore_ascii_case(scheme))
+    }
+}
+
+impl<V: Verifier> RealmConfig<V> {
+    /// Same as [`verify_credentials`], the realm of `ctx` is replaced,
+    /// credentials of a scheme the realm does not accept are rejected with [`Rejection::SchemeUnsupported`].
+    pub fn verify(
+        &self,
+        credentials: Option<Result<Credentials, CredentialsParseError>>,
+        ctx: &VerifyContext<'_>,
+    ) -> Result<V::Identity, Rejection> {
+        let ctx = VerifyContext {
+            source: ctx.source,
+            realm: Some(&self.realm),
+            client_addr: ctx.client_addr,
+        };
+
+        match credentials {
+            Some(Ok(c)) if !self.allows(c.scheme()) => {
+                Err(self.verifier.reject(Rejection::SchemeUnsupported, &ctx))
+            }
+            credentials => verify_credentials(&self.verifier, credentials, &ctx),
+        }
+    }
+}
+
+//
+#[derive(Debug, Clone)]
+struct Route<V> {
+    host: Option<Box<str>>,
+    path_prefix: Box<str>,
+    config: RealmConfig<V>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RealmRouter<V> {
+    routes: Vec<Route<V>>,
+    fallback: Option<RealmConfig<V>>,
+}
+
+impl<V> Default for RealmRouter<V> {
+    fn default() -> Self {
+        Self {
+            routes: Vec::new(),
+            fallback: None,
+        }
+    }
+}
+
+impl<V> RealmRouter<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `host` `None` matches any host, `path_prefix` matches whole path segments.
+    pub fn route(
+        mut self,
+        host: Option<&str>,
+        path_prefix: impl AsRef<str>,
+        config: RealmConfig<V>,
+    ) -> Self {
+        self.routes.push(Route {
+            host: host.map(Into::into),
+            path_prefix: path_prefix.as_ref().into(),
+            config,
+        });
+        self
+    }
+
+    /// Used when no route matches.
+    pub fn fallback(mut self, config: RealmConfig<V>) -> Self {
+        self.fallback = Some(config);
+        self
+    }
+
+    /// `host` is the `Host` header, used when `request_uri` is not absolute.
+    ///
+    /// The longest matching path prefix wins, a route with a host wins over one without.
+    /// The path is matched after [`normalize_path`], so `/public/../admin` resolves as `/admin`.
+    pub fn resolve(
+        &self,
+        request_uri: &RequestUri<'_>,
+        host: Option<&str>,
+    ) -> Option<&RealmConfig<V>> {
+        let (host, path) = match request_uri {
+            RequestUri::AbsoluteUri { host, path, .. } => (Some(*host), *path),
+            RequestUri::Authority { host, .. } => (Some(*host), ""),
+            RequestUri::Origin { path, .. } => (host.map(host_without_port), *path),
+            RequestUri::Asterisk => (host.map(host_without_port), ""),
+        };
+        let path = normalize_path(path);
+
+        self.routes
+            .iter()
+            .filter(|route| match (&route.host, host) {
+                (Some(x), Some(host)) => x.eq_ignore_ascii_case(host),
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            .filter(|route| path_matches(&route.path_prefix, &path))
+            .max_by_key(|route| (route.path_prefix.len(), route.host.is_some()))
+            .map(|route| &route.config)
+            .or(self.fallback.as_ref())
+    }
+}
+
+fn host_without_port(host: &str) -> &str {
+    if let Some(end) = host.strip_prefix('[').and_then(|x| x.find(']')) {
+        return &host[..end + 2];
+    }
+    match host.rsplit_once(':') {
+        Some((host, port)) if port.bytes().all(|x| x.is_ascii_digit()) => host,
+        _ => host,
+    }
+}
+
+/// Normalizes the path the way a backend may before routing.
+///
+/// Percent-encoded unreserved characters are decoded, `\` is read as `/`,
+/// then empty and dot-segments are removed, see [RFC 3986 Section 6.2.2](https://www.rfc-editor.org/rfc/rfc3986#section-6.2.2).
+/// Other escapes are kept, with uppercase hex digits. `%2F` and `%5C` stay inside their segment,
+/// a backend may route them as part of a segment, decoding them would move the request to another realm.
+pub fn normalize_path(path: &str) -> Cow<'_, str> {
+    if !(path.contains('%')
+        || path.contains('\\')
+        || path.contains("//")
+        || path.contains("/.")
+        || path.starts_with('.'))
+    {
+        return Cow::Borrowed(path);
+    }
+
+    let bytes = path.as_bytes();
+    let mut decoded = String::with_capacity(path.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let escaped = match bytes.get(i + 1..i + 3) {
+            Some([x, y]) if b == b'%' && x.is_ascii_hexdigit() && y.is_ascii_hexdigit() => {
+                Some(hex_value(*x) << 4 | hex_value(*y))
+            }
+            _ => None,
+        };
+        match escaped {
+            Some(x) if x.is_ascii_alphanumeric() || b"-._~".contains(&x) => {
+                decoded.push(x as char);
+            }
+            Some(x) => {
+                decoded.push('%');
+                decoded.push(char::from(b"0123456789ABCDEF"[usize::from(x >> 4)]));
+                decoded.push(char::from(b"0123456789ABCDEF"[usize::from(x & 0xf)]));
+            }
+            None => {
+                let c = path[i..].chars().next().unwrap_or_default();
+                decoded.push(if c == '\\' { '/' } else { c });
+                i += c.len_utf8();
+                continue;
+            }
+        }
+        i += 3;
+    }
+
+    let mut segments: Vec<&str> = Vec::new();
+    let mut trailing_slash = false;
+    for segment in decoded.split('/') {
+        trailing_slash = matches!(segment, "" | "." | "..");
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            x => segments.push(x),
+        }
+    }
+
+    let mut normalized = String::with_capacity(decoded.len());
+    for segment in &segments {
+        normalized.push('/');
+        normalized.push_str(segment);
+    }
+    if trailing_slash || segments.is_empty() {
+        normalized.push('/');
+    }
+    Cow::Owned(normalized)
+}
+
+fn hex_value(b: u8) -> u8 {
+    match b {
+        b'0'..=b'9' => b - b'0',
+        b'a'..=b'f' => b - b'a' + 10,
+        _ => b - b'A' + 10,
+    }
+}
+
+fn path_matches(prefix: &str, path: &str) -> bool {
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.ends_with('/'),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct AllowAll;
+
+    impl Verifier for AllowAll {
+        type Identity = ();
+
+        fn verify(
+            &self,
+            _credentials: &Credentials,
+            ctx: &VerifyContext<'_>,
+        ) -> Result<Self::Identity, Rejection> {
+            assert!(ctx.realm.is_some());
+            Ok(())
+        }
+    }
+
+    fn origin(path: &str) -> RequestUri<'_> {
+        RequestUri::Origin {
+            path,
+            query: None,
+            fragment: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve() {
+        let router = RealmRouter::new()
+            .route(None, "/", RealmConfig::new("default", AllowAll))
+            .route(None, "/a", RealmConfig::new("a", AllowAll))
+            .route(
+                Some("foo.example.com"),
+                "/",
+                RealmConfig::new("foo", AllowAll),
+            )
+            .route(
+                Some("foo.example.com"),
+                "/a/b",
+                RealmConfig::new("foo-b", AllowAll),
+            );
+
+        let realm = |uri: &RequestUri<'_>, host| router.resolve(uri, host).map(|x| x.realm());
+        assert_eq!(realm(&origin("/"), None), Some("default"));
+        assert_eq!(realm(&origin("/ab"), None), Some("default"));
+        assert_eq!(realm(&origin("/a/x"), None), Some("a"));
+        assert_eq!(
+            realm(&origin("/x"), Some("FOO.example.com:8080")),
+            Some("foo")
+        );
+        assert_eq!(realm(&origin("/a/x"), Some("foo.example.com")), Some("a"));
+        assert_eq!(
+            realm(&origin("/a/b"), Some("foo.example.com")),
+            Some("foo-b")
+        );
+
+        let uri = RequestUri::AbsoluteUri {
+            scheme: "https",
+            username: None,
+            password: None,
+            host: "foo.example.com",
+            port: None,
+            path: "/a/b/c",
+            query: None,
+            fragment: None,
+        };
+        assert_eq!(realm(&uri, None), Some("foo-b"));
+
+        //
+        let router = RealmRouter::new().route(None, "/a", RealmConfig::new("a", AllowAll));
+        assert!(router.resolve(&origin("/b"), None).is_none());
+        let router = router.fallback(RealmConfig::new("fallback", AllowAll));
+        assert_eq!(
+            router.resolve(&origin("/b"), None).map(|x| x.realm()),
+            Some("fallback")
+        );
+
+        assert_eq!(host_without_port("[::1]:8080"), "[::1]");
+    }
+
+    #[test]
+    fn test_resolve_normalized() {
+        let router = RealmRouter::new()
+            .route(None, "/public", RealmConfig::new("public", AllowAll))
+            .route(None, "/admin", RealmConfig::new("admin", AllowAll))
+            .fallback(RealmConfig::new("fallback", AllowAll));
+
+        let realm = |path| router.resolve(&origin(path), None).map(|x| x.realm());
+        for path in [
+            "/admin",
+            "/public/../admin",
+            "/public/./../admin/x",
+            "/%61dmin",
+            "/public/%2e%2E/admin",
+            "/public\\..\\admin",
+            "//admin",
+            "/./admin/",
+        ] {
+            assert_eq!(realm(path), Some("admin"), "{path}");
+        }
+        assert_eq!(realm("/public/x/../y"), Some("public"));
+        assert_eq!(realm("/public/../../x"), Some("fallback"));
+        // Encoded separators do not start a segment, the dot-segments stay inside `x%2F..`.
+        assert_eq!(realm("/admin/x%2F..%2F..%2Fpublic"), Some("admin"));
+        assert_eq!(realm("/admin/x%5C..%5C..%5Cpublic"), Some("admin"));
+        assert_eq!(realm("/public%2F..%2Fadmin"), Some("fallback"));
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert!(matches!(normalize_path("/a/b"), Cow::Borrowed("/a/b")));
+        assert_eq!(normalize_path("/a/./b/../c/"), "/a/c/");
+        assert_eq!(normalize_path("/a/.."), "/");
+        assert_eq!(normalize_path("/%7e%41%2f%3a%zz"), "/~A%2F%3A%zz");
+        assert_eq!(
+            normalize_path("/admin/x%2F..%2F..%2Fpublic"),
+            "/admin/x%2F..%2F..%2Fpublic"
+        );
+        assert_eq!(normalize_path("/caf%C3%A9/é"), "/caf%C3%A9/é");
+        assert_eq!(normalize_path(""), "");
+    }
+
+    #[cfg(all(feature = "scheme-basic", feature = "scheme-bearer"))]
+    #[test]
+    fn test_challenges_and_verify() {
+        use alloc::{string::ToString as _, vec};
+
+        use crate::verifier::CredentialsSource;
+
+        let config = RealmConfig::new("tenant-a", AllowAll)
+            .basic(Some("UTF-8"))
+            .bearer(Some("read write"));
+        assert_eq!(
+            config
+                .challenges()
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                r#"Basic realm="tenant-a", charset="UTF-8""#,
+                r#"Bearer realm="tenant-a", scope="read write""#,
+            ]
+        );
+
+        let ctx = VerifyContext::new(CredentialsSource::Authorization);
+        assert!(config
+            .verify(Some(Ok(Credentials::bearer("foo"))), &ctx)
+            .is_ok());
+
+        let config = RealmConfig::new("tenant-b", AllowAll).basic(None);
+        match config.verify(Some(Ok(Credentials::bearer("foo"))), &ctx) {
+            Err(Rejection::SchemeUnsupported) => {}
+            x => panic!("{x:?}"),
+        }
+        match config.verify(None, &ctx) {
+            Err(Rejection::Missing) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+}
@@ -3,14 +3,15 @@ use alloc::{boxed::Box, string::String, vec};
 use http_auth::ChallengeRef;
 
 use crate::{
-    schemes::NAME_BASIC as NAME, CHALLENGE_PARAM_REALM as PARAM_REALM, COMMA, D_Q_M, EQ_S, SP,
+    challenge::push_quoted_string, schemes::NAME_BASIC as NAME,
+    CHALLENGE_PARAM_REALM as PARAM_REALM, COMMA, EQ_S, SP,
 };
 
 //
-const PARAM_CHARSET: &str = "charset";
+pub(crate) const PARAM_CHARSET: &str = "charset";
 
 //
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Challenge {
     pub realm: Box<str>,
     pub charset: Option<Box<str>>,
@@ -31,9 +32,7 @@ impl Challenge {
 
         s.push_str(PARAM_REALM);
         s.push(EQ_S);
-        s.push(D_Q_M);
-        s.push_str(self.realm.as_ref());
-        s.push(D_Q_M);
+        push_quoted_string(&mut s, &self.realm);
 
         let mut params = vec![];
         if let Some(charset) = &self.charset {
@@ -46,11 +45,9 @@ impl Challenge {
             s.push_str(k);
             s.push(EQ_S);
             if is_quoted {
-                s.push(D_Q_M);
-            }
-            s.push_str(v.as_ref());
-            if is_quoted {
-                s.push(D_Q_M);
+                push_quoted_string(&mut s, v.as_ref());
+            } else {
+                s.push_str(v.as_ref());
             }
         }
 
@@ -58,35 +55,12 @@ impl Challenge {
     }
 }
 
-//
-// Ref https://github.com/scottlamb/http-auth/blob/v0.1.5/src/basic.rs#L69-L90
 //
 impl TryFrom<&ChallengeRef<'_>> for Challenge {
     type Error = ChallengeParseError;
 
     fn try_from(c: &ChallengeRef<'_>) -> Result<Self, Self::Error> {
-        if !c.scheme.eq_ignore_ascii_case(NAME) {
-            return Err(ChallengeParseError::SchemeMismatch);
-        }
-
-        let realm = c
-            .params
-            .iter()
-            .find(|(k, _)| k.eq_ignore_ascii_case(PARAM_REALM))
-            .map(|(_, v)| v.as_escaped().into());
-        //
-        // TODO, Optional
-        // Ref https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/WWW-Authenticate#basic
-        //
-        let realm = realm.unwrap_or_default();
-
-        let charset = c
-            .params
-            .iter()
-            .find(|(k, _)| k.eq_ignore_ascii_case(PARAM_CHARSET))
-            .map(|(_, v)| v.as_escaped().into());
-
-        Ok(Self { realm, charset })
+        super::ChallengeRef::try_from(c).map(Into::into)
     }
 }
 
@@ -156,4 +130,14 @@ mod tests {
         c.charset = Some(DEMO_CHALLENGE_CHARSET_STR.into());
         assert_eq!(c.to_string(), DEMO_CHALLENGE_STR);
     }
+
+    #[test]
+    fn test_render_escaped() {
+        let c = Challenge::new(r#"a "b" \c"#);
+        let s = c.to_string();
+        assert_eq!(s, r#"Basic realm="a \"b\" \\c""#);
+
+        let parsed = s.parse::<crate::Challenges>().unwrap();
+        assert_eq!(parsed.0, [crate::Challenge::Basic(c)]);
+    }
 }
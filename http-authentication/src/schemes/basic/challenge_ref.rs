@@ -0,0 +1,102 @@
+use crate::{
+    challenge::unescape_quoted_string, schemes::NAME_BASIC as NAME,
+    CHALLENGE_PARAM_REALM as PARAM_REALM,
+};
+
+use super::challenge::PARAM_CHARSET;
+use super::{Challenge, ChallengeParseError};
+
+//
+/// Borrowed [`Challenge`], the values are escaped as they appear in the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChallengeRef<'a> {
+    pub realm: &'a str,
+    pub charset: Option<&'a str>,
+}
+
+impl<'a> ChallengeRef<'a> {
+    pub fn new(realm: &'a str) -> Self {
+        Self {
+            realm,
+            charset: None,
+        }
+    }
+
+    /// Unescapes the quoted-string values.
+    pub fn into_owned(self) -> Challenge {
+        Challenge {
+            realm: unescape_quoted_string(self.realm),
+            charset: self.charset.map(unescape_quoted_string),
+        }
+    }
+}
+
+impl From<ChallengeRef<'_>> for Challenge {
+    fn from(c: ChallengeRef<'_>) -> Self {
+        c.into_owned()
+    }
+}
+
+//
+// Ref https://github.com/scottlamb/http-auth/blob/v0.1.5/src/basic.rs#L69-L90
+//
+impl<'a> TryFrom<&http_auth::ChallengeRef<'a>> for ChallengeRef<'a> {
+    type Error = ChallengeParseError;
+
+    fn try_from(c: &http_auth::ChallengeRef<'a>) -> Result<Self, Self::Error> {
+        if !c.scheme.eq_ignore_ascii_case(NAME) {
+            return Err(ChallengeParseError::SchemeMismatch);
+        }
+
+        Self::from_params(|name| {
+            c.params
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_escaped())
+        })
+    }
+}
+
+impl<'a> ChallengeRef<'a> {
+    /// `find` returns the escaped value of a param by its case-insensitive name.
+    pub(crate) fn from_params(
+        find: impl Fn(&str) -> Option<&'a str>,
+    ) -> Result<Self, ChallengeParseError> {
+        //
+        // TODO, Optional
+        // Ref https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/WWW-Authenticate#basic
+        //
+        let realm = find(PARAM_REALM).unwrap_or_default();
+
+        Ok(Self {
+            realm,
+            charset: find(PARAM_CHARSET),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http_auth::ParamValue;
+
+    #[test]
+    fn test_try_from_challenge_ref() {
+        let mut c = http_auth::ChallengeRef::new(NAME);
+        c.params
+            .push((PARAM_REALM, ParamValue::try_from_escaped("foo").unwrap()));
+        c.params.push((
+            PARAM_CHARSET,
+            ParamValue::try_from_escaped("UTF-8").unwrap(),
+        ));
+
+        let c = ChallengeRef::try_from(&c).unwrap();
+        assert_eq!(c.realm, "foo");
+        assert_eq!(c.charset, Some("UTF-8"));
+
+        let c = c.into_owned();
+        assert_eq!(c.realm, "foo".into());
+        assert_eq!(c.charset, Some("UTF-8".into()));
+    }
+}
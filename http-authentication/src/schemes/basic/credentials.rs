@@ -1,15 +1,28 @@
 use alloc::{boxed::Box, format, string::String};
 use core::str::{self, FromStr};
 
-use base64::{engine::general_purpose, Engine as _};
+use base64::{
+    alphabet,
+    engine::{general_purpose, DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    DecodeError, Engine as _,
+};
 
-use crate::{schemes::NAME_BASIC as NAME, SP};
+use crate::{
+    parse_options::{split_token68, ParseErrorPosition, ParseOptions, Token68Error},
+    schemes::NAME_BASIC as NAME,
+    SP,
+};
 
 //
 const COLON: char = ':';
 
+const STANDARD_PADDING_INDIFFERENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
 //
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Credentials {
     pub user_id: Box<str>,
     pub password: Box<str>,
@@ -24,39 +37,22 @@ impl Credentials {
     }
 
     pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, CredentialsParseError> {
-        let bytes = bytes.as_ref();
-
-        if bytes.len() < NAME.len() + 1 {
-            return Err(CredentialsParseError::Other("too short"));
-        }
-
-        if !&bytes[..NAME.len()].eq_ignore_ascii_case(NAME.as_bytes()) {
-            return Err(CredentialsParseError::SchemeMismatch);
-        }
+        Self::from_bytes_with_options(bytes, &ParseOptions::default())
+    }
 
-        if bytes[NAME.len()..NAME.len() + 1] != [SP as u8] {
-            return Err(CredentialsParseError::OneSPMismatch);
-        }
+    pub fn from_bytes_with_options(
+        bytes: impl AsRef<[u8]>,
+        options: &ParseOptions,
+    ) -> Result<Self, CredentialsParseError> {
+        let bytes = bytes.as_ref();
 
-        let token68_bytes = &bytes[NAME.len() + 1..];
+        let (token68_offset, token68_bytes) = token68_bytes(bytes, options)?;
 
-        let token68_b64_decoded_bytes = general_purpose::STANDARD
+        let token68_b64_decoded_bytes = engine(options)
             .decode(token68_bytes)
-            .map_err(CredentialsParseError::Token68DecodeFailed)?;
-
-        let mut token68_split = token68_b64_decoded_bytes.split(|x| *x == COLON as u8);
-        let user_id = token68_split
-            .next()
-            .ok_or(CredentialsParseError::UserIdMissing)?;
-        let user_id = str::from_utf8(user_id).map_err(CredentialsParseError::UserIdToStrFailed)?;
-        let password = token68_split
-            .next()
-            .ok_or(CredentialsParseError::PasswordMissing)?;
-        let password =
-            str::from_utf8(password).map_err(CredentialsParseError::PasswordToStrFailed)?;
-        if token68_split.next().is_some() {
-            return Err(CredentialsParseError::Token68PairsMismatch);
-        }
+            .map_err(|err| token68_decode_failed(bytes, token68_offset, err))?;
+
+        let (user_id, password) = user_id_and_password(&token68_b64_decoded_bytes, token68_offset)?;
 
         Ok(Self::new(user_id, password))
     }
@@ -69,18 +65,115 @@ impl Credentials {
     }
 }
 
+//
+pub(crate) fn token68_bytes<'a>(
+    bytes: &'a [u8],
+    options: &ParseOptions,
+) -> Result<(usize, &'a [u8]), CredentialsParseError> {
+    split_token68(bytes, NAME, options, true).map_err(|(err, pos)| match err {
+        Token68Error::TooShort => CredentialsParseError::Other("too short", pos),
+        Token68Error::SchemeMismatch => CredentialsParseError::SchemeMismatch(pos),
+        Token68Error::OneSPMismatch => CredentialsParseError::OneSPMismatch(pos),
+        Token68Error::Token68Invalid => CredentialsParseError::Token68Invalid(pos),
+    })
+}
+
+pub(crate) fn engine(options: &ParseOptions) -> &'static GeneralPurpose {
+    if options.allow_unpadded_base64 {
+        &STANDARD_PADDING_INDIFFERENT
+    } else {
+        &general_purpose::STANDARD
+    }
+}
+
+pub(crate) fn token68_decode_failed(
+    bytes: &[u8],
+    token68_offset: usize,
+    err: DecodeError,
+) -> CredentialsParseError {
+    let pos = match err {
+        DecodeError::InvalidByte(i, _) | DecodeError::InvalidLastSymbol(i, _) => {
+            ParseErrorPosition::from_input(bytes, token68_offset + i, 1)
+        }
+        DecodeError::InvalidLength | DecodeError::InvalidPadding => {
+            ParseErrorPosition::redacted(token68_offset)
+        }
+    };
+    CredentialsParseError::Token68DecodeFailed(err, pos)
+}
+
+pub(crate) fn user_id_and_password(
+    token68_b64_decoded_bytes: &[u8],
+    token68_offset: usize,
+) -> Result<(&str, &str), CredentialsParseError> {
+    let pos = || ParseErrorPosition::redacted(token68_offset);
+
+    let mut token68_split = token68_b64_decoded_bytes.split(|x| *x == COLON as u8);
+    let user_id = token68_split
+        .next()
+        .ok_or_else(|| CredentialsParseError::UserIdMissing(pos()))?;
+    let user_id = str::from_utf8(user_id)
+        .map_err(|err| CredentialsParseError::UserIdToStrFailed(err, pos()))?;
+    let password = token68_split
+        .next()
+        .ok_or_else(|| CredentialsParseError::PasswordMissing(pos()))?;
+    let password = str::from_utf8(password)
+        .map_err(|err| CredentialsParseError::PasswordToStrFailed(err, pos()))?;
+    if token68_split.next().is_some() {
+        return Err(CredentialsParseError::Token68PairsMismatch(pos()));
+    }
+
+    Ok((user_id, password))
+}
+
 //
 #[derive(Debug)]
 pub enum CredentialsParseError {
-    SchemeMismatch,
-    OneSPMismatch,
-    Token68DecodeFailed(base64::DecodeError),
-    UserIdMissing,
-    UserIdToStrFailed(str::Utf8Error),
-    PasswordMissing,
-    PasswordToStrFailed(str::Utf8Error),
-    Token68PairsMismatch,
-    Other(&'static str),
+    SchemeMismatch(ParseErrorPosition),
+    OneSPMismatch(ParseErrorPosition),
+    Token68Invalid(ParseErrorPosition),
+    Token68DecodeFailed(base64::DecodeError, ParseErrorPosition),
+    Token68DecodeBufTooSmall(ParseErrorPosition),
+    UserIdMissing(ParseErrorPosition),
+    UserIdToStrFailed(str::Utf8Error, ParseErrorPosition),
+    PasswordMissing(ParseErrorPosition),
+    PasswordToStrFailed(str::Utf8Error, ParseErrorPosition),
+    Token68PairsMismatch(ParseErrorPosition),
+    Other(&'static str, ParseErrorPosition),
+}
+
+impl CredentialsParseError {
+    pub fn position(&self) -> &ParseErrorPosition {
+        match self {
+            Self::SchemeMismatch(pos)
+            | Self::OneSPMismatch(pos)
+            | Self::Token68Invalid(pos)
+            | Self::Token68DecodeFailed(_, pos)
+            | Self::Token68DecodeBufTooSmall(pos)
+            | Self::UserIdMissing(pos)
+            | Self::UserIdToStrFailed(_, pos)
+            | Self::PasswordMissing(pos)
+            | Self::PasswordToStrFailed(_, pos)
+            | Self::Token68PairsMismatch(pos)
+            | Self::Other(_, pos) => pos,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::SchemeMismatch(_) => "SchemeMismatch",
+            Self::OneSPMismatch(_) => "OneSPMismatch",
+            Self::Token68Invalid(_) => "Token68Invalid",
+            Self::Token68DecodeFailed(_, _) => "Token68DecodeFailed",
+            Self::Token68DecodeBufTooSmall(_) => "Token68DecodeBufTooSmall",
+            Self::UserIdMissing(_) => "UserIdMissing",
+            Self::UserIdToStrFailed(_, _) => "UserIdToStrFailed",
+            Self::PasswordMissing(_) => "PasswordMissing",
+            Self::PasswordToStrFailed(_, _) => "PasswordToStrFailed",
+            Self::Token68PairsMismatch(_) => "Token68PairsMismatch",
+            Self::Other(_, _) => "Other",
+        }
+    }
 }
 
 impl core::fmt::Display for CredentialsParseError {
@@ -133,24 +226,65 @@ mod tests {
 
         //
         match Credentials::from_str("Basic") {
-            Err(CredentialsParseError::Other(err)) => {
-                assert_eq!(err, "too short")
+            Err(CredentialsParseError::Other(err, pos)) => {
+                assert_eq!(err, "too short");
+                assert_eq!(pos, ParseErrorPosition::new(0, "Basic"));
             }
             x => panic!("{x:?}"),
         }
 
         match Credentials::from_str("MyScheme ") {
-            Err(CredentialsParseError::SchemeMismatch) => {}
+            Err(CredentialsParseError::SchemeMismatch(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "MyScheme"));
+            }
             x => panic!("{x:?}"),
         }
 
         match Credentials::from_str("Basic-") {
-            Err(CredentialsParseError::OneSPMismatch) => {}
+            Err(CredentialsParseError::OneSPMismatch(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(5, "-"));
+            }
             x => panic!("{x:?}"),
         }
 
         match Credentials::from_str("Basic dGVzdDoxMjM6Zm9v") {
-            Err(CredentialsParseError::Token68PairsMismatch) => {}
+            Err(CredentialsParseError::Token68PairsMismatch(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(6, ""));
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_with_options() {
+        let s = "  Basic   YWxhZGRpbjpvcGVuc2VzYW1l ";
+        match Credentials::from_str(s) {
+            Err(CredentialsParseError::SchemeMismatch(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, " "));
+            }
+            x => panic!("{x:?}"),
+        }
+        let c = Credentials::from_bytes_with_options(s, &ParseOptions::lenient()).unwrap();
+        assert_eq!(c.user_id, DEMO_CREDENTIALS_USER_ID_STR.into());
+        assert_eq!(c.password, DEMO_CREDENTIALS_PASSWORD_STR.into());
+
+        // "foo:bar1" encoded without padding
+        let s = "Basic Zm9vOmJhcjE";
+        match Credentials::from_str(s) {
+            Err(CredentialsParseError::Token68DecodeFailed(_, pos)) => {
+                assert_eq!(pos.offset, 6);
+                assert_eq!(pos.fragment, "".into());
+            }
+            x => panic!("{x:?}"),
+        }
+        let c = Credentials::from_bytes_with_options(s, &ParseOptions::lenient()).unwrap();
+        assert_eq!(c.user_id, "foo".into());
+        assert_eq!(c.password, "bar1".into());
+
+        match Credentials::from_str("Basic Zm9v,mJhcjE=") {
+            Err(CredentialsParseError::Token68Invalid(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(10, ","));
+            }
             x => panic!("{x:?}"),
         }
     }
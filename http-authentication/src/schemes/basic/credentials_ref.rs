@@ -0,0 +1,106 @@
+use base64::{DecodeSliceError, Engine as _};
+
+use crate::parse_options::{ParseErrorPosition, ParseOptions};
+
+use super::credentials::{engine, token68_bytes, token68_decode_failed, user_id_and_password};
+use super::{Credentials, CredentialsParseError};
+
+//
+/// Borrowed [`Credentials`].
+///
+/// The token68 is decoded into a caller-provided buffer, `user_id` and `password` point into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialsRef<'a> {
+    pub user_id: &'a str,
+    pub password: &'a str,
+}
+
+impl<'a> CredentialsRef<'a> {
+    pub fn new(user_id: &'a str, password: &'a str) -> Self {
+        Self { user_id, password }
+    }
+
+    /// `buf` must be large enough to hold the decoded token68, 3/4 of its length is sufficient.
+    pub fn from_bytes(bytes: &[u8], buf: &'a mut [u8]) -> Result<Self, CredentialsParseError> {
+        Self::from_bytes_with_options(bytes, buf, &ParseOptions::default())
+    }
+
+    pub fn from_bytes_with_options(
+        bytes: &[u8],
+        buf: &'a mut [u8],
+        options: &ParseOptions,
+    ) -> Result<Self, CredentialsParseError> {
+        let (token68_offset, token68_bytes) = token68_bytes(bytes, options)?;
+
+        let n = engine(options)
+            .decode_slice(token68_bytes, buf)
+            .map_err(|err| match err {
+                DecodeSliceError::DecodeError(err) => {
+                    token68_decode_failed(bytes, token68_offset, err)
+                }
+                DecodeSliceError::OutputSliceTooSmall => {
+                    CredentialsParseError::Token68DecodeBufTooSmall(ParseErrorPosition::redacted(
+                        token68_offset,
+                    ))
+                }
+            })?;
+
+        let (user_id, password) = user_id_and_password(&buf[..n], token68_offset)?;
+
+        Ok(Self::new(user_id, password))
+    }
+
+    pub fn into_owned(self) -> Credentials {
+        Credentials::new(self.user_id, self.password)
+    }
+}
+
+impl From<CredentialsRef<'_>> for Credentials {
+    fn from(c: CredentialsRef<'_>) -> Self {
+        c.into_owned()
+    }
+}
+
+impl<'a> From<&'a Credentials> for CredentialsRef<'a> {
+    fn from(c: &'a Credentials) -> Self {
+        Self::new(&c.user_id, &c.password)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schemes::basic::{
+        DEMO_CREDENTIALS_PASSWORD_STR, DEMO_CREDENTIALS_STR, DEMO_CREDENTIALS_USER_ID_STR,
+    };
+
+    #[test]
+    fn test_parse() {
+        let mut buf = [0; 64];
+        let c = CredentialsRef::from_bytes(DEMO_CREDENTIALS_STR.as_bytes(), &mut buf).unwrap();
+        assert_eq!(c.user_id, DEMO_CREDENTIALS_USER_ID_STR);
+        assert_eq!(c.password, DEMO_CREDENTIALS_PASSWORD_STR);
+
+        let c = c.into_owned();
+        assert_eq!(c.user_id, DEMO_CREDENTIALS_USER_ID_STR.into());
+        assert_eq!(c.password, DEMO_CREDENTIALS_PASSWORD_STR.into());
+
+        //
+        let mut buf = [0; 4];
+        match CredentialsRef::from_bytes(DEMO_CREDENTIALS_STR.as_bytes(), &mut buf) {
+            Err(CredentialsParseError::Token68DecodeBufTooSmall(pos)) => {
+                assert_eq!(pos.offset, 6);
+            }
+            x => panic!("{x:?}"),
+        }
+
+        let mut buf = [0; 64];
+        match CredentialsRef::from_bytes(b"Basic-", &mut buf) {
+            Err(CredentialsParseError::OneSPMismatch(pos)) => {
+                assert_eq!(pos.offset, 5);
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+}
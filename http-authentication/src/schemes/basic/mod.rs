@@ -9,6 +9,11 @@ pub(crate) use credentials::{
     DEMO_CREDENTIALS_PASSWORD_STR, DEMO_CREDENTIALS_STR, DEMO_CREDENTIALS_USER_ID_STR,
 };
 
+//
+pub mod credentials_ref;
+
+pub use credentials_ref::CredentialsRef;
+
 //
 pub mod challenge;
 
@@ -18,3 +23,8 @@ pub(crate) use challenge::{
     DEMO_CHALLENGE_CHARSET_STR, DEMO_CHALLENGE_REALM_STR, DEMO_CHALLENGE_STR,
     DEMO_CHALLENGE_STR_SIMPLE,
 };
+
+//
+pub mod challenge_ref;
+
+pub use challenge_ref::ChallengeRef;
@@ -3,17 +3,23 @@ use alloc::{boxed::Box, string::String, vec};
 use http_auth::ChallengeRef;
 
 use crate::{
-    schemes::NAME_BEARER as NAME, CHALLENGE_PARAM_REALM as PARAM_REALM, COMMA, D_Q_M, EQ_S, SP,
+    challenge::push_quoted_string, schemes::NAME_BEARER as NAME,
+    CHALLENGE_PARAM_REALM as PARAM_REALM, COMMA, EQ_S, SP,
 };
 
 //
-const PARAM_SCOPE: &str = "scope";
-const PARAM_ERROR: &str = "error";
-const PARAM_ERROR_DESCRIPTION: &str = "error_description";
-const PARAM_ERROR_URI: &str = "error_uri";
+pub(crate) const PARAM_SCOPE: &str = "scope";
+pub(crate) const PARAM_ERROR: &str = "error";
+pub(crate) const PARAM_ERROR_DESCRIPTION: &str = "error_description";
+pub(crate) const PARAM_ERROR_URI: &str = "error_uri";
+
+// Ref https://www.rfc-editor.org/rfc/rfc6750.html#section-3.1
+pub const ERROR_INVALID_REQUEST: &str = "invalid_request";
+pub const ERROR_INVALID_TOKEN: &str = "invalid_token";
+pub const ERROR_INSUFFICIENT_SCOPE: &str = "insufficient_scope";
 
 //
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Challenge {
     pub realm: Box<str>,
     pub scope: Option<Box<str>>,
@@ -40,9 +46,7 @@ impl Challenge {
 
         s.push_str(PARAM_REALM);
         s.push(EQ_S);
-        s.push(D_Q_M);
-        s.push_str(self.realm.as_ref());
-        s.push(D_Q_M);
+        push_quoted_string(&mut s, &self.realm);
 
         let mut params = vec![];
         if let Some(scope) = &self.scope {
@@ -64,11 +68,9 @@ impl Challenge {
             s.push_str(k);
             s.push(EQ_S);
             if is_quoted {
-                s.push(D_Q_M);
-            }
-            s.push_str(v.as_ref());
-            if is_quoted {
-                s.push(D_Q_M);
+                push_quoted_string(&mut s, v.as_ref());
+            } else {
+                s.push_str(v.as_ref());
             }
         }
 
@@ -80,49 +82,7 @@ impl TryFrom<&ChallengeRef<'_>> for Challenge {
     type Error = ChallengeParseError;
 
     fn try_from(c: &ChallengeRef<'_>) -> Result<Self, Self::Error> {
-        if !c.scheme.eq_ignore_ascii_case(NAME) {
-            return Err(ChallengeParseError::SchemeMismatch);
-        }
-
-        let realm = c
-            .params
-            .iter()
-            .find(|(k, _)| k.eq_ignore_ascii_case(PARAM_REALM))
-            .map(|(_, v)| v.as_escaped().into());
-        //
-        // TODO, Optional
-        // Ref https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/WWW-Authenticate#basic
-        //
-        let realm = realm.unwrap_or_default();
-
-        let scope = c
-            .params
-            .iter()
-            .find(|(k, _)| k.eq_ignore_ascii_case(PARAM_SCOPE))
-            .map(|(_, v)| v.as_escaped().into());
-        let error = c
-            .params
-            .iter()
-            .find(|(k, _)| k.eq_ignore_ascii_case(PARAM_ERROR))
-            .map(|(_, v)| v.as_escaped().into());
-        let error_description = c
-            .params
-            .iter()
-            .find(|(k, _)| k.eq_ignore_ascii_case(PARAM_ERROR_DESCRIPTION))
-            .map(|(_, v)| v.as_escaped().into());
-        let error_uri = c
-            .params
-            .iter()
-            .find(|(k, _)| k.eq_ignore_ascii_case(PARAM_ERROR_URI))
-            .map(|(_, v)| v.as_escaped().into());
-
-        Ok(Self {
-            realm,
-            scope,
-            error,
-            error_description,
-            error_uri,
-        })
+        super::ChallengeRef::try_from(c).map(Into::into)
     }
 }
 
@@ -0,0 +1,116 @@
+use crate::{
+    challenge::unescape_quoted_string, schemes::NAME_BEARER as NAME,
+    CHALLENGE_PARAM_REALM as PARAM_REALM,
+};
+
+use super::challenge::{PARAM_ERROR, PARAM_ERROR_DESCRIPTION, PARAM_ERROR_URI, PARAM_SCOPE};
+use super::{Challenge, ChallengeParseError};
+
+//
+/// Borrowed [`Challenge`], the values are escaped as they appear in the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChallengeRef<'a> {
+    pub realm: &'a str,
+    pub scope: Option<&'a str>,
+    pub error: Option<&'a str>,
+    pub error_description: Option<&'a str>,
+    pub error_uri: Option<&'a str>,
+}
+
+impl<'a> ChallengeRef<'a> {
+    pub fn new(realm: &'a str) -> Self {
+        Self {
+            realm,
+            scope: None,
+            error: None,
+            error_description: None,
+            error_uri: None,
+        }
+    }
+
+    /// Unescapes the quoted-string values.
+    pub fn into_owned(self) -> Challenge {
+        Challenge {
+            realm: unescape_quoted_string(self.realm),
+            scope: self.scope.map(unescape_quoted_string),
+            error: self.error.map(unescape_quoted_string),
+            error_description: self.error_description.map(unescape_quoted_string),
+            error_uri: self.error_uri.map(unescape_quoted_string),
+        }
+    }
+}
+
+impl From<ChallengeRef<'_>> for Challenge {
+    fn from(c: ChallengeRef<'_>) -> Self {
+        c.into_owned()
+    }
+}
+
+impl<'a> TryFrom<&http_auth::ChallengeRef<'a>> for ChallengeRef<'a> {
+    type Error = ChallengeParseError;
+
+    fn try_from(c: &http_auth::ChallengeRef<'a>) -> Result<Self, Self::Error> {
+        if !c.scheme.eq_ignore_ascii_case(NAME) {
+            return Err(ChallengeParseError::SchemeMismatch);
+        }
+
+        Self::from_params(|name| {
+            c.params
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_escaped())
+        })
+    }
+}
+
+impl<'a> ChallengeRef<'a> {
+    /// `find` returns the escaped value of a param by its case-insensitive name.
+    pub(crate) fn from_params(
+        find: impl Fn(&str) -> Option<&'a str>,
+    ) -> Result<Self, ChallengeParseError> {
+        // realm is optional for Bearer.
+        // Ref https://www.rfc-editor.org/rfc/rfc6750.html#section-3
+        let realm = find(PARAM_REALM).unwrap_or_default();
+
+        Ok(Self {
+            realm,
+            scope: find(PARAM_SCOPE),
+            error: find(PARAM_ERROR),
+            error_description: find(PARAM_ERROR_DESCRIPTION),
+            error_uri: find(PARAM_ERROR_URI),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http_auth::ParamValue;
+
+    #[test]
+    fn test_try_from_challenge_ref() {
+        let mut c = http_auth::ChallengeRef::new(NAME);
+        c.params
+            .push((PARAM_REALM, ParamValue::try_from_escaped("foo").unwrap()));
+        c.params.push((
+            PARAM_SCOPE,
+            ParamValue::try_from_escaped("openid profile email").unwrap(),
+        ));
+        c.params.push((
+            PARAM_ERROR,
+            ParamValue::try_from_escaped("invalid_token").unwrap(),
+        ));
+
+        let c = ChallengeRef::try_from(&c).unwrap();
+        assert_eq!(c.realm, "foo");
+        assert_eq!(c.scope, Some("openid profile email"));
+        assert_eq!(c.error, Some("invalid_token"));
+        assert_eq!(c.error_description, None);
+        assert_eq!(c.error_uri, None);
+
+        let c = c.into_owned();
+        assert_eq!(c.realm, "foo".into());
+        assert_eq!(c.error, Some("invalid_token".into()));
+    }
+}
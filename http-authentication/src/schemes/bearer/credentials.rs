@@ -1,10 +1,14 @@
 use alloc::{boxed::Box, format, string::String};
 use core::str::{self, FromStr};
 
-use crate::{schemes::NAME_BEARER as NAME, SP};
+use crate::{
+    parse_options::{split_token68, ParseErrorPosition, ParseOptions, Token68Error},
+    schemes::NAME_BEARER as NAME,
+    SP,
+};
 
 //
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Credentials {
     pub token: Box<str>,
 }
@@ -17,24 +21,14 @@ impl Credentials {
     }
 
     pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, CredentialsParseError> {
-        let bytes = bytes.as_ref();
-
-        if bytes.len() < NAME.len() + 1 {
-            return Err(CredentialsParseError::Other("too short"));
-        }
-
-        if !&bytes[..NAME.len()].eq_ignore_ascii_case(NAME.as_bytes()) {
-            return Err(CredentialsParseError::SchemeMismatch);
-        }
-
-        if bytes[NAME.len()..NAME.len() + 1] != [SP as u8] {
-            return Err(CredentialsParseError::OneSPMismatch);
-        }
-
-        let token68_bytes = &bytes[NAME.len() + 1..];
+        Self::from_bytes_with_options(bytes, &ParseOptions::default())
+    }
 
-        let token = token68_bytes;
-        let token = str::from_utf8(token).map_err(CredentialsParseError::TokenToStrFailed)?;
+    pub fn from_bytes_with_options(
+        bytes: impl AsRef<[u8]>,
+        options: &ParseOptions,
+    ) -> Result<Self, CredentialsParseError> {
+        let token = token(bytes.as_ref(), options)?;
 
         Ok(Self::new(token))
     }
@@ -44,13 +38,63 @@ impl Credentials {
     }
 }
 
+//
+pub(crate) fn token<'a>(
+    bytes: &'a [u8],
+    options: &ParseOptions,
+) -> Result<&'a str, CredentialsParseError> {
+    let (token68_offset, token68_bytes) =
+        split_token68(bytes, NAME, options, !options.allow_invalid_bearer_token).map_err(
+            |(err, pos)| match err {
+                Token68Error::TooShort => CredentialsParseError::Other("too short", pos),
+                Token68Error::SchemeMismatch => CredentialsParseError::SchemeMismatch(pos),
+                Token68Error::OneSPMismatch => CredentialsParseError::OneSPMismatch(pos),
+                // The token is the secret, only its offset is kept.
+                Token68Error::Token68Invalid => {
+                    CredentialsParseError::TokenInvalid(ParseErrorPosition::redacted(pos.offset))
+                }
+            },
+        )?;
+
+    let token = token68_bytes;
+    str::from_utf8(token).map_err(|err| {
+        CredentialsParseError::TokenToStrFailed(
+            err,
+            ParseErrorPosition::redacted(token68_offset + err.valid_up_to()),
+        )
+    })
+}
+
 //
 #[derive(Debug)]
 pub enum CredentialsParseError {
-    SchemeMismatch,
-    OneSPMismatch,
-    TokenToStrFailed(str::Utf8Error),
-    Other(&'static str),
+    SchemeMismatch(ParseErrorPosition),
+    OneSPMismatch(ParseErrorPosition),
+    TokenInvalid(ParseErrorPosition),
+    TokenToStrFailed(str::Utf8Error, ParseErrorPosition),
+    Other(&'static str, ParseErrorPosition),
+}
+
+impl CredentialsParseError {
+    pub fn position(&self) -> &ParseErrorPosition {
+        match self {
+            Self::SchemeMismatch(pos)
+            | Self::OneSPMismatch(pos)
+            | Self::TokenInvalid(pos)
+            | Self::TokenToStrFailed(_, pos)
+            | Self::Other(_, pos) => pos,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::SchemeMismatch(_) => "SchemeMismatch",
+            Self::OneSPMismatch(_) => "OneSPMismatch",
+            Self::TokenInvalid(_) => "TokenInvalid",
+            Self::TokenToStrFailed(_, _) => "TokenToStrFailed",
+            Self::Other(_, _) => "Other",
+        }
+    }
 }
 
 impl core::fmt::Display for CredentialsParseError {
@@ -100,19 +144,57 @@ mod tests {
 
         //
         match Credentials::from_str("Bearer") {
-            Err(CredentialsParseError::Other(err)) => {
-                assert_eq!(err, "too short")
+            Err(CredentialsParseError::Other(err, pos)) => {
+                assert_eq!(err, "too short");
+                assert_eq!(pos, ParseErrorPosition::new(0, "Bearer"));
             }
             x => panic!("{x:?}"),
         }
 
         match Credentials::from_str("MyScheme ") {
-            Err(CredentialsParseError::SchemeMismatch) => {}
+            Err(CredentialsParseError::SchemeMismatch(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "MyScheme"));
+            }
             x => panic!("{x:?}"),
         }
 
         match Credentials::from_str("Bearer-") {
-            Err(CredentialsParseError::OneSPMismatch) => {}
+            Err(CredentialsParseError::OneSPMismatch(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(6, "-"));
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_with_options() {
+        let s = "Bearer  mF_9.B5f-4.1JqM\t";
+        match Credentials::from_bytes_with_options(s, &ParseOptions::strict()) {
+            Err(CredentialsParseError::TokenInvalid(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(7, ""));
+            }
+            x => panic!("{x:?}"),
+        }
+        let c = Credentials::from_bytes_with_options(s, &ParseOptions::lenient()).unwrap();
+        assert_eq!(c.token, DEMO_CREDENTIALS_TOKEN_STR.into());
+
+        match Credentials::from_bytes_with_options("Bearer mF_9 B5f", &ParseOptions::strict()) {
+            Err(CredentialsParseError::TokenInvalid(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(11, ""));
+            }
+            x => panic!("{x:?}"),
+        }
+        // The default keeps accepting tokens outside of the token68 grammar.
+        let c = Credentials::from_str("Bearer mF_9 B5f").unwrap();
+        assert_eq!(c.token, "mF_9 B5f".into());
+        let c = Credentials::from_bytes_with_options("Bearer mF_9 B5f", &ParseOptions::lenient())
+            .unwrap();
+        assert_eq!(c.token, "mF_9 B5f".into());
+
+        match Credentials::from_bytes_with_options(b"Bearer mF_9\xff", &ParseOptions::lenient()) {
+            Err(CredentialsParseError::TokenToStrFailed(_, pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(11, ""));
+            }
             x => panic!("{x:?}"),
         }
     }
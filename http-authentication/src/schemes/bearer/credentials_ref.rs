@@ -0,0 +1,68 @@
+use crate::parse_options::ParseOptions;
+
+use super::credentials::token;
+use super::{Credentials, CredentialsParseError};
+
+//
+/// Borrowed [`Credentials`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialsRef<'a> {
+    pub token: &'a str,
+}
+
+impl<'a> CredentialsRef<'a> {
+    pub fn new(token: &'a str) -> Self {
+        Self { token }
+    }
+
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, CredentialsParseError> {
+        Self::from_bytes_with_options(bytes, &ParseOptions::default())
+    }
+
+    pub fn from_bytes_with_options(
+        bytes: &'a [u8],
+        options: &ParseOptions,
+    ) -> Result<Self, CredentialsParseError> {
+        token(bytes, options).map(Self::new)
+    }
+
+    pub fn into_owned(self) -> Credentials {
+        Credentials::new(self.token)
+    }
+}
+
+impl From<CredentialsRef<'_>> for Credentials {
+    fn from(c: CredentialsRef<'_>) -> Self {
+        c.into_owned()
+    }
+}
+
+impl<'a> From<&'a Credentials> for CredentialsRef<'a> {
+    fn from(c: &'a Credentials) -> Self {
+        Self::new(&c.token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schemes::bearer::{DEMO_CREDENTIALS_STR, DEMO_CREDENTIALS_TOKEN_STR};
+
+    #[test]
+    fn test_parse() {
+        let c = CredentialsRef::from_bytes(DEMO_CREDENTIALS_STR.as_bytes()).unwrap();
+        assert_eq!(c.token, DEMO_CREDENTIALS_TOKEN_STR);
+
+        let c = c.into_owned();
+        assert_eq!(c.token, DEMO_CREDENTIALS_TOKEN_STR.into());
+
+        //
+        match CredentialsRef::from_bytes(b"Bearer-") {
+            Err(CredentialsParseError::OneSPMismatch(pos)) => {
+                assert_eq!(pos.offset, 6);
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+}
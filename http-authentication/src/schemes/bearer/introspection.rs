@@ -0,0 +1,497 @@
+//! [OAuth 2.0 Token Introspection](https://www.rfc-editor.org/rfc/rfc7662.html) of opaque Bearer tokens.
+//!
+//! The request to the authorization server is made by an [`IntrospectionClient`],
+//! responses are cached by the SHA-256 of the token.
+
+use core::{future::Future, pin::Pin, time::Duration};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use sha2::{Digest as _, Sha256};
+
+pub use super::challenge::{ERROR_INSUFFICIENT_SCOPE, ERROR_INVALID_TOKEN};
+use super::{Challenge, Credentials};
+use crate::verifier::Rejection;
+
+//
+/// The fields of https://www.rfc-editor.org/rfc/rfc7662.html#section-2.2 used here.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntrospectionResponse {
+    pub active: bool,
+    pub scope: Option<Box<str>>,
+    pub client_id: Option<Box<str>>,
+    pub username: Option<Box<str>>,
+    pub token_type: Option<Box<str>>,
+    pub exp: Option<u64>,
+    pub iat: Option<u64>,
+    pub nbf: Option<u64>,
+    pub sub: Option<Box<str>>,
+    /// A string `aud` becomes a single entry.
+    pub aud: Vec<Box<str>>,
+    pub iss: Option<Box<str>>,
+    pub jti: Option<Box<str>>,
+}
+
+impl IntrospectionResponse {
+    pub fn from_json_slice(bytes: &[u8]) -> Result<Self, IntrospectionError> {
+        use serde_json::Value;
+
+        let value = serde_json::from_slice::<Value>(bytes)
+            .map_err(|_| IntrospectionError::ResponseInvalid("not json"))?;
+        let object = value
+            .as_object()
+            .ok_or(IntrospectionError::ResponseInvalid("not an object"))?;
+
+        let string = |name: &'static str| match object.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(s)) => Ok(Some(Box::<str>::from(s.as_str()))),
+            Some(_) => Err(IntrospectionError::ResponseInvalid(name)),
+        };
+        let number = |name: &'static str| match object.get(name) {
+            None | Some(Value::Null) => Ok(None),
+            Some(x) => x
+                .as_u64()
+                .map(Some)
+                .ok_or(IntrospectionError::ResponseInvalid(name)),
+        };
+
+        let active = object
+            .get("active")
+            .and_then(Value::as_bool)
+            .ok_or(IntrospectionError::ResponseInvalid("active"))?;
+        let aud = match object.get("aud") {
+            None | Some(Value::Null) => Vec::new(),
+            Some(Value::String(s)) => vec![s.as_str().into()],
+            Some(Value::Array(list)) => list
+                .iter()
+                .map(|x| x.as_str().map(Into::into))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(IntrospectionError::ResponseInvalid("aud"))?,
+            Some(_) => return Err(IntrospectionError::ResponseInvalid("aud")),
+        };
+
+        Ok(Self {
+            active,
+            scope: string("scope")?,
+            client_id: string("client_id")?,
+            username: string("username")?,
+            token_type: string("token_type")?,
+            exp: number("exp")?,
+            iat: number("iat")?,
+            nbf: number("nbf")?,
+            sub: string("sub")?,
+            aud,
+            iss: string("iss")?,
+            jti: string("jti")?,
+        })
+    }
+
+    pub fn scopes(&self) -> impl Iterator<Item = &str> {
+        self.scope
+            .as_deref()
+            .unwrap_or_default()
+            .split(' ')
+            .filter(|x| !x.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntrospectionError {
+    /// The endpoint could not be reached or answered with an error status.
+    Unavailable(Box<str>),
+    ResponseInvalid(&'static str),
+}
+
+impl core::fmt::Display for IntrospectionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl std::error::Error for IntrospectionError {}
+
+//
+pub type IntrospectionFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<IntrospectionResponse, IntrospectionError>> + Send + 'a>>;
+
+/// Sends the token to the introspection endpoint, authenticated as the resource server.
+pub trait IntrospectionClient {
+    fn introspect<'a>(&'a self, token: &'a str) -> IntrospectionFuture<'a>;
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntrospectionConfig {
+    pub realm: Box<str>,
+    /// Every scope is required.
+    pub required_scopes: Vec<Box<str>>,
+    /// Active responses are cached until `exp`, at most this long.
+    pub max_ttl: Duration,
+    /// Inactive responses are cached this long, at most `max_ttl`.
+    pub inactive_ttl: Duration,
+    /// The cache holds at most this many entries, the oldest one is evicted first.
+    pub max_entries: usize,
+}
+
+impl IntrospectionConfig {
+    pub fn new(realm: impl AsRef<str>) -> Self {
+        Self {
+            realm: realm.as_ref().into(),
+            required_scopes: Vec::new(),
+            max_ttl: Duration::from_secs(60 * 5),
+            inactive_ttl: Duration::from_secs(30),
+            max_entries: 10_000,
+        }
+    }
+
+    pub fn require_scope(mut self, scope: impl AsRef<str>) -> Self {
+        self.required_scopes.push(scope.as_ref().into());
+        self
+    }
+}
+
+#[derive(Debug)]
+pub enum IntrospectionRejection {
+    /// Inactive, expired or not yet valid.
+    InvalidToken,
+    /// `scope` is the space-separated required scopes.
+    InsufficientScope {
+        scope: Box<str>,
+    },
+    Unavailable(IntrospectionError),
+}
+
+impl IntrospectionRejection {
+    /// `None` for [`IntrospectionRejection::Unavailable`], which is not the client's fault.
+    pub fn challenge(&self, config: &IntrospectionConfig) -> Option<Challenge> {
+        let mut c = Challenge::new(&config.realm);
+        match self {
+            Self::InvalidToken => {
+                c.error = Some(ERROR_INVALID_TOKEN.into());
+            }
+            Self::InsufficientScope { scope } => {
+                c.error = Some(ERROR_INSUFFICIENT_SCOPE.into());
+                c.scope = Some(scope.clone());
+            }
+            Self::Unavailable(_) => return None,
+        }
+        Some(c)
+    }
+}
+
+impl core::fmt::Display for IntrospectionRejection {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl std::error::Error for IntrospectionRejection {}
+
+impl From<IntrospectionRejection> for Rejection {
+    fn from(r: IntrospectionRejection) -> Self {
+        match r {
+            IntrospectionRejection::InvalidToken => Self::Invalid,
+            IntrospectionRejection::InsufficientScope { scope } => {
+                Self::InsufficientScope { scope }
+            }
+            IntrospectionRejection::Unavailable(_) => Self::Other("introspection unavailable"),
+        }
+    }
+}
+
+//
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    response: IntrospectionResponse,
+    expires_at: u64,
+}
+
+#[derive(Debug, Default)]
+struct Cache {
+    entries: HashMap<[u8; 32], CacheEntry>,
+    // Keys in insertion order, for evicting the oldest one.
+    order: VecDeque<[u8; 32]>,
+}
+
+#[derive(Debug)]
+pub struct IntrospectionVerifier<C> {
+    client: C,
+    config: IntrospectionConfig,
+    cache: Mutex<Cache>,
+}
+
+impl<C: IntrospectionClient> IntrospectionVerifier<C> {
+    pub fn new(client: C, config: IntrospectionConfig) -> Self {
+        Self {
+            client,
+            config,
+            cache: Default::default(),
+        }
+    }
+
+    pub fn config(&self) -> &IntrospectionConfig {
+        &self.config
+    }
+
+    pub async fn verify(
+        &self,
+        credentials: &Credentials,
+    ) -> Result<IntrospectionResponse, IntrospectionRejection> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.as_secs())
+            .unwrap_or_default();
+        self.verify_at(credentials, now).await
+    }
+
+    /// `now` is in seconds since the Unix epoch.
+    pub async fn verify_at(
+        &self,
+        credentials: &Credentials,
+        now: u64,
+    ) -> Result<IntrospectionResponse, IntrospectionRejection> {
+        let key: [u8; 32] = Sha256::digest(credentials.token.as_bytes()).into();
+
+        let cached = self
+            .cache
+            .lock()
+            .expect("lock")
+            .entries
+            .get(&key)
+            .filter(|x| x.expires_at > now)
+            .map(|x| x.response.clone());
+        let response = match cached {
+            Some(response) => response,
+            None => {
+                let response = self
+                    .client
+                    .introspect(&credentials.token)
+                    .await
+                    .map_err(IntrospectionRejection::Unavailable)?;
+                self.insert(key, &response, now);
+                response
+            }
+        };
+
+        self.check(response, now)
+    }
+
+    pub fn clear_cache(&self) {
+        let mut cache = self.cache.lock().expect("lock");
+        cache.entries.clear();
+        cache.order.clear();
+    }
+
+    fn insert(&self, key: [u8; 32], response: &IntrospectionResponse, now: u64) {
+        let max_ttl = self.config.max_ttl.as_secs();
+        let ttl = if response.active {
+            match response.exp {
+                Some(exp) => exp.saturating_sub(now).min(max_ttl),
+                None => max_ttl,
+            }
+        } else {
+            self.config.inactive_ttl.as_secs().min(max_ttl)
+        };
+        if ttl == 0 || self.config.max_entries == 0 {
+            return;
+        }
+
+        let entry = CacheEntry {
+            response: response.clone(),
+            expires_at: now.saturating_add(ttl),
+        };
+        let mut cache = self.cache.lock().expect("lock");
+        if let Some(x) = cache.entries.get_mut(&key) {
+            *x = entry;
+            return;
+        }
+        while cache.entries.len() >= self.config.max_entries {
+            match cache.order.pop_front() {
+                Some(oldest) => {
+                    cache.entries.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+        cache.entries.insert(key, entry);
+        cache.order.push_back(key);
+    }
+
+    fn check(
+        &self,
+        response: IntrospectionResponse,
+        now: u64,
+    ) -> Result<IntrospectionResponse, IntrospectionRejection> {
+        if !response.active
+            || response.exp.map(|x| x <= now).unwrap_or(false)
+            || response.nbf.map(|x| x > now).unwrap_or(false)
+        {
+            return Err(IntrospectionRejection::InvalidToken);
+        }
+        if !self
+            .config
+            .required_scopes
+            .iter()
+            .all(|x| response.scopes().any(|y| y == x.as_ref()))
+        {
+            return Err(IntrospectionRejection::InsufficientScope {
+                scope: self.config.required_scopes.join(" ").into(),
+            });
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use alloc::string::ToString as _;
+
+    struct LocalClient {
+        calls: AtomicUsize,
+    }
+
+    impl IntrospectionClient for LocalClient {
+        fn introspect<'a>(&'a self, token: &'a str) -> IntrospectionFuture<'a> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                let json = match token {
+                    "active" => {
+                        r#"{"active":true,"scope":"read write","sub":"Z5O3upPC88QrAjx00dis","aud":"https://protected.example.net/resource","exp":1000}"#
+                    }
+                    "inactive" => r#"{"active":false}"#,
+                    _ => return Err(IntrospectionError::Unavailable("down".into())),
+                };
+                IntrospectionResponse::from_json_slice(json.as_bytes())
+            })
+        }
+    }
+
+    fn verifier(config: IntrospectionConfig) -> IntrospectionVerifier<LocalClient> {
+        IntrospectionVerifier::new(
+            LocalClient {
+                calls: AtomicUsize::new(0),
+            },
+            config,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_verify_and_cache() {
+        let v = verifier(IntrospectionConfig::new("example").require_scope("read"));
+        let calls = |v: &IntrospectionVerifier<LocalClient>| v.client.calls.load(Ordering::SeqCst);
+
+        let response = v.verify_at(&Credentials::new("active"), 100).await.unwrap();
+        assert_eq!(response.sub, Some("Z5O3upPC88QrAjx00dis".into()));
+        assert_eq!(
+            response.aud,
+            ["https://protected.example.net/resource".into()]
+        );
+        v.verify_at(&Credentials::new("active"), 399).await.unwrap();
+        assert_eq!(calls(&v), 1);
+        // max_ttl is 300s.
+        v.verify_at(&Credentials::new("active"), 400).await.unwrap();
+        assert_eq!(calls(&v), 2);
+        // exp is 1000.
+        match v.verify_at(&Credentials::new("active"), 1000).await {
+            Err(IntrospectionRejection::InvalidToken) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match v.verify_at(&Credentials::new("inactive"), 100).await {
+            Err(IntrospectionRejection::InvalidToken) => {}
+            x => panic!("{x:?}"),
+        }
+        v.verify_at(&Credentials::new("inactive"), 129)
+            .await
+            .unwrap_err();
+        assert_eq!(calls(&v), 4);
+        v.verify_at(&Credentials::new("inactive"), 130)
+            .await
+            .unwrap_err();
+        assert_eq!(calls(&v), 5);
+
+        match v.verify_at(&Credentials::new("foo"), 100).await {
+            Err(err @ IntrospectionRejection::Unavailable(_)) => {
+                assert!(err.challenge(v.config()).is_none());
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_cache_max_entries() {
+        let mut config = IntrospectionConfig::new("example");
+        config.max_entries = 3;
+        let v = verifier(config);
+        let response = IntrospectionResponse {
+            active: true,
+            ..Default::default()
+        };
+
+        for i in 0..10_u8 {
+            v.insert([i; 32], &response, 100);
+        }
+        let cache = v.cache.lock().unwrap();
+        assert_eq!(cache.entries.len(), 3);
+        assert_eq!(cache.order.len(), 3);
+        assert!(cache.entries.contains_key(&[9; 32]));
+        assert!(!cache.entries.contains_key(&[6; 32]));
+        drop(cache);
+
+        // Refreshing an entry does not grow the cache.
+        v.insert([9; 32], &response, 200);
+        assert_eq!(v.cache.lock().unwrap().entries.len(), 3);
+
+        // `now + ttl` saturates.
+        v.insert([0; 32], &response, u64::MAX);
+        assert_eq!(
+            v.cache.lock().unwrap().entries[&[0; 32]].expires_at,
+            u64::MAX
+        );
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_scope() {
+        let v = verifier(
+            IntrospectionConfig::new("example")
+                .require_scope("read")
+                .require_scope("admin"),
+        );
+        match v.verify_at(&Credentials::new("active"), 100).await {
+            Err(err @ IntrospectionRejection::InsufficientScope { .. }) => {
+                assert_eq!(
+                    err.challenge(v.config()).unwrap().to_string(),
+                    r#"Bearer realm="example", scope="read admin", error="insufficient_scope""#
+                );
+                match Rejection::from(err) {
+                    Rejection::InsufficientScope { scope } => assert_eq!(&*scope, "read admin"),
+                    x => panic!("{x:?}"),
+                }
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_response_from_json_slice() {
+        let response = IntrospectionResponse::from_json_slice(
+            br#"{"active":true,"aud":["a","b"],"exp":1419356238,"username":"jdoe"}"#,
+        )
+        .unwrap();
+        assert_eq!(response.aud.len(), 2);
+        assert_eq!(response.username, Some("jdoe".into()));
+        assert_eq!(response.scopes().count(), 0);
+
+        assert_eq!(
+            IntrospectionResponse::from_json_slice(br#"{"active":"true"}"#),
+            Err(IntrospectionError::ResponseInvalid("active"))
+        );
+    }
+}
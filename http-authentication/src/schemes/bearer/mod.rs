@@ -7,12 +7,44 @@ pub use credentials::{Credentials, CredentialsParseError};
 #[cfg(test)]
 pub(crate) use credentials::{DEMO_CREDENTIALS_STR, DEMO_CREDENTIALS_TOKEN_STR};
 
+//
+pub mod credentials_ref;
+
+pub use credentials_ref::CredentialsRef;
+
 //
 pub mod challenge;
 
-pub use challenge::{Challenge, ChallengeParseError};
+pub use challenge::{
+    Challenge, ChallengeParseError, ERROR_INSUFFICIENT_SCOPE, ERROR_INVALID_REQUEST,
+    ERROR_INVALID_TOKEN,
+};
 #[cfg(test)]
 pub(crate) use challenge::{
     DEMO_CHALLENGE_ERROR_DESCRIPTION_STR, DEMO_CHALLENGE_ERROR_STR, DEMO_CHALLENGE_REALM_STR,
     DEMO_CHALLENGE_STR, DEMO_CHALLENGE_STR_SIMPLE,
 };
+
+//
+pub mod challenge_ref;
+
+pub use challenge_ref::ChallengeRef;
+
+//
+#[cfg(feature = "introspection")]
+pub mod introspection;
+
+#[cfg(feature = "introspection")]
+pub use introspection::{
+    IntrospectionClient, IntrospectionConfig, IntrospectionRejection, IntrospectionResponse,
+    IntrospectionVerifier,
+};
+
+//
+#[cfg(feature = "revocation")]
+pub mod revocation;
+
+#[cfg(feature = "revocation")]
+pub use revocation::{
+    FileRevocationStore, MemoryRevocationStore, RevocationChecked, RevocationKey, RevocationStore,
+};
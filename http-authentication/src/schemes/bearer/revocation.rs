@@ -0,0 +1,523 @@
+//! Deny-list of revoked Bearer tokens, by the SHA-256 of the token or the `jti` claim of a JWT.
+//!
+//! Entries may expire, usually at the `exp` of the token, after which the token is rejected anyway.
+
+use core::time::Duration;
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, RwLock,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use sha2::{Digest as _, Sha256};
+
+use super::{Challenge, Credentials, ERROR_INVALID_TOKEN};
+use crate::{
+    credentials::Credentials as AnyCredentials,
+    verifier::{Rejection, Verifier, VerifyContext},
+};
+
+//
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RevocationKey {
+    TokenHash([u8; 32]),
+    Jti(Box<str>),
+}
+
+impl RevocationKey {
+    pub fn token(token: impl AsRef<[u8]>) -> Self {
+        Self::TokenHash(Sha256::digest(token.as_ref()).into())
+    }
+
+    pub fn jti(jti: impl AsRef<str>) -> Self {
+        Self::Jti(jti.as_ref().into())
+    }
+
+    /// The keys to look up for `credentials`, the `jti` only when the token is a JWT with one.
+    ///
+    /// The signature of the JWT is not checked, a forged `jti` can only cause a rejection.
+    pub fn of(credentials: &Credentials) -> (Self, Option<Self>) {
+        (
+            Self::token(credentials.token.as_bytes()),
+            jwt_jti(&credentials.token).map(Self::Jti),
+        )
+    }
+}
+
+fn jwt_jti(token: &str) -> Option<Box<str>> {
+    let mut parts = token.split('.');
+    let (_, payload, _) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+    let payload = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    let value = serde_json::from_slice::<serde_json::Value>(&payload).ok()?;
+    value.get("jti")?.as_str().map(Into::into)
+}
+
+/// `sha256:<hex>` or `jti:<value>`.
+impl core::fmt::Display for RevocationKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TokenHash(hash) => {
+                f.write_str("sha256:")?;
+                for b in hash {
+                    write!(f, "{b:02x}")?;
+                }
+                Ok(())
+            }
+            Self::Jti(jti) => write!(f, "jti:{jti}"),
+        }
+    }
+}
+
+impl core::str::FromStr for RevocationKey {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(hex) = s.strip_prefix("sha256:") {
+            // `from_str_radix` alone would accept a sign, e.g. `+f`.
+            if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err("hash invalid");
+            }
+            let mut hash = [0_u8; 32];
+            for (i, x) in hash.iter_mut().enumerate() {
+                *x = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| "hash invalid")?;
+            }
+            Ok(Self::TokenHash(hash))
+        } else if let Some(jti) = s.strip_prefix("jti:") {
+            if jti.is_empty() {
+                return Err("jti empty");
+            }
+            Ok(Self::jti(jti))
+        } else {
+            Err("prefix unknown")
+        }
+    }
+}
+
+//
+pub trait RevocationStore {
+    /// `now` is in seconds since the Unix epoch.
+    fn is_revoked_at(&self, key: &RevocationKey, now: u64) -> bool;
+
+    fn is_revoked(&self, key: &RevocationKey) -> bool {
+        self.is_revoked_at(key, unix_now())
+    }
+}
+
+impl<S: RevocationStore + ?Sized> RevocationStore for std::sync::Arc<S> {
+    fn is_revoked_at(&self, key: &RevocationKey, now: u64) -> bool {
+        (**self).is_revoked_at(key, now)
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_secs())
+        .unwrap_or_default()
+}
+
+//
+/// Entries without an expiry are kept until removed.
+#[derive(Debug, Default)]
+pub struct MemoryRevocationStore {
+    entries: RwLock<HashMap<RevocationKey, Option<u64>>>,
+}
+
+impl MemoryRevocationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `expires_at` is in seconds since the Unix epoch.
+    pub fn revoke(&self, key: RevocationKey, expires_at: Option<u64>) {
+        self.entries
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(key, expires_at);
+    }
+
+    pub fn revoke_for(&self, key: RevocationKey, ttl: Duration) {
+        self.revoke(key, Some(unix_now().saturating_add(ttl.as_secs())))
+    }
+
+    pub fn remove(&self, key: &RevocationKey) {
+        self.entries
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(key);
+    }
+
+    /// Adds the entries, existing entries are kept.
+    pub fn load(&self, entries: impl IntoIterator<Item = (RevocationKey, Option<u64>)>) {
+        self.entries
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .extend(entries);
+    }
+
+    /// Replaces all entries.
+    pub fn replace(&self, entries: impl IntoIterator<Item = (RevocationKey, Option<u64>)>) {
+        let entries = entries.into_iter().collect();
+        *self.entries.write().unwrap_or_else(|err| err.into_inner()) = entries;
+    }
+
+    pub fn prune_at(&self, now: u64) {
+        self.entries
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .retain(|_, expires_at| expires_at.map(|x| x > now).unwrap_or(true));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl RevocationStore for MemoryRevocationStore {
+    fn is_revoked_at(&self, key: &RevocationKey, now: u64) -> bool {
+        match self
+            .entries
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(key)
+        {
+            Some(Some(expires_at)) => *expires_at > now,
+            Some(None) => true,
+            None => false,
+        }
+    }
+}
+
+//
+/// Reads a deny-list file, one [`RevocationKey`] per line optionally followed by the expiry in
+/// seconds since the Unix epoch. Blank lines and lines starting with `#` are skipped.
+///
+/// ```text
+/// # revoked 2024-05-01
+/// sha256:9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08 1714608000
+/// jti:2d9a1f3c
+/// ```
+#[derive(Debug)]
+pub struct FileRevocationStore {
+    path: PathBuf,
+    modified: Mutex<Option<SystemTime>>,
+    store: MemoryRevocationStore,
+}
+
+impl FileRevocationStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FileRevocationError> {
+        let s = Self {
+            path: path.as_ref().to_owned(),
+            modified: Mutex::new(None),
+            store: MemoryRevocationStore::new(),
+        };
+        s.reload()?;
+        Ok(s)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reloads the file when its modification time changed, returns whether it was reloaded.
+    pub fn refresh(&self) -> Result<bool, FileRevocationError> {
+        let modified = fs::metadata(&self.path)
+            .and_then(|x| x.modified())
+            .map_err(FileRevocationError::Io)?;
+        if *self.modified.lock().unwrap_or_else(|err| err.into_inner()) == Some(modified) {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Entries are replaced only when the whole file parses.
+    pub fn reload(&self) -> Result<(), FileRevocationError> {
+        let modified = fs::metadata(&self.path)
+            .and_then(|x| x.modified())
+            .map_err(FileRevocationError::Io)?;
+        let content = fs::read_to_string(&self.path).map_err(FileRevocationError::Io)?;
+        let entries = parse_file(&content)?;
+
+        self.store.replace(entries);
+        *self.modified.lock().unwrap_or_else(|err| err.into_inner()) = Some(modified);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.store.is_empty()
+    }
+}
+
+impl RevocationStore for FileRevocationStore {
+    fn is_revoked_at(&self, key: &RevocationKey, now: u64) -> bool {
+        self.store.is_revoked_at(key, now)
+    }
+}
+
+fn parse_file(content: &str) -> Result<Vec<(RevocationKey, Option<u64>)>, FileRevocationError> {
+    content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_no, line)| {
+            let invalid = |msg| FileRevocationError::LineInvalid(line_no, msg);
+            let mut parts = line.split_ascii_whitespace();
+            let key = parts
+                .next()
+                .unwrap_or_default()
+                .parse::<RevocationKey>()
+                .map_err(invalid)?;
+            let expires_at = parts
+                .next()
+                .map(|x| x.parse::<u64>().map_err(|_| invalid("expiry invalid")))
+                .transpose()?;
+            if parts.next().is_some() {
+                return Err(invalid("too many fields"));
+            }
+            Ok((key, expires_at))
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub enum FileRevocationError {
+    Io(io::Error),
+    /// The 1-based line number.
+    LineInvalid(usize, &'static str),
+}
+
+impl core::fmt::Display for FileRevocationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl std::error::Error for FileRevocationError {}
+
+//
+#[derive(Debug, Default)]
+pub struct RevocationMetrics {
+    checks: AtomicU64,
+    hits: AtomicU64,
+}
+
+impl RevocationMetrics {
+    /// Bearer credentials looked up.
+    pub fn checks(&self) -> u64 {
+        self.checks.load(Ordering::Relaxed)
+    }
+
+    /// Bearer credentials rejected as revoked.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+}
+
+/// Wraps a [`Verifier`], revoked Bearer tokens are rejected with [`Rejection::Invalid`]
+/// before reaching it. Other credentials are passed through.
+#[derive(Debug)]
+pub struct RevocationChecked<V, S> {
+    inner: V,
+    store: S,
+    metrics: RevocationMetrics,
+}
+
+impl<V, S: RevocationStore> RevocationChecked<V, S> {
+    pub fn new(inner: V, store: S) -> Self {
+        Self {
+            inner,
+            store,
+            metrics: RevocationMetrics::default(),
+        }
+    }
+
+    pub fn inner(&self) -> &V {
+        &self.inner
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn metrics(&self) -> &RevocationMetrics {
+        &self.metrics
+    }
+
+    /// `Err` is the challenge to send, `error="invalid_token"`.
+    pub fn check(&self, credentials: &Credentials, realm: &str) -> Result<(), Challenge> {
+        self.check_at(credentials, realm, unix_now())
+    }
+
+    pub fn check_at(
+        &self,
+        credentials: &Credentials,
+        realm: &str,
+        now: u64,
+    ) -> Result<(), Challenge> {
+        self.metrics.checks.fetch_add(1, Ordering::Relaxed);
+
+        let (hash, jti) = RevocationKey::of(credentials);
+        if self.store.is_revoked_at(&hash, now)
+            || jti
+                .map(|x| self.store.is_revoked_at(&x, now))
+                .unwrap_or(false)
+        {
+            self.metrics.hits.fetch_add(1, Ordering::Relaxed);
+
+            let mut c = Challenge::new(realm);
+            c.error = Some(ERROR_INVALID_TOKEN.into());
+            c.error_description = Some("The access token has been revoked".into());
+            return Err(c);
+        }
+        Ok(())
+    }
+}
+
+impl<V: Verifier, S: RevocationStore> Verifier for RevocationChecked<V, S> {
+    type Identity = V::Identity;
+
+    fn verify(
+        &self,
+        credentials: &AnyCredentials,
+        ctx: &VerifyContext<'_>,
+    ) -> Result<Self::Identity, Rejection> {
+        if let Some(c) = credentials.as_bearer() {
+            self.check(c, ctx.realm.unwrap_or_default())
+                .map_err(|_| Rejection::Invalid)?;
+        }
+        self.inner.verify(credentials, ctx)
+    }
+
+    fn reject(&self, rejection: Rejection, ctx: &VerifyContext<'_>) -> Rejection {
+        self.inner.reject(rejection, ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString as _;
+
+    // {"alg":"none"}.{"jti":"abc"}.
+    const JWT: &str = "eyJhbGciOiJub25lIn0.eyJqdGkiOiJhYmMifQ.";
+
+    #[test]
+    fn test_key() {
+        let (hash, jti) = RevocationKey::of(&Credentials::new(JWT));
+        assert_eq!(jti, Some(RevocationKey::jti("abc")));
+        assert_eq!(hash.to_string().parse::<RevocationKey>().unwrap(), hash);
+        assert_eq!(
+            RevocationKey::token("test").to_string(),
+            "sha256:9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+        );
+
+        assert_eq!(RevocationKey::of(&Credentials::new("opaque")).1, None);
+        assert!("md5:00".parse::<RevocationKey>().is_err());
+        let signed = alloc::format!("sha256:+f{}", "0".repeat(62));
+        assert_eq!(signed.parse::<RevocationKey>(), Err("hash invalid"));
+    }
+
+    #[test]
+    fn test_memory_store() {
+        let store = MemoryRevocationStore::new();
+        store.revoke(RevocationKey::token("a"), Some(100));
+        store.load([(RevocationKey::jti("abc"), None)]);
+
+        assert!(store.is_revoked_at(&RevocationKey::token("a"), 99));
+        assert!(!store.is_revoked_at(&RevocationKey::token("a"), 100));
+        assert!(store.is_revoked_at(&RevocationKey::jti("abc"), u64::MAX));
+
+        store.prune_at(100);
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_file_store() {
+        let path = std::env::temp_dir().join(format!(
+            "http-authentication-revocation-{}.txt",
+            std::process::id()
+        ));
+        fs::write(
+            &path,
+            "# comment\n\nsha256:9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08 200\njti:abc\n",
+        )
+        .unwrap();
+
+        let store = FileRevocationStore::open(&path).unwrap();
+        assert_eq!(store.len(), 2);
+        assert!(store.is_revoked_at(&RevocationKey::token("test"), 100));
+        assert!(!store.refresh().unwrap());
+
+        fs::write(&path, "jti:abc 1 2\n").unwrap();
+        match store.reload() {
+            Err(FileRevocationError::LineInvalid(1, _)) => {}
+            x => panic!("{x:?}"),
+        }
+        assert_eq!(store.len(), 2);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_revocation_checked() {
+        use crate::verifier::CredentialsSource;
+
+        struct AcceptAll;
+        impl Verifier for AcceptAll {
+            type Identity = ();
+
+            fn verify(
+                &self,
+                _credentials: &AnyCredentials,
+                _ctx: &VerifyContext<'_>,
+            ) -> Result<Self::Identity, Rejection> {
+                Ok(())
+            }
+        }
+
+        let store = MemoryRevocationStore::new();
+        store.revoke(RevocationKey::jti("abc"), None);
+        let verifier = RevocationChecked::new(AcceptAll, store);
+
+        let c = Credentials::new(JWT);
+        assert_eq!(
+            verifier.check_at(&c, "example", 0).unwrap_err().to_string(),
+            r#"Bearer realm="example", error="invalid_token", error_description="The access token has been revoked""#
+        );
+
+        let ctx = VerifyContext::new(CredentialsSource::Authorization);
+        match verifier.verify(&AnyCredentials::Bearer(c), &ctx) {
+            Err(Rejection::Invalid) => {}
+            x => panic!("{x:?}"),
+        }
+        verifier
+            .verify(&AnyCredentials::bearer("other"), &ctx)
+            .unwrap();
+
+        assert_eq!(verifier.metrics().checks(), 3);
+        assert_eq!(verifier.metrics().hits(), 2);
+    }
+}
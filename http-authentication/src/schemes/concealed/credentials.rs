@@ -0,0 +1,346 @@
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::str::{self, FromStr};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+
+use crate::{
+    parse_options::{is_whitespace, ParseErrorPosition, ParseOptions},
+    schemes::NAME_CONCEALED as NAME,
+    COMMA, D_Q_M, EQ_S, SP,
+};
+
+//
+pub(crate) const PARAM_KEY_ID: &str = "k";
+pub(crate) const PARAM_PUBLIC_KEY: &str = "a";
+pub(crate) const PARAM_PROOF: &str = "p";
+pub(crate) const PARAM_SIGNATURE_SCHEME: &str = "s";
+pub(crate) const PARAM_VERIFICATION: &str = "v";
+
+//
+/// The byte sequences are kept base64url encoded without padding, as sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub key_id: Box<str>,
+    pub public_key: Box<str>,
+    /// The signature.
+    pub proof: Box<str>,
+    /// A TLS SignatureScheme, see `SIGNATURE_SCHEME_*`.
+    pub signature_scheme: u16,
+    /// The last 16 bytes of the keying material.
+    pub verification: Box<str>,
+}
+
+impl Credentials {
+    pub fn new(
+        key_id: impl AsRef<str>,
+        public_key: impl AsRef<str>,
+        proof: impl AsRef<str>,
+        signature_scheme: u16,
+        verification: impl AsRef<str>,
+    ) -> Self {
+        Self {
+            key_id: key_id.as_ref().into(),
+            public_key: public_key.as_ref().into(),
+            proof: proof.as_ref().into(),
+            signature_scheme,
+            verification: verification.as_ref().into(),
+        }
+    }
+
+    /// Encodes the byte sequences.
+    pub fn from_raw(
+        key_id: &[u8],
+        public_key: &[u8],
+        proof: &[u8],
+        signature_scheme: u16,
+        verification: &[u8],
+    ) -> Self {
+        Self::new(
+            URL_SAFE_NO_PAD.encode(key_id),
+            URL_SAFE_NO_PAD.encode(public_key),
+            URL_SAFE_NO_PAD.encode(proof),
+            signature_scheme,
+            URL_SAFE_NO_PAD.encode(verification),
+        )
+    }
+
+    pub fn from_bytes(bytes: impl AsRef<[u8]>) -> Result<Self, CredentialsParseError> {
+        Self::from_bytes_with_options(bytes, &ParseOptions::default())
+    }
+
+    pub fn from_bytes_with_options(
+        bytes: impl AsRef<[u8]>,
+        options: &ParseOptions,
+    ) -> Result<Self, CredentialsParseError> {
+        let p = params(bytes.as_ref(), options)?;
+
+        Ok(Self::new(
+            p.key_id,
+            p.public_key,
+            p.proof,
+            p.signature_scheme,
+            p.verification,
+        ))
+    }
+
+    pub fn key_id_bytes(&self) -> Vec<u8> {
+        decode(&self.key_id)
+    }
+
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        decode(&self.public_key)
+    }
+
+    pub fn proof_bytes(&self) -> Vec<u8> {
+        decode(&self.proof)
+    }
+
+    pub fn verification_bytes(&self) -> Vec<u8> {
+        decode(&self.verification)
+    }
+
+    fn internal_to_string(&self) -> String {
+        format!(
+            "{NAME}{SP}{PARAM_KEY_ID}{EQ_S}{}{COMMA}{SP}{PARAM_PUBLIC_KEY}{EQ_S}{}{COMMA}{SP}{PARAM_PROOF}{EQ_S}{}{COMMA}{SP}{PARAM_SIGNATURE_SCHEME}{EQ_S}{}{COMMA}{SP}{PARAM_VERIFICATION}{EQ_S}{}",
+            self.key_id, self.public_key, self.proof, self.signature_scheme, self.verification
+        )
+    }
+}
+
+// The values were checked while parsing, `Credentials::new` is not checked.
+fn decode(s: &str) -> Vec<u8> {
+    URL_SAFE_NO_PAD.decode(s).unwrap_or_default()
+}
+
+//
+pub(crate) struct Params<'a> {
+    pub(crate) key_id: &'a str,
+    pub(crate) public_key: &'a str,
+    pub(crate) proof: &'a str,
+    pub(crate) signature_scheme: u16,
+    pub(crate) verification: &'a str,
+}
+
+/// Parses `Concealed k=<b64>, a=<b64>, p=<b64>, s=<int>, v=<b64>`, the values may be quoted,
+/// other params are ignored.
+pub(crate) fn params<'a>(
+    bytes: &'a [u8],
+    options: &ParseOptions,
+) -> Result<Params<'a>, CredentialsParseError> {
+    let (start, end) = if options.allow_surrounding_whitespace {
+        let start = bytes
+            .iter()
+            .position(|x| !is_whitespace(*x))
+            .unwrap_or(bytes.len());
+        let end = bytes
+            .iter()
+            .rposition(|x| !is_whitespace(*x))
+            .map(|x| x + 1)
+            .unwrap_or(start);
+        (start, end)
+    } else {
+        (0, bytes.len())
+    };
+
+    if end - start < NAME.len() + 1 {
+        return Err(CredentialsParseError::Other(
+            "too short",
+            ParseErrorPosition::from_input(bytes, start, end - start),
+        ));
+    }
+    if !bytes[start..start + NAME.len()].eq_ignore_ascii_case(NAME.as_bytes()) {
+        let scheme_len = bytes[start..end]
+            .iter()
+            .position(|x| *x == SP as u8)
+            .unwrap_or(end - start);
+        return Err(CredentialsParseError::SchemeMismatch(
+            ParseErrorPosition::from_input(bytes, start, scheme_len.max(1)),
+        ));
+    }
+    let offset = start + NAME.len();
+    if bytes[offset] != SP as u8 {
+        return Err(CredentialsParseError::OneSPMismatch(
+            ParseErrorPosition::from_input(bytes, offset, 1),
+        ));
+    }
+
+    let offset = offset + 1;
+    let params = str::from_utf8(&bytes[offset..end]).map_err(|err| {
+        CredentialsParseError::ParamsToStrFailed(
+            err,
+            ParseErrorPosition::from_input(bytes, offset + err.valid_up_to(), 1),
+        )
+    })?;
+
+    let mut key_id = None;
+    let mut public_key = None;
+    let mut proof = None;
+    let mut signature_scheme = None;
+    let mut verification = None;
+    let mut param_offset = offset;
+    for param in params.split(COMMA) {
+        let pos = || ParseErrorPosition::from_input(bytes, param_offset, param.len().max(1));
+        let (name, value) = param
+            .split_once(EQ_S)
+            .ok_or_else(|| CredentialsParseError::ParamInvalid(pos()))?;
+        let name = name.trim_matches(|x| is_whitespace(x as u8));
+        let value = value.trim_matches(|x| is_whitespace(x as u8));
+        let value = match value
+            .strip_prefix(D_Q_M)
+            .and_then(|x| x.strip_suffix(D_Q_M))
+        {
+            Some(x) => x,
+            None => value,
+        };
+
+        let byte_sequence = || {
+            if !value.is_empty() && URL_SAFE_NO_PAD.decode(value).is_ok() {
+                Ok(Some(value))
+            } else {
+                Err(CredentialsParseError::ParamInvalid(pos()))
+            }
+        };
+        if name.eq_ignore_ascii_case(PARAM_KEY_ID) {
+            key_id = byte_sequence()?;
+        } else if name.eq_ignore_ascii_case(PARAM_PUBLIC_KEY) {
+            public_key = byte_sequence()?;
+        } else if name.eq_ignore_ascii_case(PARAM_PROOF) {
+            proof = byte_sequence()?;
+        } else if name.eq_ignore_ascii_case(PARAM_VERIFICATION) {
+            verification = byte_sequence()?;
+        } else if name.eq_ignore_ascii_case(PARAM_SIGNATURE_SCHEME) {
+            signature_scheme = Some(
+                value
+                    .parse::<u16>()
+                    .map_err(|_| CredentialsParseError::ParamInvalid(pos()))?,
+            );
+        }
+        param_offset += param.len() + 1;
+    }
+
+    let missing = |name| {
+        CredentialsParseError::ParamMissing(name, ParseErrorPosition::from_input(bytes, end, 0))
+    };
+    Ok(Params {
+        key_id: key_id.ok_or_else(|| missing(PARAM_KEY_ID))?,
+        public_key: public_key.ok_or_else(|| missing(PARAM_PUBLIC_KEY))?,
+        proof: proof.ok_or_else(|| missing(PARAM_PROOF))?,
+        signature_scheme: signature_scheme.ok_or_else(|| missing(PARAM_SIGNATURE_SCHEME))?,
+        verification: verification.ok_or_else(|| missing(PARAM_VERIFICATION))?,
+    })
+}
+
+//
+#[derive(Debug)]
+pub enum CredentialsParseError {
+    SchemeMismatch(ParseErrorPosition),
+    OneSPMismatch(ParseErrorPosition),
+    ParamsToStrFailed(str::Utf8Error, ParseErrorPosition),
+    ParamInvalid(ParseErrorPosition),
+    ParamMissing(&'static str, ParseErrorPosition),
+    Other(&'static str, ParseErrorPosition),
+}
+
+impl CredentialsParseError {
+    pub fn position(&self) -> &ParseErrorPosition {
+        match self {
+            Self::SchemeMismatch(pos)
+            | Self::OneSPMismatch(pos)
+            | Self::ParamsToStrFailed(_, pos)
+            | Self::ParamInvalid(pos)
+            | Self::ParamMissing(_, pos)
+            | Self::Other(_, pos) => pos,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::SchemeMismatch(_) => "SchemeMismatch",
+            Self::OneSPMismatch(_) => "OneSPMismatch",
+            Self::ParamsToStrFailed(_, _) => "ParamsToStrFailed",
+            Self::ParamInvalid(_) => "ParamInvalid",
+            Self::ParamMissing(_, _) => "ParamMissing",
+            Self::Other(_, _) => "Other",
+        }
+    }
+}
+
+impl core::fmt::Display for CredentialsParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CredentialsParseError {}
+
+//
+impl core::fmt::Display for Credentials {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.internal_to_string())
+    }
+}
+
+//
+impl FromStr for Credentials {
+    type Err = CredentialsParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes(s.as_bytes())
+    }
+}
+
+//
+//
+//
+// Shaped after https://www.rfc-editor.org/rfc/rfc9729.html#section-4, the values are not a valid proof.
+#[cfg(test)]
+pub(crate) const DEMO_CREDENTIALS_STR: &str = "Concealed k=YmFzZW1lbnQ, a=VGhpcyBpcyBh-HB1YmxpYyBrZXkgaW4gdXNl_GhlcmU, p=SW4gdGhlIHJlYWwgd29ybGQsIHRoaXMgd291bGQgYmUgYSBzaWduYXR1cmU, s=2055, v=dmVyaWZpY2F0aW9uX3ZhbHVl";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString as _;
+
+    #[test]
+    fn test_parse_and_render() {
+        let c = DEMO_CREDENTIALS_STR.parse::<Credentials>().unwrap();
+        assert_eq!(c.key_id_bytes(), b"basement");
+        assert_eq!(c.signature_scheme, 2055);
+        assert_eq!(c.verification_bytes(), b"verification_value");
+        assert_eq!(c.to_string(), DEMO_CREDENTIALS_STR);
+
+        let c = Credentials::from_str(r#"Concealed v="dg", s=1027, p=cA, a="YQ", k=aw"#).unwrap();
+        assert_eq!(c, Credentials::from_raw(b"k", b"a", b"p", 1027, b"v"));
+
+        //
+        match Credentials::from_str("Concealed k=aw, a=YQ, p=cA, v=dg") {
+            Err(CredentialsParseError::ParamMissing(PARAM_SIGNATURE_SCHEME, pos)) => {
+                assert_eq!(pos.offset, 32);
+            }
+            x => panic!("{x:?}"),
+        }
+
+        match Credentials::from_str("Concealed k=a=, a=YQ, p=cA, s=1, v=dg") {
+            Err(CredentialsParseError::ParamInvalid(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(10, "k=a="));
+            }
+            x => panic!("{x:?}"),
+        }
+
+        match Credentials::from_str("Concealed k=aw, a=YQ, p=cA, s=65536, v=dg") {
+            Err(CredentialsParseError::ParamInvalid(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(27, " s=65536"));
+            }
+            x => panic!("{x:?}"),
+        }
+
+        match Credentials::from_str("Bearer k=aw") {
+            Err(CredentialsParseError::SchemeMismatch(pos)) => {
+                assert_eq!(pos, ParseErrorPosition::new(0, "Bearer"));
+            }
+            x => panic!("{x:?}"),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::parse_options::ParseOptions;
+
+use super::credentials::params;
+use super::{Credentials, CredentialsParseError};
+
+//
+/// Borrowed [`Credentials`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialsRef<'a> {
+    pub key_id: &'a str,
+    pub public_key: &'a str,
+    pub proof: &'a str,
+    pub signature_scheme: u16,
+    pub verification: &'a str,
+}
+
+impl<'a> CredentialsRef<'a> {
+    pub fn new(
+        key_id: &'a str,
+        public_key: &'a str,
+        proof: &'a str,
+        signature_scheme: u16,
+        verification: &'a str,
+    ) -> Self {
+        Self {
+            key_id,
+            public_key,
+            proof,
+            signature_scheme,
+            verification,
+        }
+    }
+
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, CredentialsParseError> {
+        Self::from_bytes_with_options(bytes, &ParseOptions::default())
+    }
+
+    pub fn from_bytes_with_options(
+        bytes: &'a [u8],
+        options: &ParseOptions,
+    ) -> Result<Self, CredentialsParseError> {
+        params(bytes, options).map(|p| {
+            Self::new(
+                p.key_id,
+                p.public_key,
+                p.proof,
+                p.signature_scheme,
+                p.verification,
+            )
+        })
+    }
+
+    pub fn into_owned(self) -> Credentials {
+        Credentials::new(
+            self.key_id,
+            self.public_key,
+            self.proof,
+            self.signature_scheme,
+            self.verification,
+        )
+    }
+}
+
+impl From<CredentialsRef<'_>> for Credentials {
+    fn from(c: CredentialsRef<'_>) -> Self {
+        c.into_owned()
+    }
+}
+
+impl<'a> From<&'a Credentials> for CredentialsRef<'a> {
+    fn from(c: &'a Credentials) -> Self {
+        Self::new(
+            &c.key_id,
+            &c.public_key,
+            &c.proof,
+            c.signature_scheme,
+            &c.verification,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schemes::concealed::DEMO_CREDENTIALS_STR;
+
+    #[test]
+    fn test_parse() {
+        let c = CredentialsRef::from_bytes(DEMO_CREDENTIALS_STR.as_bytes()).unwrap();
+        assert_eq!(c.key_id, "YmFzZW1lbnQ");
+
+        let c = c.into_owned();
+        assert_eq!(c.signature_scheme, 2055);
+    }
+}
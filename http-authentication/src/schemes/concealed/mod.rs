@@ -0,0 +1,30 @@
+//! [The Concealed HTTP Authentication Scheme](https://www.rfc-editor.org/rfc/rfc9729.html)
+//!
+//! Only the credentials are implemented, servers do not send a challenge so that unauthenticated
+//! clients cannot tell the resource exists. The proof is a signature over TLS keying material.
+
+//
+pub mod credentials;
+
+#[cfg(test)]
+pub(crate) use credentials::DEMO_CREDENTIALS_STR;
+pub use credentials::{Credentials, CredentialsParseError};
+
+//
+pub mod credentials_ref;
+
+pub use credentials_ref::CredentialsRef;
+
+//
+pub mod verifier;
+
+pub use verifier::{
+    exporter_context, signature_input, ConcealedIdentity, ConcealedVerifier, RequestTarget,
+    SignatureVerifier,
+};
+
+//
+/// TLS SignatureScheme values, Ref https://www.iana.org/assignments/tls-parameters/tls-parameters.xhtml#tls-signaturescheme
+pub const SIGNATURE_SCHEME_ECDSA_SECP256R1_SHA256: u16 = 0x0403;
+pub const SIGNATURE_SCHEME_RSA_PSS_RSAE_SHA256: u16 = 0x0804;
+pub const SIGNATURE_SCHEME_ED25519: u16 = 0x0807;
@@ -0,0 +1,297 @@
+//! Server-side verification, Ref https://www.rfc-editor.org/rfc/rfc9729.html#section-3
+//!
+//! The keying material is exported from the TLS connection by the caller, the signature
+//! algorithms are provided by a [`SignatureVerifier`].
+
+use alloc::{boxed::Box, vec::Vec};
+
+use super::Credentials;
+use crate::verifier::Rejection;
+
+//
+pub const EXPORTER_LABEL: &str = "EXPORTER-HTTP-Concealed-Authentication";
+/// The first 32 bytes are signed, the last 16 are sent as `v`.
+pub const EXPORTER_OUTPUT_LEN: usize = 48;
+pub const SIGNATURE_CONTEXT: &str = "HTTP Concealed Authentication";
+
+//
+/// The origin and realm the credentials are bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestTarget<'a> {
+    pub scheme: &'a str,
+    pub host: &'a str,
+    pub port: u16,
+    pub realm: &'a str,
+}
+
+impl<'a> RequestTarget<'a> {
+    pub fn new(scheme: &'a str, host: &'a str, port: u16) -> Self {
+        Self {
+            scheme,
+            host,
+            port,
+            realm: "",
+        }
+    }
+
+    pub fn with_realm(mut self, realm: &'a str) -> Self {
+        self.realm = realm;
+        self
+    }
+}
+
+/// The context passed to the keying material exporter.
+pub fn exporter_context(
+    signature_scheme: u16,
+    key_id: &[u8],
+    public_key: &[u8],
+    target: &RequestTarget<'_>,
+) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(
+        2 + key_id.len() + public_key.len() + target.scheme.len() + target.host.len() + 32,
+    );
+    buf.extend_from_slice(&signature_scheme.to_be_bytes());
+    push_with_len(&mut buf, key_id);
+    push_with_len(&mut buf, public_key);
+    push_with_len(&mut buf, target.scheme.as_bytes());
+    push_with_len(&mut buf, target.host.as_bytes());
+    buf.extend_from_slice(&target.port.to_be_bytes());
+    push_with_len(&mut buf, target.realm.as_bytes());
+    buf
+}
+
+/// The signed message, built from the first 32 bytes of the exporter output.
+pub fn signature_input(exporter_output: &[u8]) -> Vec<u8> {
+    let signed = &exporter_output[..exporter_output.len().min(32)];
+    let mut buf = Vec::with_capacity(64 + SIGNATURE_CONTEXT.len() + 1 + signed.len());
+    buf.extend_from_slice(&[0x20; 64]);
+    buf.extend_from_slice(SIGNATURE_CONTEXT.as_bytes());
+    buf.push(0);
+    buf.extend_from_slice(signed);
+    buf
+}
+
+// QUIC variable-length integer, Ref https://www.rfc-editor.org/rfc/rfc9000.html#section-16
+fn push_with_len(buf: &mut Vec<u8>, bytes: &[u8]) {
+    let len = bytes.len() as u64;
+    match len {
+        0..=0x3f => buf.push(len as u8),
+        0x40..=0x3fff => buf.extend_from_slice(&(0x4000 | len as u16).to_be_bytes()),
+        0x4000..=0x3fff_ffff => buf.extend_from_slice(&(0x8000_0000 | len as u32).to_be_bytes()),
+        _ => buf.extend_from_slice(&(0xc000_0000_0000_0000 | len).to_be_bytes()),
+    }
+    buf.extend_from_slice(bytes);
+}
+
+//
+/// Checks `signature` over `message` with the public key, as sent in `a`.
+pub trait SignatureVerifier {
+    fn verify(
+        &self,
+        signature_scheme: u16,
+        public_key: &[u8],
+        message: &[u8],
+        signature: &[u8],
+    ) -> bool;
+}
+
+impl<F> SignatureVerifier for F
+where
+    F: Fn(u16, &[u8], &[u8], &[u8]) -> bool,
+{
+    fn verify(
+        &self,
+        signature_scheme: u16,
+        public_key: &[u8],
+        message: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        self(signature_scheme, public_key, message, signature)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConcealedIdentity {
+    pub key_id: Vec<u8>,
+}
+
+//
+#[derive(Debug, Clone)]
+struct RegisteredKey {
+    key_id: Box<[u8]>,
+    public_key: Box<[u8]>,
+    signature_scheme: u16,
+}
+
+/// Accepts the registered keys, each key ID is bound to one public key and signature scheme.
+#[derive(Debug, Clone)]
+pub struct ConcealedVerifier<S> {
+    keys: Vec<RegisteredKey>,
+    signature_verifier: S,
+}
+
+impl<S: SignatureVerifier> ConcealedVerifier<S> {
+    pub fn new(signature_verifier: S) -> Self {
+        Self {
+            keys: Vec::new(),
+            signature_verifier,
+        }
+    }
+
+    pub fn key(mut self, key_id: &[u8], public_key: &[u8], signature_scheme: u16) -> Self {
+        self.keys.push(RegisteredKey {
+            key_id: key_id.into(),
+            public_key: public_key.into(),
+            signature_scheme,
+        });
+        self
+    }
+
+    /// `export` is called with the label, the context and the output to fill, it returns `false`
+    /// when the connection cannot export keying material, e.g. it is not TLS 1.3.
+    ///
+    /// All failures are [`Rejection::Invalid`], the response should not tell them apart.
+    pub fn verify<E>(
+        &self,
+        credentials: &Credentials,
+        target: &RequestTarget<'_>,
+        export: E,
+    ) -> Result<ConcealedIdentity, Rejection>
+    where
+        E: FnOnce(&[u8], &[u8], &mut [u8]) -> bool,
+    {
+        let key_id = credentials.key_id_bytes();
+        let public_key = credentials.public_key_bytes();
+
+        if !self.keys.iter().any(|x| {
+            *x.key_id == *key_id
+                && *x.public_key == *public_key
+                && x.signature_scheme == credentials.signature_scheme
+        }) {
+            return Err(Rejection::Invalid);
+        }
+        let signature_scheme = credentials.signature_scheme;
+
+        let context = exporter_context(signature_scheme, &key_id, &public_key, target);
+        let mut output = [0_u8; EXPORTER_OUTPUT_LEN];
+        if !export(EXPORTER_LABEL.as_bytes(), &context, &mut output) {
+            return Err(Rejection::Other("keying material unavailable"));
+        }
+
+        if !constant_time_eq(&output[32..], &credentials.verification_bytes()) {
+            return Err(Rejection::Invalid);
+        }
+        if !self.signature_verifier.verify(
+            signature_scheme,
+            &public_key,
+            &signature_input(&output),
+            &credentials.proof_bytes(),
+        ) {
+            return Err(Rejection::Invalid);
+        }
+
+        Ok(ConcealedIdentity { key_id })
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0_u8, |acc, (x, y)| acc | (x ^ y));
+    core::hint::black_box(diff) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schemes::concealed::SIGNATURE_SCHEME_ED25519;
+
+    // Stands in for the TLS exporter, the output only depends on the label and the context.
+    fn export(label: &[u8], context: &[u8], out: &mut [u8]) -> bool {
+        for (i, x) in out.iter_mut().enumerate() {
+            *x = label
+                .iter()
+                .chain(context)
+                .fold(i as u8, |acc, b| acc.rotate_left(3) ^ b);
+        }
+        true
+    }
+
+    // The "signature" is the message itself.
+    fn signature_verifier(_: u16, _: &[u8], message: &[u8], signature: &[u8]) -> bool {
+        message == signature
+    }
+
+    fn credentials(target: &RequestTarget<'_>) -> Credentials {
+        let context = exporter_context(SIGNATURE_SCHEME_ED25519, b"kid", b"pub", target);
+        let mut output = [0; EXPORTER_OUTPUT_LEN];
+        export(EXPORTER_LABEL.as_bytes(), &context, &mut output);
+        Credentials::from_raw(
+            b"kid",
+            b"pub",
+            &signature_input(&output),
+            SIGNATURE_SCHEME_ED25519,
+            &output[32..],
+        )
+    }
+
+    #[test]
+    fn test_verify() {
+        let verifier = ConcealedVerifier::new(signature_verifier).key(
+            b"kid",
+            b"pub",
+            SIGNATURE_SCHEME_ED25519,
+        );
+        let target = RequestTarget::new("https", "example.com", 443);
+        let c = credentials(&target);
+
+        assert_eq!(verifier.verify(&c, &target, export).unwrap().key_id, b"kid");
+
+        // Bound to the origin.
+        let other = RequestTarget::new("https", "example.com", 8443);
+        match verifier.verify(&c, &other, export) {
+            Err(Rejection::Invalid) => {}
+            x => panic!("{x:?}"),
+        }
+        match verifier.verify(&c, &target.with_realm("foo"), export) {
+            Err(Rejection::Invalid) => {}
+            x => panic!("{x:?}"),
+        }
+
+        let mut forged = c.clone();
+        forged.proof = credentials(&other).proof;
+        match verifier.verify(&forged, &target, export) {
+            Err(Rejection::Invalid) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match verifier.verify(&c, &target, |_: &[u8], _: &[u8], _: &mut [u8]| false) {
+            Err(Rejection::Other(_)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        let verifier = ConcealedVerifier::new(signature_verifier).key(b"kid", b"other", 2055);
+        match verifier.verify(&c, &target, export) {
+            Err(Rejection::Invalid) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[test]
+    fn test_exporter_context() {
+        let target = RequestTarget::new("https", "a", 443).with_realm("r");
+        assert_eq!(
+            exporter_context(0x0807, &[1; 64], b"", &target),
+            [
+                &[0x08, 0x07, 0x40, 64][..],
+                &[1; 64],
+                &[0, 5],
+                b"https",
+                &[1, b'a', 0x01, 0xbb, 1, b'r'],
+            ]
+            .concat()
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use alloc::{boxed::Box, string::String, vec};
+
+use http_auth::ChallengeRef;
+
+use crate::{
+    challenge::push_quoted_string, schemes::NAME_DIGEST as NAME,
+    CHALLENGE_PARAM_REALM as PARAM_REALM, COMMA, EQ_S, SP,
+};
+
+use super::Algorithm;
+
+//
+pub(crate) const PARAM_DOMAIN: &str = "domain";
+pub(crate) const PARAM_NONCE: &str = "nonce";
+pub(crate) const PARAM_OPAQUE: &str = "opaque";
+pub(crate) const PARAM_STALE: &str = "stale";
+pub(crate) const PARAM_ALGORITHM: &str = "algorithm";
+pub(crate) const PARAM_QOP: &str = "qop";
+pub(crate) const PARAM_CHARSET: &str = "charset";
+pub(crate) const PARAM_USERHASH: &str = "userhash";
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub realm: Box<str>,
+    pub domain: Option<Box<str>>,
+    pub nonce: Box<str>,
+    pub opaque: Option<Box<str>>,
+    pub stale: bool,
+    /// `None` means MD5.
+    pub algorithm: Option<Algorithm>,
+    pub qop: Option<Box<str>>,
+    pub charset: Option<Box<str>>,
+    pub userhash: bool,
+}
+
+impl Challenge {
+    pub fn new(realm: impl AsRef<str>, nonce: impl AsRef<str>) -> Self {
+        Self {
+            realm: realm.as_ref().into(),
+            domain: None,
+            nonce: nonce.as_ref().into(),
+            opaque: None,
+            stale: false,
+            algorithm: None,
+            qop: None,
+            charset: None,
+            userhash: false,
+        }
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm.unwrap_or_default()
+    }
+
+    fn internal_to_string(&self) -> String {
+        let mut s = String::with_capacity(60);
+        s.push_str(NAME);
+        s.push(SP);
+
+        s.push_str(PARAM_REALM);
+        s.push(EQ_S);
+        push_quoted_string(&mut s, &self.realm);
+
+        let mut params = vec![];
+        if let Some(qop) = &self.qop {
+            params.push((PARAM_QOP, true, qop.as_ref()));
+        }
+        if let Some(algorithm) = &self.algorithm {
+            params.push((PARAM_ALGORITHM, false, algorithm.as_str()));
+        }
+        params.push((PARAM_NONCE, true, self.nonce.as_ref()));
+        if let Some(opaque) = &self.opaque {
+            params.push((PARAM_OPAQUE, true, opaque.as_ref()));
+        }
+        if let Some(domain) = &self.domain {
+            params.push((PARAM_DOMAIN, true, domain.as_ref()));
+        }
+        if self.stale {
+            params.push((PARAM_STALE, false, "true"));
+        }
+        if let Some(charset) = &self.charset {
+            params.push((PARAM_CHARSET, false, charset.as_ref()));
+        }
+        if self.userhash {
+            params.push((PARAM_USERHASH, false, "true"));
+        }
+
+        for (k, is_quoted, v) in params {
+            s.push(COMMA);
+            s.push(SP);
+            s.push_str(k);
+            s.push(EQ_S);
+            if is_quoted {
+                push_quoted_string(&mut s, v);
+            } else {
+                s.push_str(v);
+            }
+        }
+
+        s
+    }
+}
+
+//
+impl TryFrom<&ChallengeRef<'_>> for Challenge {
+    type Error = ChallengeParseError;
+
+    fn try_from(c: &ChallengeRef<'_>) -> Result<Self, Self::Error> {
+        super::ChallengeRef::try_from(c).map(Into::into)
+    }
+}
+
+//
+#[derive(Debug)]
+pub enum ChallengeParseError {
+    SchemeMismatch,
+    NonceMissing,
+    AlgorithmUnknown,
+    Other(&'static str),
+}
+
+impl core::fmt::Display for ChallengeParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChallengeParseError {}
+
+//
+impl core::fmt::Display for Challenge {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.internal_to_string())
+    }
+}
+
+//
+//
+//
+// Ref https://www.rfc-editor.org/rfc/rfc7616.html#section-3.9.1
+#[cfg(test)]
+pub(crate) const DEMO_CHALLENGE_STR: &str = r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#;
+#[cfg(test)]
+pub(crate) const DEMO_CHALLENGE_REALM_STR: &str = "http-auth@example.org";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString as _;
+
+    #[test]
+    fn test_render() {
+        let mut c = Challenge::new(
+            DEMO_CHALLENGE_REALM_STR,
+            "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v",
+        );
+        c.qop = Some("auth, auth-int".into());
+        c.algorithm = Some(Algorithm::Sha256);
+        c.opaque = Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS".into());
+        assert_eq!(c.to_string(), DEMO_CHALLENGE_STR);
+
+        let mut c = Challenge::new("foo", "abc");
+        c.stale = true;
+        assert_eq!(c.algorithm(), Algorithm::Md5);
+        assert_eq!(
+            c.to_string(),
+            r#"Digest realm="foo", nonce="abc", stale=true"#
+        );
+    }
+}
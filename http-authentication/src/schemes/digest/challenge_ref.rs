@@ -0,0 +1,152 @@
+use crate::{
+    challenge::unescape_quoted_string, schemes::NAME_DIGEST as NAME,
+    CHALLENGE_PARAM_REALM as PARAM_REALM,
+};
+
+use super::challenge::{
+    PARAM_ALGORITHM, PARAM_CHARSET, PARAM_DOMAIN, PARAM_NONCE, PARAM_OPAQUE, PARAM_QOP,
+    PARAM_STALE, PARAM_USERHASH,
+};
+use super::{Algorithm, Challenge, ChallengeParseError};
+
+//
+/// Borrowed [`Challenge`], the values are escaped as they appear in the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChallengeRef<'a> {
+    pub realm: &'a str,
+    pub domain: Option<&'a str>,
+    pub nonce: &'a str,
+    pub opaque: Option<&'a str>,
+    pub stale: bool,
+    pub algorithm: Option<Algorithm>,
+    pub qop: Option<&'a str>,
+    pub charset: Option<&'a str>,
+    pub userhash: bool,
+}
+
+impl<'a> ChallengeRef<'a> {
+    pub fn new(realm: &'a str, nonce: &'a str) -> Self {
+        Self {
+            realm,
+            domain: None,
+            nonce,
+            opaque: None,
+            stale: false,
+            algorithm: None,
+            qop: None,
+            charset: None,
+            userhash: false,
+        }
+    }
+
+    /// Unescapes the quoted-string values.
+    pub fn into_owned(self) -> Challenge {
+        Challenge {
+            realm: unescape_quoted_string(self.realm),
+            domain: self.domain.map(unescape_quoted_string),
+            nonce: unescape_quoted_string(self.nonce),
+            opaque: self.opaque.map(unescape_quoted_string),
+            stale: self.stale,
+            algorithm: self.algorithm,
+            qop: self.qop.map(unescape_quoted_string),
+            charset: self.charset.map(unescape_quoted_string),
+            userhash: self.userhash,
+        }
+    }
+}
+
+impl From<ChallengeRef<'_>> for Challenge {
+    fn from(c: ChallengeRef<'_>) -> Self {
+        c.into_owned()
+    }
+}
+
+impl<'a> TryFrom<&http_auth::ChallengeRef<'a>> for ChallengeRef<'a> {
+    type Error = ChallengeParseError;
+
+    fn try_from(c: &http_auth::ChallengeRef<'a>) -> Result<Self, Self::Error> {
+        if !c.scheme.eq_ignore_ascii_case(NAME) {
+            return Err(ChallengeParseError::SchemeMismatch);
+        }
+
+        Self::from_params(|name| {
+            c.params
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_escaped())
+        })
+    }
+}
+
+impl<'a> ChallengeRef<'a> {
+    /// `find` returns the escaped value of a param by its case-insensitive name.
+    pub(crate) fn from_params(
+        find: impl Fn(&str) -> Option<&'a str>,
+    ) -> Result<Self, ChallengeParseError> {
+        let is_true = |name: &str| {
+            find(name)
+                .map(|x| x.eq_ignore_ascii_case("true"))
+                .unwrap_or(false)
+        };
+
+        let realm = find(PARAM_REALM).unwrap_or_default();
+        let nonce = find(PARAM_NONCE).ok_or(ChallengeParseError::NonceMissing)?;
+        let algorithm = find(PARAM_ALGORITHM)
+            .map(|x| x.parse::<Algorithm>())
+            .transpose()
+            .map_err(|_| ChallengeParseError::AlgorithmUnknown)?;
+
+        Ok(Self {
+            realm,
+            domain: find(PARAM_DOMAIN),
+            nonce,
+            opaque: find(PARAM_OPAQUE),
+            stale: is_true(PARAM_STALE),
+            algorithm,
+            qop: find(PARAM_QOP),
+            charset: find(PARAM_CHARSET),
+            userhash: is_true(PARAM_USERHASH),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http_auth::ParamValue;
+
+    #[test]
+    fn test_try_from_challenge_ref() {
+        let mut c = http_auth::ChallengeRef::new(NAME);
+        c.params
+            .push((PARAM_REALM, ParamValue::try_from_escaped("foo").unwrap()));
+        c.params
+            .push((PARAM_NONCE, ParamValue::try_from_escaped("abc").unwrap()));
+        c.params.push((
+            PARAM_ALGORITHM,
+            ParamValue::try_from_escaped("SHA-256").unwrap(),
+        ));
+        c.params
+            .push((PARAM_STALE, ParamValue::try_from_escaped("TRUE").unwrap()));
+
+        let c = ChallengeRef::try_from(&c).unwrap();
+        assert_eq!(c.realm, "foo");
+        assert_eq!(c.nonce, "abc");
+        assert_eq!(c.algorithm, Some(Algorithm::Sha256));
+        assert!(c.stale);
+        assert!(!c.userhash);
+
+        let c = c.into_owned();
+        assert_eq!(c.nonce, "abc".into());
+
+        //
+        let mut c = http_auth::ChallengeRef::new(NAME);
+        c.params
+            .push((PARAM_REALM, ParamValue::try_from_escaped("foo").unwrap()));
+        match ChallengeRef::try_from(&c) {
+            Err(ChallengeParseError::NonceMissing) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+}
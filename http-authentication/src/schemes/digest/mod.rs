@@ -0,0 +1,20 @@
+//! [HTTP Digest Access Authentication](https://www.rfc-editor.org/rfc/rfc7616.html)
+//!
+//! Only the challenge is implemented.
+
+//
+pub mod algorithm;
+
+pub use algorithm::Algorithm;
+
+//
+pub mod challenge;
+
+pub use challenge::{Challenge, ChallengeParseError};
+#[cfg(test)]
+pub(crate) use challenge::{DEMO_CHALLENGE_REALM_STR, DEMO_CHALLENGE_STR};
+
+//
+pub mod challenge_ref;
+
+pub use challenge_ref::ChallengeRef;
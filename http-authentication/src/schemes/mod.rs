@@ -1,6 +1,9 @@
 pub const NAME_BASIC: &str = "Basic";
 pub const NAME_BEARER: &str = "Bearer";
+pub const NAME_CONCEALED: &str = "Concealed";
 pub const NAME_DIGEST: &str = "Digest";
+pub const NAME_MUTUAL: &str = "Mutual";
+pub const NAME_VAPID: &str = "vapid";
 
 //
 #[cfg(feature = "scheme-basic")]
@@ -8,3 +11,15 @@ pub mod basic;
 
 #[cfg(feature = "scheme-bearer")]
 pub mod bearer;
+
+#[cfg(feature = "scheme-concealed")]
+pub mod concealed;
+
+#[cfg(feature = "scheme-digest")]
+pub mod digest;
+
+#[cfg(feature = "scheme-mutual")]
+pub mod mutual;
+
+#[cfg(feature = "scheme-vapid")]
+pub mod vapid;
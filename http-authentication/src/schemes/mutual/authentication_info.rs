@@ -0,0 +1,74 @@
+use alloc::{boxed::Box, string::String};
+
+use crate::{schemes::NAME_MUTUAL as NAME, SP};
+
+use super::{
+    credentials::CredentialsParseError, find_param, parse_single, push_param, PARAM_SID, PARAM_VKS,
+};
+
+//
+/// The `200-VFY-S` message, sent in `Authentication-Info`, proves the server knows the session key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticationInfo {
+    pub sid: Box<str>,
+    pub vks: Box<str>,
+}
+
+impl AuthenticationInfo {
+    pub fn new(sid: impl AsRef<str>, vks: impl AsRef<str>) -> Self {
+        Self {
+            sid: sid.as_ref().into(),
+            vks: vks.as_ref().into(),
+        }
+    }
+
+    pub fn from_str_value(s: &str) -> Result<Self, CredentialsParseError> {
+        let c = parse_single(s).map_err(CredentialsParseError::Other)?;
+        if !c.scheme.eq_ignore_ascii_case(NAME) {
+            return Err(CredentialsParseError::SchemeMismatch);
+        }
+
+        let require = |name: &'static str| {
+            find_param(&c, name).ok_or(CredentialsParseError::ParamMissing(name))
+        };
+        Ok(Self {
+            sid: require(PARAM_SID)?,
+            vks: require(PARAM_VKS)?,
+        })
+    }
+
+    fn internal_to_string(&self) -> String {
+        let mut s = String::with_capacity(40);
+        s.push_str(NAME);
+        s.push(SP);
+        push_param(&mut s, PARAM_SID, &self.sid, true);
+        push_param(&mut s, PARAM_VKS, &self.vks, true);
+        s
+    }
+}
+
+impl core::fmt::Display for AuthenticationInfo {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.internal_to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString as _;
+
+    #[test]
+    fn test_parse_and_render() {
+        let s = r#"Mutual sid="abcdef", vks="dmtz""#;
+        let c = AuthenticationInfo::from_str_value(s).unwrap();
+        assert_eq!(c, AuthenticationInfo::new("abcdef", "dmtz"));
+        assert_eq!(c.to_string(), s);
+
+        match AuthenticationInfo::from_str_value(r#"Mutual sid="abcdef""#) {
+            Err(CredentialsParseError::ParamMissing(PARAM_VKS)) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+}
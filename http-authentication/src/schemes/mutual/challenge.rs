@@ -0,0 +1,281 @@
+use alloc::{boxed::Box, string::String};
+
+use http_auth::ChallengeRef;
+
+use crate::{schemes::NAME_MUTUAL as NAME, CHALLENGE_PARAM_REALM as PARAM_REALM, SP};
+
+use super::{
+    find_param, find_param_u64, parse_single, push_param, Reason, Validation, PARAM_ALGORITHM,
+    PARAM_AUTH_SCOPE, PARAM_KS1, PARAM_NC_MAX, PARAM_NC_WINDOW, PARAM_PATH, PARAM_REASON,
+    PARAM_SID, PARAM_TIME, PARAM_VALIDATION,
+};
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChallengeMessage {
+    /// `401-INIT`, starts or restarts the authentication.
+    Init,
+    /// `401-STALE`, the session expired.
+    Stale,
+    /// `401-KEX-S1`, the server half of the key exchange.
+    KexS1,
+}
+
+/// A `WWW-Authenticate` message, the fields present depend on [`ChallengeMessage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub reason: Option<Reason>,
+    pub algorithm: Option<Box<str>>,
+    pub validation: Option<Validation>,
+    pub auth_scope: Option<Box<str>>,
+    pub realm: Option<Box<str>>,
+    pub sid: Option<Box<str>>,
+    pub ks1: Option<Box<str>>,
+    pub nc_max: Option<u64>,
+    pub nc_window: Option<u64>,
+    pub time: Option<u64>,
+    pub path: Option<Box<str>>,
+}
+
+impl Challenge {
+    fn empty() -> Self {
+        Self {
+            reason: None,
+            algorithm: None,
+            validation: None,
+            auth_scope: None,
+            realm: None,
+            sid: None,
+            ks1: None,
+            nc_max: None,
+            nc_window: None,
+            time: None,
+            path: None,
+        }
+    }
+
+    pub fn init(
+        reason: Reason,
+        algorithm: impl AsRef<str>,
+        validation: Validation,
+        realm: impl AsRef<str>,
+    ) -> Self {
+        Self {
+            reason: Some(reason),
+            algorithm: Some(algorithm.as_ref().into()),
+            validation: Some(validation),
+            realm: Some(realm.as_ref().into()),
+            ..Self::empty()
+        }
+    }
+
+    pub fn stale(sid: impl AsRef<str>) -> Self {
+        Self {
+            reason: Some(Reason::StaleSession),
+            sid: Some(sid.as_ref().into()),
+            ..Self::empty()
+        }
+    }
+
+    pub fn kex_s1(
+        algorithm: impl AsRef<str>,
+        validation: Validation,
+        realm: impl AsRef<str>,
+        sid: impl AsRef<str>,
+        ks1: impl AsRef<str>,
+    ) -> Self {
+        Self {
+            algorithm: Some(algorithm.as_ref().into()),
+            validation: Some(validation),
+            realm: Some(realm.as_ref().into()),
+            sid: Some(sid.as_ref().into()),
+            ks1: Some(ks1.as_ref().into()),
+            ..Self::empty()
+        }
+    }
+
+    pub fn message(&self) -> ChallengeMessage {
+        match (&self.reason, &self.ks1) {
+            (_, Some(_)) => ChallengeMessage::KexS1,
+            (Some(Reason::StaleSession), None) => ChallengeMessage::Stale,
+            _ => ChallengeMessage::Init,
+        }
+    }
+
+    pub fn from_str_value(s: &str) -> Result<Self, ChallengeParseError> {
+        let c = parse_single(s).map_err(ChallengeParseError::Other)?;
+        Self::try_from(&c)
+    }
+
+    fn internal_to_string(&self) -> String {
+        let mut s = String::with_capacity(80);
+        s.push_str(NAME);
+        s.push(SP);
+
+        if let Some(reason) = &self.reason {
+            push_param(&mut s, PARAM_REASON, reason.as_str(), false);
+        }
+        if let Some(algorithm) = &self.algorithm {
+            push_param(&mut s, PARAM_ALGORITHM, algorithm, false);
+        }
+        if let Some(validation) = &self.validation {
+            push_param(&mut s, PARAM_VALIDATION, validation.as_str(), false);
+        }
+        if let Some(auth_scope) = &self.auth_scope {
+            push_param(&mut s, PARAM_AUTH_SCOPE, auth_scope, true);
+        }
+        if let Some(realm) = &self.realm {
+            push_param(&mut s, PARAM_REALM, realm, true);
+        }
+        if let Some(sid) = &self.sid {
+            push_param(&mut s, PARAM_SID, sid, true);
+        }
+        if let Some(ks1) = &self.ks1 {
+            push_param(&mut s, PARAM_KS1, ks1, true);
+        }
+        for (k, v) in [
+            (PARAM_NC_MAX, self.nc_max),
+            (PARAM_NC_WINDOW, self.nc_window),
+            (PARAM_TIME, self.time),
+        ] {
+            if let Some(v) = v {
+                push_param(&mut s, k, &alloc::format!("{v}"), false);
+            }
+        }
+        if let Some(path) = &self.path {
+            push_param(&mut s, PARAM_PATH, path, true);
+        }
+
+        s
+    }
+}
+
+//
+impl TryFrom<&ChallengeRef<'_>> for Challenge {
+    type Error = ChallengeParseError;
+
+    fn try_from(c: &ChallengeRef<'_>) -> Result<Self, Self::Error> {
+        if !c.scheme.eq_ignore_ascii_case(NAME) {
+            return Err(ChallengeParseError::SchemeMismatch);
+        }
+
+        let find = |name: &str| find_param(c, name);
+        let find_u64 =
+            |name: &'static str| find_param_u64(c, name).map_err(ChallengeParseError::ParamInvalid);
+
+        let challenge = Self {
+            reason: find(PARAM_REASON).map(|x| x.parse().unwrap_or_else(|x| match x {})),
+            algorithm: find(PARAM_ALGORITHM),
+            validation: find(PARAM_VALIDATION).map(|x| x.parse().unwrap_or_else(|x| match x {})),
+            auth_scope: find(PARAM_AUTH_SCOPE),
+            realm: find(PARAM_REALM),
+            sid: find(PARAM_SID),
+            ks1: find(PARAM_KS1),
+            nc_max: find_u64(PARAM_NC_MAX)?,
+            nc_window: find_u64(PARAM_NC_WINDOW)?,
+            time: find_u64(PARAM_TIME)?,
+            path: find(PARAM_PATH),
+        };
+
+        let required: &[(&'static str, bool)] = match challenge.message() {
+            ChallengeMessage::Init => &[
+                (PARAM_REASON, challenge.reason.is_some()),
+                (PARAM_ALGORITHM, challenge.algorithm.is_some()),
+                (PARAM_VALIDATION, challenge.validation.is_some()),
+                (PARAM_REALM, challenge.realm.is_some()),
+            ],
+            ChallengeMessage::Stale => &[(PARAM_SID, challenge.sid.is_some())],
+            ChallengeMessage::KexS1 => &[
+                (PARAM_ALGORITHM, challenge.algorithm.is_some()),
+                (PARAM_VALIDATION, challenge.validation.is_some()),
+                (PARAM_REALM, challenge.realm.is_some()),
+                (PARAM_SID, challenge.sid.is_some()),
+            ],
+        };
+        if let Some((name, _)) = required.iter().find(|(_, present)| !present) {
+            return Err(ChallengeParseError::ParamMissing(name));
+        }
+
+        Ok(challenge)
+    }
+}
+
+//
+#[derive(Debug)]
+pub enum ChallengeParseError {
+    SchemeMismatch,
+    ParamMissing(&'static str),
+    ParamInvalid(&'static str),
+    Other(&'static str),
+}
+
+impl core::fmt::Display for ChallengeParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ChallengeParseError {}
+
+//
+impl core::fmt::Display for Challenge {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.internal_to_string())
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+pub(crate) const DEMO_CHALLENGE_INIT_STR: &str = r#"Mutual reason=initial, algorithm=iso-kam3-dl-2048-sha256, validation=host, realm="mutual@example.com""#;
+#[cfg(test)]
+pub(crate) const DEMO_CHALLENGE_KEX_S1_STR: &str = r#"Mutual algorithm=iso-kam3-dl-2048-sha256, validation=host, realm="mutual@example.com", sid="abcdef", ks1="c2VydmVyLWtleQ==", nc-max=4096, nc-window=128, time=3600, path="/""#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString as _;
+
+    #[test]
+    fn test_parse_and_render() {
+        let c = Challenge::from_str_value(DEMO_CHALLENGE_INIT_STR).unwrap();
+        assert_eq!(c.message(), ChallengeMessage::Init);
+        assert_eq!(c.reason, Some(Reason::Initial));
+        assert_eq!(c.validation, Some(Validation::Host));
+        assert_eq!(c.realm, Some("mutual@example.com".into()));
+        assert_eq!(c.to_string(), DEMO_CHALLENGE_INIT_STR);
+
+        let c = Challenge::from_str_value(DEMO_CHALLENGE_KEX_S1_STR).unwrap();
+        assert_eq!(c.message(), ChallengeMessage::KexS1);
+        assert_eq!(c.ks1, Some("c2VydmVyLWtleQ==".into()));
+        assert_eq!(c.nc_max, Some(4096));
+        assert_eq!(c.to_string(), DEMO_CHALLENGE_KEX_S1_STR);
+
+        let c = Challenge::stale("abcdef");
+        assert_eq!(
+            c.to_string(),
+            r#"Mutual reason=stale-session, sid="abcdef""#
+        );
+        assert_eq!(
+            Challenge::from_str_value(&c.to_string()).unwrap().message(),
+            ChallengeMessage::Stale
+        );
+
+        //
+        match Challenge::from_str_value(r#"Mutual reason=initial, validation=host"#) {
+            Err(ChallengeParseError::ParamMissing(PARAM_ALGORITHM)) => {}
+            x => panic!("{x:?}"),
+        }
+        match Challenge::from_str_value(r#"Mutual sid="a", ks1="b", nc-max=x"#) {
+            Err(ChallengeParseError::ParamInvalid(PARAM_NC_MAX)) => {}
+            x => panic!("{x:?}"),
+        }
+        match Challenge::from_str_value(r#"Basic realm="foo""#) {
+            Err(ChallengeParseError::SchemeMismatch) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+}
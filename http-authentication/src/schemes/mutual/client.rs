@@ -0,0 +1,370 @@
+//! Client side message flow.
+//!
+//! ```text
+//! 401-INIT    -> req-KEX-C1
+//! 401-KEX-S1  -> req-VFY-C
+//! 200-VFY-S   -> authenticated, later requests reuse the session with a new `nc`
+//! 401-STALE   -> req-KEX-C1 of a new session
+//! ```
+
+use alloc::boxed::Box;
+
+use super::{
+    challenge::ChallengeMessage, AuthenticationInfo, Challenge, Credentials, Reason, Validation,
+};
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyExchangeContext<'a> {
+    pub algorithm: &'a str,
+    pub validation: &'a Validation,
+    pub auth_scope: Option<&'a str>,
+    pub realm: &'a str,
+    pub user: &'a str,
+}
+
+/// The group operations of the algorithm, the password is held by the implementation.
+pub trait KeyExchange {
+    /// Starts a new exchange, returns `kc1`.
+    fn start(&mut self, ctx: &KeyExchangeContext<'_>) -> Result<Box<str>, &'static str>;
+
+    /// Derives the session secret from `ks1`.
+    fn finish(
+        &mut self,
+        ctx: &KeyExchangeContext<'_>,
+        sid: &str,
+        ks1: &str,
+    ) -> Result<(), &'static str>;
+
+    /// Returns `vkc` of the request numbered `nc`.
+    fn client_verifier(
+        &self,
+        ctx: &KeyExchangeContext<'_>,
+        sid: &str,
+        nc: u64,
+    ) -> Result<Box<str>, &'static str>;
+
+    fn verify_server(&self, ctx: &KeyExchangeContext<'_>, sid: &str, nc: u64, vks: &str) -> bool;
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientState {
+    Idle,
+    /// `req-KEX-C1` was sent.
+    KeyExchanging,
+    /// `req-VFY-C` was sent.
+    Verifying {
+        sid: Box<str>,
+        nc: u64,
+    },
+    Authenticated {
+        sid: Box<str>,
+        nc: u64,
+    },
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientError {
+    UnexpectedMessage(ChallengeMessage),
+    UnexpectedAuthenticationInfo,
+    Rejected(Reason),
+    SessionMismatch,
+    KeyExchangeFailed(&'static str),
+    ServerVerificationFailed,
+}
+
+impl core::fmt::Display for ClientError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ClientError {}
+
+//
+#[derive(Debug, Clone)]
+struct Session {
+    algorithm: Box<str>,
+    validation: Validation,
+    auth_scope: Option<Box<str>>,
+    realm: Box<str>,
+}
+
+#[derive(Debug)]
+pub struct Client<K> {
+    kex: K,
+    user: Box<str>,
+    session: Option<Session>,
+    state: ClientState,
+}
+
+impl<K: KeyExchange> Client<K> {
+    pub fn new(kex: K, user: impl AsRef<str>) -> Self {
+        Self {
+            kex,
+            user: user.as_ref().into(),
+            session: None,
+            state: ClientState::Idle,
+        }
+    }
+
+    pub fn state(&self) -> &ClientState {
+        &self.state
+    }
+
+    /// Returns the `Authorization` message answering a `WWW-Authenticate` message.
+    pub fn on_challenge(&mut self, challenge: &Challenge) -> Result<Credentials, ClientError> {
+        let message = challenge.message();
+        let result = match message {
+            ChallengeMessage::Init => {
+                if let Some(reason) = &challenge.reason {
+                    if is_failure(reason) && self.state != ClientState::Idle {
+                        self.state = ClientState::Failed;
+                        return Err(ClientError::Rejected(reason.clone()));
+                    }
+                }
+                // Required fields are checked when parsing.
+                self.session = Some(Session {
+                    algorithm: challenge.algorithm.clone().unwrap_or_default(),
+                    validation: challenge.validation.clone().unwrap_or(Validation::Host),
+                    auth_scope: challenge.auth_scope.clone(),
+                    realm: challenge.realm.clone().unwrap_or_default(),
+                });
+                self.start()
+            }
+            ChallengeMessage::Stale => match self.state {
+                ClientState::Authenticated { .. } | ClientState::Verifying { .. } => self.start(),
+                _ => Err(ClientError::UnexpectedMessage(message)),
+            },
+            ChallengeMessage::KexS1 => self.exchange(challenge),
+        };
+        if result.is_err() {
+            self.state = ClientState::Failed;
+        }
+        result
+    }
+
+    /// Checks the `Authentication-Info` message of the response to `req-VFY-C`.
+    pub fn on_authentication_info(&mut self, info: &AuthenticationInfo) -> Result<(), ClientError> {
+        let (ClientState::Verifying { sid, nc }, Some(session)) = (&self.state, &self.session)
+        else {
+            return Err(ClientError::UnexpectedAuthenticationInfo);
+        };
+        if *sid != info.sid {
+            self.state = ClientState::Failed;
+            return Err(ClientError::SessionMismatch);
+        }
+
+        let ctx = context(session, &self.user);
+        if !self.kex.verify_server(&ctx, sid, *nc, &info.vks) {
+            self.state = ClientState::Failed;
+            return Err(ClientError::ServerVerificationFailed);
+        }
+
+        self.state = ClientState::Authenticated {
+            sid: sid.clone(),
+            nc: *nc,
+        };
+        Ok(())
+    }
+
+    /// `req-VFY-C` for the next request of an authenticated session.
+    pub fn next_credentials(&mut self) -> Option<Result<Credentials, ClientError>> {
+        let ClientState::Authenticated { sid, nc } = &self.state else {
+            return None;
+        };
+        let (sid, nc) = (sid.clone(), nc + 1);
+
+        let result = self.verify_c(&sid, nc);
+        if result.is_ok() {
+            self.state = ClientState::Authenticated { sid, nc };
+        }
+        Some(result)
+    }
+
+    fn start(&mut self) -> Result<Credentials, ClientError> {
+        let session = self
+            .session
+            .as_ref()
+            .ok_or(ClientError::UnexpectedMessage(ChallengeMessage::Stale))?;
+        let kc1 = self
+            .kex
+            .start(&context(session, &self.user))
+            .map_err(ClientError::KeyExchangeFailed)?;
+
+        let mut credentials = Credentials::kex_c1(
+            &session.algorithm,
+            session.validation.clone(),
+            &session.realm,
+            &self.user,
+            kc1,
+        );
+        credentials.auth_scope = session.auth_scope.clone();
+        self.state = ClientState::KeyExchanging;
+        Ok(credentials)
+    }
+
+    fn exchange(&mut self, challenge: &Challenge) -> Result<Credentials, ClientError> {
+        let (ClientState::KeyExchanging, Some(session)) = (&self.state, &self.session) else {
+            return Err(ClientError::UnexpectedMessage(ChallengeMessage::KexS1));
+        };
+        if challenge.realm.as_deref() != Some(&session.realm) {
+            return Err(ClientError::SessionMismatch);
+        }
+
+        let sid = challenge.sid.as_deref().unwrap_or_default();
+        let ks1 = challenge.ks1.as_deref().unwrap_or_default();
+        self.kex
+            .finish(&context(session, &self.user), sid, ks1)
+            .map_err(ClientError::KeyExchangeFailed)?;
+
+        let credentials = self.verify_c(sid, 1)?;
+        self.state = ClientState::Verifying {
+            sid: sid.into(),
+            nc: 1,
+        };
+        Ok(credentials)
+    }
+
+    fn verify_c(&self, sid: &str, nc: u64) -> Result<Credentials, ClientError> {
+        let session = self
+            .session
+            .as_ref()
+            .ok_or(ClientError::UnexpectedMessage(ChallengeMessage::KexS1))?;
+        let vkc = self
+            .kex
+            .client_verifier(&context(session, &self.user), sid, nc)
+            .map_err(ClientError::KeyExchangeFailed)?;
+
+        let mut credentials = Credentials::vfy_c(
+            &session.algorithm,
+            session.validation.clone(),
+            &session.realm,
+            sid,
+            nc,
+            vkc,
+        );
+        credentials.auth_scope = session.auth_scope.clone();
+        Ok(credentials)
+    }
+}
+
+fn context<'a>(session: &'a Session, user: &'a str) -> KeyExchangeContext<'a> {
+    KeyExchangeContext {
+        algorithm: &session.algorithm,
+        validation: &session.validation,
+        auth_scope: session.auth_scope.as_deref(),
+        realm: &session.realm,
+        user,
+    }
+}
+
+fn is_failure(reason: &Reason) -> bool {
+    matches!(
+        reason,
+        Reason::AuthFailed | Reason::UserUnknown | Reason::InvalidCredential | Reason::AuthzFailed
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::format;
+
+    use crate::schemes::mutual::challenge::{DEMO_CHALLENGE_INIT_STR, DEMO_CHALLENGE_KEX_S1_STR};
+
+    // Not a real group, only records the flow.
+    #[derive(Default)]
+    struct FakeKex {
+        secret: Option<Box<str>>,
+    }
+
+    impl KeyExchange for FakeKex {
+        fn start(&mut self, ctx: &KeyExchangeContext<'_>) -> Result<Box<str>, &'static str> {
+            Ok(format!("kc1-{}", ctx.user).into())
+        }
+
+        fn finish(
+            &mut self,
+            _ctx: &KeyExchangeContext<'_>,
+            _sid: &str,
+            ks1: &str,
+        ) -> Result<(), &'static str> {
+            self.secret = Some(ks1.into());
+            Ok(())
+        }
+
+        fn client_verifier(
+            &self,
+            _ctx: &KeyExchangeContext<'_>,
+            sid: &str,
+            nc: u64,
+        ) -> Result<Box<str>, &'static str> {
+            let secret = self.secret.as_ref().ok_or("no secret")?;
+            Ok(format!("vkc-{secret}-{sid}-{nc}").into())
+        }
+
+        fn verify_server(
+            &self,
+            _ctx: &KeyExchangeContext<'_>,
+            sid: &str,
+            nc: u64,
+            vks: &str,
+        ) -> bool {
+            vks == format!("vks-{sid}-{nc}")
+        }
+    }
+
+    #[test]
+    fn test_flow() {
+        let mut client = Client::new(FakeKex::default(), "aladdin");
+
+        let init = Challenge::from_str_value(DEMO_CHALLENGE_INIT_STR).unwrap();
+        let c = client.on_challenge(&init).unwrap();
+        assert_eq!(c.kc1, Some("kc1-aladdin".into()));
+        assert_eq!(client.state(), &ClientState::KeyExchanging);
+
+        let kex_s1 = Challenge::from_str_value(DEMO_CHALLENGE_KEX_S1_STR).unwrap();
+        let c = client.on_challenge(&kex_s1).unwrap();
+        assert_eq!(c.sid, Some("abcdef".into()));
+        assert_eq!(c.nc, Some(1));
+        assert_eq!(c.vkc, Some("vkc-c2VydmVyLWtleQ==-abcdef-1".into()));
+
+        match client.on_authentication_info(&AuthenticationInfo::new("abcdef", "bad")) {
+            Err(ClientError::ServerVerificationFailed) => {}
+            x => panic!("{x:?}"),
+        }
+        assert_eq!(client.state(), &ClientState::Failed);
+
+        //
+        let mut client = Client::new(FakeKex::default(), "aladdin");
+        client.on_challenge(&init).unwrap();
+        client.on_challenge(&kex_s1).unwrap();
+        client
+            .on_authentication_info(&AuthenticationInfo::new("abcdef", "vks-abcdef-1"))
+            .unwrap();
+        let c = client.next_credentials().unwrap().unwrap();
+        assert_eq!(c.nc, Some(2));
+
+        let c = client.on_challenge(&Challenge::stale("abcdef")).unwrap();
+        assert!(c.kc1.is_some());
+
+        let mut failed = init.clone();
+        failed.reason = Some(Reason::AuthFailed);
+        match client.on_challenge(&failed) {
+            Err(ClientError::Rejected(Reason::AuthFailed)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        //
+        let mut client = Client::new(FakeKex::default(), "aladdin");
+        match client.on_challenge(&kex_s1) {
+            Err(ClientError::UnexpectedMessage(ChallengeMessage::KexS1)) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+}
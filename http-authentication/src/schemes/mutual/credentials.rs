@@ -0,0 +1,223 @@
+use alloc::{boxed::Box, string::String};
+
+use crate::{schemes::NAME_MUTUAL as NAME, CHALLENGE_PARAM_REALM as PARAM_REALM, SP};
+
+use super::{
+    find_param, find_param_u64, parse_single, push_param, Validation, PARAM_ALGORITHM,
+    PARAM_AUTH_SCOPE, PARAM_KC1, PARAM_NC, PARAM_SID, PARAM_USER, PARAM_VALIDATION, PARAM_VKC,
+};
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CredentialsMessage {
+    /// `req-KEX-C1`, the client half of the key exchange.
+    KexC1,
+    /// `req-VFY-C`, proves the client knows the session key.
+    VfyC,
+}
+
+/// An `Authorization` message, the fields present depend on [`CredentialsMessage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub algorithm: Box<str>,
+    pub validation: Validation,
+    pub auth_scope: Option<Box<str>>,
+    pub realm: Box<str>,
+    pub user: Option<Box<str>>,
+    pub kc1: Option<Box<str>>,
+    pub sid: Option<Box<str>>,
+    pub nc: Option<u64>,
+    pub vkc: Option<Box<str>>,
+}
+
+impl Credentials {
+    pub fn kex_c1(
+        algorithm: impl AsRef<str>,
+        validation: Validation,
+        realm: impl AsRef<str>,
+        user: impl AsRef<str>,
+        kc1: impl AsRef<str>,
+    ) -> Self {
+        Self {
+            algorithm: algorithm.as_ref().into(),
+            validation,
+            auth_scope: None,
+            realm: realm.as_ref().into(),
+            user: Some(user.as_ref().into()),
+            kc1: Some(kc1.as_ref().into()),
+            sid: None,
+            nc: None,
+            vkc: None,
+        }
+    }
+
+    pub fn vfy_c(
+        algorithm: impl AsRef<str>,
+        validation: Validation,
+        realm: impl AsRef<str>,
+        sid: impl AsRef<str>,
+        nc: u64,
+        vkc: impl AsRef<str>,
+    ) -> Self {
+        Self {
+            algorithm: algorithm.as_ref().into(),
+            validation,
+            auth_scope: None,
+            realm: realm.as_ref().into(),
+            user: None,
+            kc1: None,
+            sid: Some(sid.as_ref().into()),
+            nc: Some(nc),
+            vkc: Some(vkc.as_ref().into()),
+        }
+    }
+
+    pub fn message(&self) -> CredentialsMessage {
+        if self.vkc.is_some() {
+            CredentialsMessage::VfyC
+        } else {
+            CredentialsMessage::KexC1
+        }
+    }
+
+    pub fn from_str_value(s: &str) -> Result<Self, CredentialsParseError> {
+        let c = parse_single(s).map_err(CredentialsParseError::Other)?;
+        if !c.scheme.eq_ignore_ascii_case(NAME) {
+            return Err(CredentialsParseError::SchemeMismatch);
+        }
+
+        let find = |name: &str| find_param(&c, name);
+        let require =
+            |name: &'static str| find(name).ok_or(CredentialsParseError::ParamMissing(name));
+
+        let mut credentials = Self {
+            algorithm: require(PARAM_ALGORITHM)?,
+            validation: require(PARAM_VALIDATION)?
+                .parse()
+                .unwrap_or_else(|x| match x {}),
+            auth_scope: find(PARAM_AUTH_SCOPE),
+            realm: require(PARAM_REALM)?,
+            user: find(PARAM_USER),
+            kc1: find(PARAM_KC1),
+            sid: find(PARAM_SID),
+            nc: find_param_u64(&c, PARAM_NC).map_err(CredentialsParseError::ParamInvalid)?,
+            vkc: find(PARAM_VKC),
+        };
+
+        match credentials.message() {
+            CredentialsMessage::KexC1 => {
+                require(PARAM_USER)?;
+                require(PARAM_KC1)?;
+            }
+            CredentialsMessage::VfyC => {
+                require(PARAM_SID)?;
+                credentials.nc = Some(
+                    credentials
+                        .nc
+                        .ok_or(CredentialsParseError::ParamMissing(PARAM_NC))?,
+                );
+            }
+        }
+
+        Ok(credentials)
+    }
+
+    fn internal_to_string(&self) -> String {
+        let mut s = String::with_capacity(80);
+        s.push_str(NAME);
+        s.push(SP);
+
+        push_param(&mut s, PARAM_ALGORITHM, &self.algorithm, false);
+        push_param(&mut s, PARAM_VALIDATION, self.validation.as_str(), false);
+        if let Some(auth_scope) = &self.auth_scope {
+            push_param(&mut s, PARAM_AUTH_SCOPE, auth_scope, true);
+        }
+        push_param(&mut s, PARAM_REALM, &self.realm, true);
+        if let Some(user) = &self.user {
+            push_param(&mut s, PARAM_USER, user, true);
+        }
+        if let Some(kc1) = &self.kc1 {
+            push_param(&mut s, PARAM_KC1, kc1, true);
+        }
+        if let Some(sid) = &self.sid {
+            push_param(&mut s, PARAM_SID, sid, true);
+        }
+        if let Some(nc) = self.nc {
+            push_param(&mut s, PARAM_NC, &alloc::format!("{nc}"), false);
+        }
+        if let Some(vkc) = &self.vkc {
+            push_param(&mut s, PARAM_VKC, vkc, true);
+        }
+
+        s
+    }
+}
+
+//
+#[derive(Debug)]
+pub enum CredentialsParseError {
+    SchemeMismatch,
+    ParamMissing(&'static str),
+    ParamInvalid(&'static str),
+    Other(&'static str),
+}
+
+impl core::fmt::Display for CredentialsParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CredentialsParseError {}
+
+//
+impl core::fmt::Display for Credentials {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.internal_to_string())
+    }
+}
+
+//
+//
+//
+#[cfg(test)]
+pub(crate) const DEMO_CREDENTIALS_KEX_C1_STR: &str = r#"Mutual algorithm=iso-kam3-dl-2048-sha256, validation=host, realm="mutual@example.com", user="aladdin", kc1="Y2xpZW50LWtleQ==""#;
+#[cfg(test)]
+pub(crate) const DEMO_CREDENTIALS_VFY_C_STR: &str = r#"Mutual algorithm=iso-kam3-dl-2048-sha256, validation=host, realm="mutual@example.com", sid="abcdef", nc=1, vkc="dmtj""#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use alloc::string::ToString as _;
+
+    #[test]
+    fn test_parse_and_render() {
+        let c = Credentials::from_str_value(DEMO_CREDENTIALS_KEX_C1_STR).unwrap();
+        assert_eq!(c.message(), CredentialsMessage::KexC1);
+        assert_eq!(c.user, Some("aladdin".into()));
+        assert_eq!(c.to_string(), DEMO_CREDENTIALS_KEX_C1_STR);
+
+        let c = Credentials::from_str_value(DEMO_CREDENTIALS_VFY_C_STR).unwrap();
+        assert_eq!(c.message(), CredentialsMessage::VfyC);
+        assert_eq!(c.nc, Some(1));
+        assert_eq!(c.to_string(), DEMO_CREDENTIALS_VFY_C_STR);
+
+        let mut c = Credentials::kex_c1("foo", Validation::Host, "bar", r#"a"b"#, "kc1");
+        c.auth_scope = Some("example.com".into());
+        assert_eq!(Credentials::from_str_value(&c.to_string()).unwrap(), c);
+
+        //
+        match Credentials::from_str_value(
+            r#"Mutual algorithm=foo, validation=host, realm="bar", sid="abc", vkc="def""#,
+        ) {
+            Err(CredentialsParseError::ParamMissing(PARAM_NC)) => {}
+            x => panic!("{x:?}"),
+        }
+        match Credentials::from_str_value(r#"Mutual algorithm=foo, realm="bar""#) {
+            Err(CredentialsParseError::ParamMissing(PARAM_VALIDATION)) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! [Mutual Authentication Protocol for HTTP](https://www.rfc-editor.org/rfc/rfc8120.html)
+//!
+//! Experimental, the messages are not part of [`Challenge`](crate::Challenge) and
+//! [`Credentials`](crate::Credentials), parse the header values with the types of this module.
+//! The group operations of the key exchange are provided by a [`KeyExchange`].
+
+use alloc::{boxed::Box, string::String};
+
+use crate::{challenge::push_quoted_string, COMMA, EQ_S, SP};
+
+//
+pub(crate) const PARAM_ALGORITHM: &str = "algorithm";
+pub(crate) const PARAM_VALIDATION: &str = "validation";
+pub(crate) const PARAM_AUTH_SCOPE: &str = "auth-scope";
+pub(crate) const PARAM_REASON: &str = "reason";
+pub(crate) const PARAM_USER: &str = "user";
+pub(crate) const PARAM_SID: &str = "sid";
+pub(crate) const PARAM_NC: &str = "nc";
+pub(crate) const PARAM_NC_MAX: &str = "nc-max";
+pub(crate) const PARAM_NC_WINDOW: &str = "nc-window";
+pub(crate) const PARAM_TIME: &str = "time";
+pub(crate) const PARAM_PATH: &str = "path";
+pub(crate) const PARAM_KC1: &str = "kc1";
+pub(crate) const PARAM_KS1: &str = "ks1";
+pub(crate) const PARAM_VKC: &str = "vkc";
+pub(crate) const PARAM_VKS: &str = "vks";
+
+//
+pub mod validation;
+
+pub use validation::{Reason, Validation};
+
+//
+pub mod challenge;
+
+pub use challenge::{Challenge, ChallengeParseError};
+
+//
+pub mod credentials;
+
+pub use credentials::{Credentials, CredentialsParseError};
+
+//
+pub mod authentication_info;
+
+pub use authentication_info::AuthenticationInfo;
+
+//
+pub mod client;
+
+pub use client::{Client, ClientError, ClientState, KeyExchange, KeyExchangeContext};
+
+//
+/// Appends `, k=v`, quoted strings are escaped.
+pub(crate) fn push_param(s: &mut String, k: &str, v: &str, is_quoted: bool) {
+    if !s.ends_with(SP) {
+        s.push(COMMA);
+        s.push(SP);
+    }
+    s.push_str(k);
+    s.push(EQ_S);
+    if is_quoted {
+        push_quoted_string(s, v);
+    } else {
+        s.push_str(v);
+    }
+}
+
+/// Unescaped value of the first param named `name`.
+pub(crate) fn find_param(c: &http_auth::ChallengeRef<'_>, name: &str) -> Option<Box<str>> {
+    c.params
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.to_unescaped().into())
+}
+
+pub(crate) fn find_param_u64(
+    c: &http_auth::ChallengeRef<'_>,
+    name: &'static str,
+) -> Result<Option<u64>, &'static str> {
+    find_param(c, name)
+        .map(|x| x.parse::<u64>().map_err(|_| name))
+        .transpose()
+}
+
+/// Parses a header value holding a single `Mutual` message.
+pub(crate) fn parse_single(s: &str) -> Result<http_auth::ChallengeRef<'_>, &'static str> {
+    let mut parser = http_auth::ChallengeParser::new(s);
+    let c = match parser.next() {
+        Some(Ok(c)) => c,
+        Some(Err(_)) => return Err("parse failed"),
+        None => return Err("empty"),
+    };
+    if parser.next().is_some() {
+        return Err("more than one message");
+    }
+    Ok(c)
+}
@@ -0,0 +1,103 @@
+use alloc::boxed::Box;
+use core::{convert::Infallible, str::FromStr};
+
+//
+/// How the authentication is bound to the connection.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Validation {
+    Host,
+    TlsServerEndPoint,
+    TlsUnique,
+    Other(Box<str>),
+}
+
+impl Validation {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Host => "host",
+            Self::TlsServerEndPoint => "tls-server-end-point",
+            Self::TlsUnique => "tls-unique",
+            Self::Other(x) => x,
+        }
+    }
+}
+
+impl FromStr for Validation {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            x if x.eq_ignore_ascii_case("host") => Self::Host,
+            x if x.eq_ignore_ascii_case("tls-server-end-point") => Self::TlsServerEndPoint,
+            x if x.eq_ignore_ascii_case("tls-unique") => Self::TlsUnique,
+            x => Self::Other(x.into()),
+        })
+    }
+}
+
+impl core::fmt::Display for Validation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+//
+/// Why a `401-INIT` or `401-STALE` message was sent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Reason {
+    Initial,
+    StaleSession,
+    AuthFailed,
+    ReauthNeeded,
+    InvalidParameters,
+    InternalError,
+    UserUnknown,
+    InvalidCredential,
+    AuthzFailed,
+    Other(Box<str>),
+}
+
+impl Reason {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Initial => "initial",
+            Self::StaleSession => "stale-session",
+            Self::AuthFailed => "auth-failed",
+            Self::ReauthNeeded => "reauth-needed",
+            Self::InvalidParameters => "invalid-parameters",
+            Self::InternalError => "internal-error",
+            Self::UserUnknown => "user-unknown",
+            Self::InvalidCredential => "invalid-credential",
+            Self::AuthzFailed => "authz-failed",
+            Self::Other(x) => x,
+        }
+    }
+}
+
+impl FromStr for Reason {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let list = [
+            Self::Initial,
+            Self::StaleSession,
+            Self::AuthFailed,
+            Self::ReauthNeeded,
+            Self::InvalidParameters,
+            Self::InternalError,
+            Self::UserUnknown,
+            Self::InvalidCredential,
+            Self::AuthzFailed,
+        ];
+        Ok(list
+            .into_iter()
+            .find(|x| x.as_str().eq_ignore_ascii_case(s))
+            .unwrap_or_else(|| Self::Other(s.into())))
+    }
+}
+
+impl core::fmt::Display for Reason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
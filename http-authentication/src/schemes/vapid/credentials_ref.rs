@@ -0,0 +1,61 @@
+use crate::parse_options::ParseOptions;
+
+use super::credentials::token_and_public_key;
+use super::{Credentials, CredentialsParseError};
+
+//
+/// Borrowed [`Credentials`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialsRef<'a> {
+    pub token: &'a str,
+    pub public_key: &'a str,
+}
+
+impl<'a> CredentialsRef<'a> {
+    pub fn new(token: &'a str, public_key: &'a str) -> Self {
+        Self { token, public_key }
+    }
+
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, CredentialsParseError> {
+        Self::from_bytes_with_options(bytes, &ParseOptions::default())
+    }
+
+    pub fn from_bytes_with_options(
+        bytes: &'a [u8],
+        options: &ParseOptions,
+    ) -> Result<Self, CredentialsParseError> {
+        token_and_public_key(bytes, options).map(|(token, public_key)| Self::new(token, public_key))
+    }
+
+    pub fn into_owned(self) -> Credentials {
+        Credentials::new(self.token, self.public_key)
+    }
+}
+
+impl From<CredentialsRef<'_>> for Credentials {
+    fn from(c: CredentialsRef<'_>) -> Self {
+        c.into_owned()
+    }
+}
+
+impl<'a> From<&'a Credentials> for CredentialsRef<'a> {
+    fn from(c: &'a Credentials) -> Self {
+        Self::new(&c.token, &c.public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schemes::vapid::DEMO_CREDENTIALS_STR;
+
+    #[test]
+    fn test_parse() {
+        let c = CredentialsRef::from_bytes(DEMO_CREDENTIALS_STR.as_bytes()).unwrap();
+        assert!(c.public_key.starts_with("BA1Hxzyi1RUM"));
+
+        let c = c.into_owned();
+        assert!(c.token.starts_with("eyJ0eXAi"));
+    }
+}
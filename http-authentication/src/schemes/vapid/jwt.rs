@@ -0,0 +1,248 @@
+//! ES256 JWT of the `t` parameter.
+
+use alloc::{
+    boxed::Box,
+    string::{String, ToString as _},
+    vec::Vec,
+};
+
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
+use p256::ecdsa::{
+    signature::{Signer as _, Verifier as _},
+    Signature, SigningKey, VerifyingKey,
+};
+use serde_json::{Map, Value};
+
+use super::Credentials;
+
+//
+pub const JWT_HEADER: &str = r#"{"typ":"JWT","alg":"ES256"}"#;
+/// `exp` must not be further than 24 hours from now.
+pub const EXP_MAX_SECS: u64 = 60 * 60 * 24;
+
+const CLAIM_AUD: &str = "aud";
+const CLAIM_EXP: &str = "exp";
+const CLAIM_SUB: &str = "sub";
+
+// The RFC forbids padding, accept it anyway.
+const ENGINE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Decodes a public key in base64url or standard base64, with or without padding.
+#[cfg(feature = "std")]
+pub(crate) fn decode_public_key(public_key: &str) -> Option<Vec<u8>> {
+    const ENGINE_STANDARD: GeneralPurpose = GeneralPurpose::new(
+        &alphabet::STANDARD,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    );
+
+    ENGINE
+        .decode(public_key)
+        .or_else(|_| ENGINE_STANDARD.decode(public_key))
+        .ok()
+}
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claims {
+    /// The origin of the push resource.
+    pub aud: Box<str>,
+    /// Seconds since the Unix epoch.
+    pub exp: u64,
+    /// A `mailto:` or `https:` contact.
+    pub sub: Option<Box<str>>,
+}
+
+impl Claims {
+    pub fn new(aud: impl AsRef<str>, exp: u64) -> Self {
+        Self {
+            aud: aud.as_ref().into(),
+            exp,
+            sub: None,
+        }
+    }
+
+    pub fn with_sub(mut self, sub: impl AsRef<str>) -> Self {
+        self.sub = Some(sub.as_ref().into());
+        self
+    }
+
+    fn to_json(&self) -> String {
+        let mut map = Map::new();
+        map.insert(CLAIM_AUD.into(), Value::from(&*self.aud));
+        map.insert(CLAIM_EXP.into(), Value::from(self.exp));
+        if let Some(sub) = &self.sub {
+            map.insert(CLAIM_SUB.into(), Value::from(&**sub));
+        }
+        Value::Object(map).to_string()
+    }
+
+    fn from_json(bytes: &[u8]) -> Result<Self, VerifyError> {
+        let value: Value = serde_json::from_slice(bytes).map_err(|_| VerifyError::ClaimsInvalid)?;
+        let aud = value
+            .get(CLAIM_AUD)
+            .and_then(Value::as_str)
+            .ok_or(VerifyError::ClaimsInvalid)?;
+        let exp = value
+            .get(CLAIM_EXP)
+            .and_then(Value::as_u64)
+            .ok_or(VerifyError::ClaimsInvalid)?;
+        let sub = match value.get(CLAIM_SUB) {
+            Some(x) => Some(x.as_str().ok_or(VerifyError::ClaimsInvalid)?.into()),
+            None => None,
+        };
+        Ok(Self {
+            aud: aud.into(),
+            exp,
+            sub,
+        })
+    }
+}
+
+//
+impl Credentials {
+    /// Signs `claims` with `signing_key`, `k` is its public key.
+    pub fn sign(claims: &Claims, signing_key: &SigningKey) -> Self {
+        let mut token = String::with_capacity(256);
+        ENGINE.encode_string(JWT_HEADER, &mut token);
+        token.push('.');
+        ENGINE.encode_string(claims.to_json(), &mut token);
+
+        let signature: Signature = signing_key.sign(token.as_bytes());
+        token.push('.');
+        ENGINE.encode_string(signature.to_bytes(), &mut token);
+
+        let public_key = signing_key.verifying_key().to_encoded_point(false);
+        Self::new(token, ENGINE.encode(public_key.as_bytes()))
+    }
+
+    pub fn public_key_bytes(&self) -> Result<Vec<u8>, VerifyError> {
+        ENGINE
+            .decode(self.public_key.as_bytes())
+            .map_err(|_| VerifyError::PublicKeyInvalid)
+    }
+
+    /// Checks the signature with `k`, `aud` against `audience` and `exp` against `now`, seconds since the Unix epoch.
+    pub fn verify(&self, audience: &str, now: u64) -> Result<Claims, VerifyError> {
+        let verifying_key = VerifyingKey::from_sec1_bytes(&self.public_key_bytes()?)
+            .map_err(|_| VerifyError::PublicKeyInvalid)?;
+
+        let (signed, signature) = self
+            .token
+            .rsplit_once('.')
+            .ok_or(VerifyError::TokenMalformed)?;
+        let (header, claims) = signed.split_once('.').ok_or(VerifyError::TokenMalformed)?;
+
+        let header = ENGINE
+            .decode(header)
+            .map_err(|_| VerifyError::TokenMalformed)?;
+        let header: Value =
+            serde_json::from_slice(&header).map_err(|_| VerifyError::TokenMalformed)?;
+        if header.get("alg").and_then(Value::as_str) != Some("ES256") {
+            return Err(VerifyError::AlgorithmUnsupported);
+        }
+
+        let signature = ENGINE
+            .decode(signature)
+            .map_err(|_| VerifyError::TokenMalformed)?;
+        let signature =
+            Signature::from_slice(&signature).map_err(|_| VerifyError::SignatureInvalid)?;
+        verifying_key
+            .verify(signed.as_bytes(), &signature)
+            .map_err(|_| VerifyError::SignatureInvalid)?;
+
+        let claims = ENGINE
+            .decode(claims)
+            .map_err(|_| VerifyError::TokenMalformed)?;
+        let claims = Claims::from_json(&claims)?;
+        if &*claims.aud != audience {
+            return Err(VerifyError::AudienceMismatch);
+        }
+        if claims.exp <= now {
+            return Err(VerifyError::Expired);
+        }
+        if claims.exp > now.saturating_add(EXP_MAX_SECS) {
+            return Err(VerifyError::ExpirationTooFar);
+        }
+
+        Ok(claims)
+    }
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyError {
+    PublicKeyInvalid,
+    TokenMalformed,
+    AlgorithmUnsupported,
+    SignatureInvalid,
+    ClaimsInvalid,
+    AudienceMismatch,
+    Expired,
+    ExpirationTooFar,
+}
+
+impl core::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifyError {}
+
+#[cfg(test)]
+pub(crate) fn demo_signing_key() -> SigningKey {
+    SigningKey::from_slice(&[7; 32]).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::schemes::vapid::DEMO_CREDENTIALS_STR;
+
+    const AUD: &str = "https://push.example.net";
+
+    #[test]
+    fn test_sign_and_verify() {
+        let claims = Claims::new(AUD, 10_003_600).with_sub("mailto:push@example.com");
+        let c = Credentials::sign(&claims, &demo_signing_key());
+        assert!(c.token.starts_with("eyJ0eXAiOiJKV1QiLCJhbGciOiJFUzI1NiJ9."));
+        assert_eq!(c.public_key_bytes().unwrap().len(), 65);
+
+        assert_eq!(c.verify(AUD, 10_000_000).unwrap(), claims);
+        assert_eq!(
+            c.verify("https://other.example.net", 10_000_000),
+            Err(VerifyError::AudienceMismatch)
+        );
+        assert_eq!(c.verify(AUD, 10_003_600), Err(VerifyError::Expired));
+        assert_eq!(c.verify(AUD, 0), Err(VerifyError::ExpirationTooFar));
+
+        let other = Credentials::sign(&claims, &SigningKey::from_slice(&[8; 32]).unwrap());
+        let c = Credentials::new(&c.token, &other.public_key);
+        assert_eq!(
+            c.verify(AUD, 10_000_000),
+            Err(VerifyError::SignatureInvalid)
+        );
+
+        let claims = Claims::new(AUD, u64::MAX);
+        let c = Credentials::sign(&claims, &demo_signing_key());
+        assert_eq!(c.verify(AUD, u64::MAX - 1).unwrap(), claims);
+    }
+
+    #[test]
+    fn test_verify_demo() {
+        let c = DEMO_CREDENTIALS_STR.parse::<Credentials>().unwrap();
+        let claims = c.verify(AUD, 1453523768 - 60).unwrap();
+        assert_eq!(claims.sub, Some("mailto:push@example.com".into()));
+    }
+}
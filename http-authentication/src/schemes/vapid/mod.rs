@@ -0,0 +1,27 @@
+//! [Voluntary Application Server Identification (VAPID) for Web Push](https://www.rfc-editor.org/rfc/rfc8292.html)
+//!
+//! Only the credentials are implemented, the `t` parameter is an ES256 JWT and `k` is the public key.
+
+//
+pub mod credentials;
+
+#[cfg(test)]
+pub(crate) use credentials::DEMO_CREDENTIALS_STR;
+pub use credentials::{Credentials, CredentialsParseError};
+
+//
+pub mod credentials_ref;
+
+pub use credentials_ref::CredentialsRef;
+
+//
+pub mod jwt;
+
+pub use jwt::{Claims, VerifyError};
+
+//
+#[cfg(feature = "std")]
+pub mod verifier;
+
+#[cfg(feature = "std")]
+pub use verifier::{VapidIdentity, VapidVerifier};
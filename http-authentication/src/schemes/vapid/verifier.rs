@@ -0,0 +1,136 @@
+//! Server-side verification, for push services receiving VAPID credentials.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    credentials::Credentials,
+    verifier::{Rejection, Verifier, VerifyContext},
+};
+
+use super::{jwt::decode_public_key, Claims};
+
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VapidIdentity {
+    pub public_key: Box<str>,
+    pub claims: Claims,
+}
+
+/// Verifies the JWT against the origin of the push service, optionally restricted to known public keys.
+#[derive(Debug, Clone)]
+pub struct VapidVerifier {
+    audience: Box<str>,
+    /// Decoded, so that equivalent encodings match.
+    public_keys: Option<Vec<Vec<u8>>>,
+}
+
+impl VapidVerifier {
+    pub fn new(audience: impl AsRef<str>) -> Self {
+        Self {
+            audience: audience.as_ref().into(),
+            public_keys: None,
+        }
+    }
+
+    /// Once called, only the allowed public keys are accepted.
+    ///
+    /// The key is base64url or standard base64, with or without padding. A key that does not
+    /// decode matches nothing.
+    pub fn allow_public_key(mut self, public_key: impl AsRef<str>) -> Self {
+        let public_keys = self.public_keys.get_or_insert_with(Vec::new);
+        if let Some(public_key) = decode_public_key(public_key.as_ref()) {
+            public_keys.push(public_key);
+        }
+        self
+    }
+
+    pub fn verify_at(
+        &self,
+        credentials: &Credentials,
+        now: u64,
+    ) -> Result<VapidIdentity, Rejection> {
+        #[allow(irrefutable_let_patterns)]
+        let Credentials::Vapid(c) = credentials
+        else {
+            return Err(Rejection::SchemeUnsupported);
+        };
+
+        if let Some(public_keys) = &self.public_keys {
+            let public_key = c.public_key_bytes().map_err(|_| Rejection::Invalid)?;
+            if !public_keys.contains(&public_key) {
+                return Err(Rejection::Invalid);
+            }
+        }
+
+        let claims = c
+            .verify(&self.audience, now)
+            .map_err(|_| Rejection::Invalid)?;
+        Ok(VapidIdentity {
+            public_key: c.public_key.clone(),
+            claims,
+        })
+    }
+}
+
+impl Verifier for VapidVerifier {
+    type Identity = VapidIdentity;
+
+    fn verify(
+        &self,
+        credentials: &Credentials,
+        _ctx: &VerifyContext<'_>,
+    ) -> Result<Self::Identity, Rejection> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.as_secs())
+            .unwrap_or_default();
+        self.verify_at(credentials, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use base64::Engine as _;
+
+    use crate::schemes::vapid::{self, jwt::demo_signing_key};
+
+    #[test]
+    fn test_verify() {
+        let aud = "https://push.example.net";
+        let c = vapid::Credentials::sign(&Claims::new(aud, 1000), &demo_signing_key());
+        let public_key = c.public_key.clone();
+        let credentials = Credentials::Vapid(c);
+
+        let identity = VapidVerifier::new(aud)
+            .verify_at(&credentials, 100)
+            .unwrap();
+        assert_eq!(identity.public_key, public_key);
+
+        match VapidVerifier::new(aud)
+            .allow_public_key("foo")
+            .verify_at(&credentials, 100)
+        {
+            Err(Rejection::Invalid) => {}
+            x => panic!("{x:?}"),
+        }
+        // Padded and standard base64 encodings of the same key.
+        let public_key_bytes = decode_public_key(&public_key).unwrap();
+        for allowed in [
+            alloc::format!("{public_key}="),
+            base64::engine::general_purpose::STANDARD.encode(&public_key_bytes),
+        ] {
+            let identity = VapidVerifier::new(aud)
+                .allow_public_key(allowed)
+                .verify_at(&credentials, 100)
+                .unwrap();
+            assert_eq!(identity.public_key, public_key);
+        }
+
+        match VapidVerifier::new(aud).verify_at(&credentials, 1000) {
+            Err(Rejection::Invalid) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Client-side selection of the challenge to answer.
+
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use crate::challenge::Challenge;
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChallengeKind {
+    DigestSha512_256,
+    DigestSha256,
+    DigestMd5,
+    Bearer,
+    Basic,
+}
+
+impl ChallengeKind {
+    // Exhaustive without a fallback arm, `Challenge` has no variants when no scheme is enabled.
+    pub fn of(challenge: &Challenge) -> Self {
+        match *challenge {
+            #[cfg(feature = "scheme-basic")]
+            Challenge::Basic(_) => Self::Basic,
+            #[cfg(feature = "scheme-bearer")]
+            Challenge::Bearer(_) => Self::Bearer,
+            #[cfg(feature = "scheme-digest")]
+            Challenge::Digest(ref c) => {
+                use crate::schemes::digest::Algorithm;
+
+                match c.algorithm() {
+                    Algorithm::Md5 | Algorithm::Md5Sess => Self::DigestMd5,
+                    Algorithm::Sha256 | Algorithm::Sha256Sess => Self::DigestSha256,
+                    Algorithm::Sha512_256 | Algorithm::Sha512_256Sess => Self::DigestSha512_256,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SkipReason {
+    /// The kind is not in the preference list.
+    NotAccepted,
+    RealmMismatch,
+    /// Basic is refused because the request is not sent over TLS.
+    BasicOverPlaintext,
+    /// Another challenge was preferred.
+    LowerPreference,
+}
+
+#[derive(Debug, Clone)]
+pub struct Selection<'a> {
+    pub chosen: Option<&'a Challenge>,
+    pub skipped: Vec<(&'a Challenge, SkipReason)>,
+}
+
+//
+#[derive(Debug, Clone)]
+pub struct ChallengeSelector {
+    preference: Vec<ChallengeKind>,
+    realm: Option<Box<str>>,
+    allow_basic_over_plaintext: bool,
+}
+
+impl Default for ChallengeSelector {
+    fn default() -> Self {
+        Self {
+            preference: vec![
+                ChallengeKind::DigestSha512_256,
+                ChallengeKind::DigestSha256,
+                ChallengeKind::DigestMd5,
+                ChallengeKind::Bearer,
+                ChallengeKind::Basic,
+            ],
+            realm: None,
+            allow_basic_over_plaintext: false,
+        }
+    }
+}
+
+impl ChallengeSelector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Most preferred first, kinds not in the list are never chosen.
+    pub fn preference(mut self, preference: impl Into<Vec<ChallengeKind>>) -> Self {
+        self.preference = preference.into();
+        self
+    }
+
+    pub fn realm(mut self, realm: impl AsRef<str>) -> Self {
+        self.realm = Some(realm.as_ref().into());
+        self
+    }
+
+    pub fn allow_basic_over_plaintext(mut self, allow: bool) -> Self {
+        self.allow_basic_over_plaintext = allow;
+        self
+    }
+
+    /// `tls` is whether the request is sent over TLS.
+    pub fn select<'a>(&self, challenges: &'a [Challenge], tls: bool) -> Selection<'a> {
+        let mut chosen: Option<(usize, &'a Challenge)> = None;
+        let mut skipped = Vec::new();
+
+        for c in challenges {
+            let kind = ChallengeKind::of(c);
+            let rank = match self.preference.iter().position(|x| *x == kind) {
+                Some(rank) => rank,
+                None => {
+                    skipped.push((c, SkipReason::NotAccepted));
+                    continue;
+                }
+            };
+            if let Some(realm) = &self.realm {
+                if realm_of(c) != Some(realm) {
+                    skipped.push((c, SkipReason::RealmMismatch));
+                    continue;
+                }
+            }
+            if kind == ChallengeKind::Basic && !tls && !self.allow_basic_over_plaintext {
+                skipped.push((c, SkipReason::BasicOverPlaintext));
+                continue;
+            }
+
+            match chosen {
+                Some((chosen_rank, _)) if chosen_rank <= rank => {
+                    skipped.push((c, SkipReason::LowerPreference));
+                }
+                Some((_, prev)) => {
+                    skipped.push((prev, SkipReason::LowerPreference));
+                    chosen = Some((rank, c));
+                }
+                None => chosen = Some((rank, c)),
+            }
+        }
+
+        Selection {
+            chosen: chosen.map(|(_, c)| c),
+            skipped,
+        }
+    }
+
+    /// Same as [`ChallengeSelector::select`], TLS is derived from the scheme of `request_uri`.
+    ///
+    /// Only an absolute URI with the `https` or `wss` scheme counts as TLS.
+    #[cfg(feature = "http-request-uri")]
+    pub fn select_for_request_uri<'a>(
+        &self,
+        challenges: &'a [Challenge],
+        request_uri: &http_request_uri::RequestUri<'_>,
+    ) -> Selection<'a> {
+        let tls = match request_uri {
+            http_request_uri::RequestUri::AbsoluteUri { scheme, .. } => {
+                scheme.eq_ignore_ascii_case("https") || scheme.eq_ignore_ascii_case("wss")
+            }
+            _ => false,
+        };
+        self.select(challenges, tls)
+    }
+}
+
+#[allow(unused_variables)]
+pub(crate) fn realm_of(challenge: &Challenge) -> Option<&str> {
+    match challenge {
+        #[cfg(feature = "scheme-basic")]
+        Challenge::Basic(c) => Some(&c.realm),
+        #[cfg(feature = "scheme-bearer")]
+        Challenge::Bearer(c) => Some(&c.realm),
+        #[cfg(feature = "scheme-digest")]
+        Challenge::Digest(c) => Some(&c.realm),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "scheme-basic",
+        feature = "scheme-bearer",
+        feature = "scheme-digest"
+    ))]
+    #[test]
+    fn test_select() {
+        use crate::schemes::{basic, bearer, digest};
+
+        let mut digest_sha256 = digest::Challenge::new("foo", "abc");
+        digest_sha256.algorithm = Some(digest::Algorithm::Sha256);
+        let challenges = [
+            Challenge::from(basic::Challenge::new("foo")),
+            Challenge::from(digest::Challenge::new("foo", "abc")),
+            Challenge::from(bearer::Challenge::new("bar")),
+            Challenge::from(digest_sha256),
+        ];
+
+        let selection = ChallengeSelector::new().select(&challenges, true);
+        assert_eq!(
+            selection.chosen.map(ChallengeKind::of),
+            Some(ChallengeKind::DigestSha256)
+        );
+        assert_eq!(
+            selection
+                .skipped
+                .iter()
+                .map(|(c, reason)| (ChallengeKind::of(c), *reason))
+                .collect::<Vec<_>>(),
+            [
+                (ChallengeKind::Basic, SkipReason::LowerPreference),
+                (ChallengeKind::Bearer, SkipReason::LowerPreference),
+                (ChallengeKind::DigestMd5, SkipReason::LowerPreference),
+            ]
+        );
+
+        //
+        let selector = ChallengeSelector::new()
+            .preference([ChallengeKind::Bearer, ChallengeKind::Basic])
+            .realm("foo");
+        let selection = selector.select(&challenges, false);
+        assert!(selection.chosen.is_none());
+        assert_eq!(
+            selection
+                .skipped
+                .iter()
+                .map(|(_, reason)| *reason)
+                .collect::<Vec<_>>(),
+            [
+                SkipReason::BasicOverPlaintext,
+                SkipReason::NotAccepted,
+                SkipReason::RealmMismatch,
+                SkipReason::NotAccepted,
+            ]
+        );
+
+        let selection = selector
+            .allow_basic_over_plaintext(true)
+            .select(&challenges, false);
+        assert_eq!(
+            selection.chosen.map(ChallengeKind::of),
+            Some(ChallengeKind::Basic)
+        );
+    }
+
+    #[cfg(all(feature = "scheme-basic", feature = "http-request-uri"))]
+    #[test]
+    fn test_select_for_request_uri() {
+        use http_request_uri::RequestUri;
+
+        use crate::schemes::basic;
+
+        let challenges = [Challenge::from(basic::Challenge::new("foo"))];
+        let uri = |scheme| RequestUri::AbsoluteUri {
+            scheme,
+            username: None,
+            password: None,
+            host: "example.com",
+            port: None,
+            path: "/",
+            query: None,
+            fragment: None,
+        };
+
+        let selector = ChallengeSelector::new();
+        assert!(selector
+            .select_for_request_uri(&challenges, &uri("https"))
+            .chosen
+            .is_some());
+        assert_eq!(
+            selector
+                .select_for_request_uri(&challenges, &uri("http"))
+                .skipped[0]
+                .1,
+            SkipReason::BasicOverPlaintext
+        );
+    }
+}
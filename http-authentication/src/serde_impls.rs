@@ -0,0 +1,917 @@
+//! `Credentials`, `Challenge`, `Challenges` and the scheme structs serialize as their header string form,
+//! [`Structured`] serializes them as objects instead.
+//! Deserializing accepts either form, so it requires a self-describing format.
+//! Parse errors serialize as their `Display` string.
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest",
+    feature = "scheme-vapid",
+    feature = "scheme-concealed"
+))]
+use alloc::boxed::Box;
+use alloc::{
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use core::{fmt, marker::PhantomData, str::FromStr as _};
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest",
+    feature = "scheme-vapid",
+    feature = "scheme-concealed"
+))]
+use serde::ser::SerializeMap as _;
+use serde::{
+    de::{self, value::MapAccessDeserializer, DeserializeOwned, MapAccess, SeqAccess, Visitor},
+    ser::SerializeSeq as _,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest"
+))]
+use crate::CHALLENGE_PARAM_REALM as PARAM_REALM;
+use crate::{
+    challenge::Challenge,
+    challenges::{Challenges, ChallengesParseError},
+    credentials::{Credentials, CredentialsParseError},
+};
+
+//
+#[cfg(any(
+    feature = "scheme-basic",
+    feature = "scheme-bearer",
+    feature = "scheme-digest",
+    feature = "scheme-vapid",
+    feature = "scheme-concealed"
+))]
+const FIELD_SCHEME: &str = "scheme";
+
+//
+/// Serializes as an object instead of the header string form.
+///
+/// Secrets, the Basic password, the Bearer and vapid tokens and the Concealed proof, are omitted unless [`Structured::with_secrets`] is called.
+#[derive(Debug, Clone, Copy)]
+pub struct Structured<'a, T: ?Sized> {
+    inner: &'a T,
+    secrets: bool,
+}
+
+impl<'a, T: ?Sized> Structured<'a, T> {
+    pub fn new(inner: &'a T) -> Self {
+        Self {
+            inner,
+            secrets: false,
+        }
+    }
+
+    pub fn with_secrets(mut self) -> Self {
+        self.secrets = true;
+        self
+    }
+}
+
+impl<T: StructuredSerialize + ?Sized> Serialize for Structured<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.inner.serialize_structured(serializer, self.secrets)
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Implemented by the types that [`Structured`] can wrap, it is sealed.
+pub trait StructuredSerialize: sealed::Sealed {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        secrets: bool,
+    ) -> Result<S::Ok, S::Error>;
+}
+
+impl sealed::Sealed for Credentials {}
+
+#[allow(unused_variables)]
+impl StructuredSerialize for Credentials {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        secrets: bool,
+    ) -> Result<S::Ok, S::Error> {
+        match *self {
+            #[cfg(feature = "scheme-basic")]
+            Self::Basic(ref c) => c.serialize_structured(serializer, secrets),
+            #[cfg(feature = "scheme-bearer")]
+            Self::Bearer(ref c) => c.serialize_structured(serializer, secrets),
+            #[cfg(feature = "scheme-vapid")]
+            Self::Vapid(ref c) => c.serialize_structured(serializer, secrets),
+            #[cfg(feature = "scheme-concealed")]
+            Self::Concealed(ref c) => c.serialize_structured(serializer, secrets),
+        }
+    }
+}
+
+impl sealed::Sealed for Challenge {}
+
+#[allow(unused_variables)]
+impl StructuredSerialize for Challenge {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        secrets: bool,
+    ) -> Result<S::Ok, S::Error> {
+        match *self {
+            #[cfg(feature = "scheme-basic")]
+            Self::Basic(ref c) => c.serialize_structured(serializer, secrets),
+            #[cfg(feature = "scheme-bearer")]
+            Self::Bearer(ref c) => c.serialize_structured(serializer, secrets),
+            #[cfg(feature = "scheme-digest")]
+            Self::Digest(ref c) => c.serialize_structured(serializer, secrets),
+        }
+    }
+}
+
+impl sealed::Sealed for Challenges {}
+
+impl StructuredSerialize for Challenges {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        secrets: bool,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for c in self.iter() {
+            seq.serialize_element(&Structured { inner: c, secrets })?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(feature = "scheme-basic")]
+impl sealed::Sealed for crate::schemes::basic::Credentials {}
+
+#[cfg(feature = "scheme-basic")]
+impl StructuredSerialize for crate::schemes::basic::Credentials {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        secrets: bool,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(FIELD_SCHEME, crate::schemes::NAME_BASIC)?;
+        map.serialize_entry("user_id", &self.user_id)?;
+        if secrets {
+            map.serialize_entry("password", &self.password)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "scheme-basic")]
+impl sealed::Sealed for crate::schemes::basic::Challenge {}
+
+#[cfg(feature = "scheme-basic")]
+impl StructuredSerialize for crate::schemes::basic::Challenge {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        _secrets: bool,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(FIELD_SCHEME, crate::schemes::NAME_BASIC)?;
+        map.serialize_entry(PARAM_REALM, &self.realm)?;
+        if let Some(charset) = &self.charset {
+            map.serialize_entry(crate::schemes::basic::challenge::PARAM_CHARSET, charset)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "scheme-bearer")]
+impl sealed::Sealed for crate::schemes::bearer::Credentials {}
+
+#[cfg(feature = "scheme-bearer")]
+impl StructuredSerialize for crate::schemes::bearer::Credentials {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        secrets: bool,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(FIELD_SCHEME, crate::schemes::NAME_BEARER)?;
+        if secrets {
+            map.serialize_entry("token", &self.token)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "scheme-vapid")]
+impl sealed::Sealed for crate::schemes::vapid::Credentials {}
+
+#[cfg(feature = "scheme-vapid")]
+impl StructuredSerialize for crate::schemes::vapid::Credentials {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        secrets: bool,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(FIELD_SCHEME, crate::schemes::NAME_VAPID)?;
+        map.serialize_entry("public_key", &self.public_key)?;
+        if secrets {
+            map.serialize_entry("token", &self.token)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "scheme-concealed")]
+impl sealed::Sealed for crate::schemes::concealed::Credentials {}
+
+#[cfg(feature = "scheme-concealed")]
+impl StructuredSerialize for crate::schemes::concealed::Credentials {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        secrets: bool,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(FIELD_SCHEME, crate::schemes::NAME_CONCEALED)?;
+        map.serialize_entry("key_id", &self.key_id)?;
+        map.serialize_entry("public_key", &self.public_key)?;
+        map.serialize_entry("signature_scheme", &self.signature_scheme)?;
+        if secrets {
+            map.serialize_entry("proof", &self.proof)?;
+            map.serialize_entry("verification", &self.verification)?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "scheme-bearer")]
+impl sealed::Sealed for crate::schemes::bearer::Challenge {}
+
+#[cfg(feature = "scheme-bearer")]
+impl StructuredSerialize for crate::schemes::bearer::Challenge {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        _secrets: bool,
+    ) -> Result<S::Ok, S::Error> {
+        use crate::schemes::bearer::challenge::{
+            PARAM_ERROR, PARAM_ERROR_DESCRIPTION, PARAM_ERROR_URI, PARAM_SCOPE,
+        };
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(FIELD_SCHEME, crate::schemes::NAME_BEARER)?;
+        map.serialize_entry(PARAM_REALM, &self.realm)?;
+        for (k, v) in [
+            (PARAM_SCOPE, &self.scope),
+            (PARAM_ERROR, &self.error),
+            (PARAM_ERROR_DESCRIPTION, &self.error_description),
+            (PARAM_ERROR_URI, &self.error_uri),
+        ] {
+            if let Some(v) = v {
+                map.serialize_entry(k, v)?;
+            }
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "scheme-digest")]
+impl sealed::Sealed for crate::schemes::digest::Challenge {}
+
+#[cfg(feature = "scheme-digest")]
+impl StructuredSerialize for crate::schemes::digest::Challenge {
+    fn serialize_structured<S: Serializer>(
+        &self,
+        serializer: S,
+        _secrets: bool,
+    ) -> Result<S::Ok, S::Error> {
+        use crate::schemes::digest::challenge::{
+            PARAM_ALGORITHM, PARAM_CHARSET, PARAM_DOMAIN, PARAM_NONCE, PARAM_OPAQUE, PARAM_QOP,
+            PARAM_STALE, PARAM_USERHASH,
+        };
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry(FIELD_SCHEME, crate::schemes::NAME_DIGEST)?;
+        map.serialize_entry(PARAM_REALM, &self.realm)?;
+        map.serialize_entry(PARAM_NONCE, &self.nonce)?;
+        for (k, v) in [
+            (PARAM_DOMAIN, &self.domain),
+            (PARAM_OPAQUE, &self.opaque),
+            (PARAM_QOP, &self.qop),
+            (PARAM_CHARSET, &self.charset),
+        ] {
+            if let Some(v) = v {
+                map.serialize_entry(k, v)?;
+            }
+        }
+        if let Some(algorithm) = &self.algorithm {
+            map.serialize_entry(PARAM_ALGORITHM, algorithm)?;
+        }
+        if self.stale {
+            map.serialize_entry(PARAM_STALE, &true)?;
+        }
+        if self.userhash {
+            map.serialize_entry(PARAM_USERHASH, &true)?;
+        }
+        map.end()
+    }
+}
+
+//
+//
+//
+macro_rules! serialize_with_display {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Serialize for $ty {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(self)
+                }
+            }
+        )*
+    };
+}
+
+serialize_with_display!(
+    Credentials,
+    Challenge,
+    Challenges,
+    CredentialsParseError,
+    ChallengesParseError,
+);
+
+#[cfg(feature = "scheme-basic")]
+serialize_with_display!(
+    crate::schemes::basic::Credentials,
+    crate::schemes::basic::Challenge,
+    crate::schemes::basic::CredentialsParseError,
+    crate::schemes::basic::ChallengeParseError,
+);
+
+#[cfg(feature = "scheme-bearer")]
+serialize_with_display!(
+    crate::schemes::bearer::Credentials,
+    crate::schemes::bearer::Challenge,
+    crate::schemes::bearer::CredentialsParseError,
+    crate::schemes::bearer::ChallengeParseError,
+);
+
+#[cfg(feature = "scheme-vapid")]
+serialize_with_display!(
+    crate::schemes::vapid::Credentials,
+    crate::schemes::vapid::CredentialsParseError,
+);
+
+#[cfg(feature = "scheme-concealed")]
+serialize_with_display!(
+    crate::schemes::concealed::Credentials,
+    crate::schemes::concealed::CredentialsParseError,
+);
+
+#[cfg(feature = "scheme-digest")]
+serialize_with_display!(
+    crate::schemes::digest::Challenge,
+    crate::schemes::digest::ChallengeParseError,
+    crate::schemes::digest::Algorithm,
+);
+
+//
+//
+//
+trait HeaderForm: Sized {
+    const EXPECTING: &'static str;
+    type Repr: DeserializeOwned;
+
+    fn parse(s: &str) -> Result<Self, String>;
+    fn from_repr(repr: Self::Repr) -> Self;
+}
+
+struct HeaderFormVisitor<T>(PhantomData<T>);
+
+impl<'de, T: HeaderForm> Visitor<'de> for HeaderFormVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(T::EXPECTING)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        T::parse(v).map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        T::Repr::deserialize(MapAccessDeserializer::new(map)).map(T::from_repr)
+    }
+}
+
+macro_rules! deserialize_with_header_form {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<'de> Deserialize<'de> for $ty {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    deserializer.deserialize_any(HeaderFormVisitor(PhantomData))
+                }
+            }
+        )*
+    };
+}
+
+deserialize_with_header_form!(Credentials, Challenge);
+
+#[cfg(feature = "scheme-basic")]
+deserialize_with_header_form!(
+    crate::schemes::basic::Credentials,
+    crate::schemes::basic::Challenge,
+);
+
+#[cfg(feature = "scheme-bearer")]
+deserialize_with_header_form!(
+    crate::schemes::bearer::Credentials,
+    crate::schemes::bearer::Challenge,
+);
+
+#[cfg(feature = "scheme-vapid")]
+deserialize_with_header_form!(crate::schemes::vapid::Credentials);
+
+#[cfg(feature = "scheme-concealed")]
+deserialize_with_header_form!(crate::schemes::concealed::Credentials);
+
+#[cfg(feature = "scheme-digest")]
+deserialize_with_header_form!(crate::schemes::digest::Challenge);
+
+#[cfg(feature = "scheme-digest")]
+impl<'de> Deserialize<'de> for crate::schemes::digest::Algorithm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|_| de::Error::invalid_value(de::Unexpected::Str(&s), &"a Digest algorithm"))
+    }
+}
+
+//
+#[derive(Deserialize)]
+#[serde(tag = "scheme")]
+enum CredentialsRepr {
+    #[cfg(feature = "scheme-basic")]
+    Basic(BasicCredentialsRepr),
+    #[cfg(feature = "scheme-bearer")]
+    Bearer(BearerCredentialsRepr),
+    #[cfg(feature = "scheme-vapid")]
+    #[serde(rename = "vapid")]
+    Vapid(VapidCredentialsRepr),
+    #[cfg(feature = "scheme-concealed")]
+    Concealed(ConcealedCredentialsRepr),
+}
+
+impl HeaderForm for Credentials {
+    const EXPECTING: &'static str = "a credentials string or map";
+    type Repr = CredentialsRepr;
+
+    fn parse(s: &str) -> Result<Self, String> {
+        Self::from_str(s).map_err(|err| err.to_string())
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        match repr {
+            #[cfg(feature = "scheme-basic")]
+            CredentialsRepr::Basic(c) => Self::Basic(HeaderForm::from_repr(c)),
+            #[cfg(feature = "scheme-bearer")]
+            CredentialsRepr::Bearer(c) => Self::Bearer(HeaderForm::from_repr(c)),
+            #[cfg(feature = "scheme-vapid")]
+            CredentialsRepr::Vapid(c) => Self::Vapid(HeaderForm::from_repr(c)),
+            #[cfg(feature = "scheme-concealed")]
+            CredentialsRepr::Concealed(c) => Self::Concealed(HeaderForm::from_repr(c)),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "scheme")]
+enum ChallengeRepr {
+    #[cfg(feature = "scheme-basic")]
+    Basic(BasicChallengeRepr),
+    #[cfg(feature = "scheme-bearer")]
+    Bearer(BearerChallengeRepr),
+    #[cfg(feature = "scheme-digest")]
+    Digest(DigestChallengeRepr),
+}
+
+impl HeaderForm for Challenge {
+    const EXPECTING: &'static str = "a challenge string or map";
+    type Repr = ChallengeRepr;
+
+    // `Challenge` has no variants when no challenge scheme is enabled.
+    #[allow(unreachable_code)]
+    fn parse(s: &str) -> Result<Self, String> {
+        let mut list = Challenges::from_str(s).map_err(|err| err.to_string())?.0;
+        if list.len() != 1 {
+            return Err("expected exactly one challenge".into());
+        }
+        Ok(list.remove(0))
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        match repr {
+            #[cfg(feature = "scheme-basic")]
+            ChallengeRepr::Basic(c) => Self::Basic(HeaderForm::from_repr(c)),
+            #[cfg(feature = "scheme-bearer")]
+            ChallengeRepr::Bearer(c) => Self::Bearer(HeaderForm::from_repr(c)),
+            #[cfg(feature = "scheme-digest")]
+            ChallengeRepr::Digest(c) => Self::Digest(HeaderForm::from_repr(c)),
+        }
+    }
+}
+
+//
+#[cfg(feature = "scheme-basic")]
+#[derive(Deserialize)]
+struct BasicCredentialsRepr {
+    user_id: Box<str>,
+    password: Box<str>,
+}
+
+#[cfg(feature = "scheme-basic")]
+impl HeaderForm for crate::schemes::basic::Credentials {
+    const EXPECTING: &'static str = "a Basic credentials string or map";
+    type Repr = BasicCredentialsRepr;
+
+    fn parse(s: &str) -> Result<Self, String> {
+        Self::from_str(s).map_err(|err| err.to_string())
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self {
+            user_id: repr.user_id,
+            password: repr.password,
+        }
+    }
+}
+
+#[cfg(feature = "scheme-basic")]
+#[derive(Deserialize)]
+struct BasicChallengeRepr {
+    realm: Box<str>,
+    #[serde(default)]
+    charset: Option<Box<str>>,
+}
+
+#[cfg(feature = "scheme-basic")]
+impl HeaderForm for crate::schemes::basic::Challenge {
+    const EXPECTING: &'static str = "a Basic challenge string or map";
+    type Repr = BasicChallengeRepr;
+
+    fn parse(s: &str) -> Result<Self, String> {
+        match Challenge::parse(s)? {
+            Challenge::Basic(c) => Ok(c),
+            #[allow(unreachable_patterns)]
+            _ => Err("scheme mismatch".into()),
+        }
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self {
+            realm: repr.realm,
+            charset: repr.charset,
+        }
+    }
+}
+
+//
+#[cfg(feature = "scheme-bearer")]
+#[derive(Deserialize)]
+struct BearerCredentialsRepr {
+    token: Box<str>,
+}
+
+#[cfg(feature = "scheme-bearer")]
+impl HeaderForm for crate::schemes::bearer::Credentials {
+    const EXPECTING: &'static str = "a Bearer credentials string or map";
+    type Repr = BearerCredentialsRepr;
+
+    fn parse(s: &str) -> Result<Self, String> {
+        Self::from_str(s).map_err(|err| err.to_string())
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self { token: repr.token }
+    }
+}
+
+#[cfg(feature = "scheme-vapid")]
+#[derive(Deserialize)]
+struct VapidCredentialsRepr {
+    token: Box<str>,
+    public_key: Box<str>,
+}
+
+#[cfg(feature = "scheme-vapid")]
+impl HeaderForm for crate::schemes::vapid::Credentials {
+    const EXPECTING: &'static str = "a vapid credentials string or map";
+    type Repr = VapidCredentialsRepr;
+
+    fn parse(s: &str) -> Result<Self, String> {
+        Self::from_str(s).map_err(|err| err.to_string())
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self {
+            token: repr.token,
+            public_key: repr.public_key,
+        }
+    }
+}
+
+#[cfg(feature = "scheme-concealed")]
+#[derive(Deserialize)]
+struct ConcealedCredentialsRepr {
+    key_id: Box<str>,
+    public_key: Box<str>,
+    proof: Box<str>,
+    signature_scheme: u16,
+    verification: Box<str>,
+}
+
+#[cfg(feature = "scheme-concealed")]
+impl HeaderForm for crate::schemes::concealed::Credentials {
+    const EXPECTING: &'static str = "a Concealed credentials string or map";
+    type Repr = ConcealedCredentialsRepr;
+
+    fn parse(s: &str) -> Result<Self, String> {
+        Self::from_str(s).map_err(|err| err.to_string())
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self {
+            key_id: repr.key_id,
+            public_key: repr.public_key,
+            proof: repr.proof,
+            signature_scheme: repr.signature_scheme,
+            verification: repr.verification,
+        }
+    }
+}
+
+#[cfg(feature = "scheme-bearer")]
+#[derive(Deserialize)]
+struct BearerChallengeRepr {
+    realm: Box<str>,
+    #[serde(default)]
+    scope: Option<Box<str>>,
+    #[serde(default)]
+    error: Option<Box<str>>,
+    #[serde(default)]
+    error_description: Option<Box<str>>,
+    #[serde(default)]
+    error_uri: Option<Box<str>>,
+}
+
+#[cfg(feature = "scheme-bearer")]
+impl HeaderForm for crate::schemes::bearer::Challenge {
+    const EXPECTING: &'static str = "a Bearer challenge string or map";
+    type Repr = BearerChallengeRepr;
+
+    fn parse(s: &str) -> Result<Self, String> {
+        match Challenge::parse(s)? {
+            Challenge::Bearer(c) => Ok(c),
+            #[allow(unreachable_patterns)]
+            _ => Err("scheme mismatch".into()),
+        }
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self {
+            realm: repr.realm,
+            scope: repr.scope,
+            error: repr.error,
+            error_description: repr.error_description,
+            error_uri: repr.error_uri,
+        }
+    }
+}
+
+//
+#[cfg(feature = "scheme-digest")]
+#[derive(Deserialize)]
+struct DigestChallengeRepr {
+    realm: Box<str>,
+    #[serde(default)]
+    domain: Option<Box<str>>,
+    nonce: Box<str>,
+    #[serde(default)]
+    opaque: Option<Box<str>>,
+    #[serde(default)]
+    stale: bool,
+    #[serde(default)]
+    algorithm: Option<crate::schemes::digest::Algorithm>,
+    #[serde(default)]
+    qop: Option<Box<str>>,
+    #[serde(default)]
+    charset: Option<Box<str>>,
+    #[serde(default)]
+    userhash: bool,
+}
+
+#[cfg(feature = "scheme-digest")]
+impl HeaderForm for crate::schemes::digest::Challenge {
+    const EXPECTING: &'static str = "a Digest challenge string or map";
+    type Repr = DigestChallengeRepr;
+
+    fn parse(s: &str) -> Result<Self, String> {
+        match Challenge::parse(s)? {
+            Challenge::Digest(c) => Ok(c),
+            #[allow(unreachable_patterns)]
+            _ => Err("scheme mismatch".into()),
+        }
+    }
+
+    fn from_repr(repr: Self::Repr) -> Self {
+        Self {
+            realm: repr.realm,
+            domain: repr.domain,
+            nonce: repr.nonce,
+            opaque: repr.opaque,
+            stale: repr.stale,
+            algorithm: repr.algorithm,
+            qop: repr.qop,
+            charset: repr.charset,
+            userhash: repr.userhash,
+        }
+    }
+}
+
+//
+struct ChallengesVisitor;
+
+impl<'de> Visitor<'de> for ChallengesVisitor {
+    type Value = Challenges;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a challenges string or a sequence of challenges")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Challenges::from_str(v).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut list = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(8));
+        while let Some(c) = seq.next_element::<Challenge>()? {
+            list.push(c);
+        }
+        Ok(Challenges::new(list))
+    }
+}
+
+impl<'de> Deserialize<'de> for Challenges {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ChallengesVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(feature = "scheme-basic")]
+    #[test]
+    fn test_credentials() {
+        use crate::schemes::basic::{
+            DEMO_CREDENTIALS_PASSWORD_STR, DEMO_CREDENTIALS_STR, DEMO_CREDENTIALS_USER_ID_STR,
+        };
+
+        let c = Credentials::from_str(DEMO_CREDENTIALS_STR).unwrap();
+
+        let s = serde_json::to_string(&c).unwrap();
+        assert_eq!(s, alloc::format!(r#""{DEMO_CREDENTIALS_STR}""#));
+        let c: Credentials = serde_json::from_str(&s).unwrap();
+        assert_eq!(c.to_string(), DEMO_CREDENTIALS_STR);
+
+        let s = serde_json::to_string(&Structured::new(&c)).unwrap();
+        assert_eq!(
+            s,
+            alloc::format!(r#"{{"scheme":"Basic","user_id":"{DEMO_CREDENTIALS_USER_ID_STR}"}}"#)
+        );
+        match serde_json::from_str::<Credentials>(&s) {
+            Err(err) => assert!(err.to_string().contains("password")),
+            x => panic!("{x:?}"),
+        }
+
+        let s = serde_json::to_string(&Structured::new(&c).with_secrets()).unwrap();
+        assert_eq!(
+            s,
+            alloc::format!(
+                r#"{{"scheme":"Basic","user_id":"{DEMO_CREDENTIALS_USER_ID_STR}","password":"{DEMO_CREDENTIALS_PASSWORD_STR}"}}"#
+            )
+        );
+        let c: Credentials = serde_json::from_str(&s).unwrap();
+        assert_eq!(c.to_string(), DEMO_CREDENTIALS_STR);
+
+        //
+        match serde_json::from_str::<Credentials>(r#""Basic  Zm9v""#) {
+            Err(err) => assert!(err.to_string().contains("Token68Invalid")),
+            x => panic!("{x:?}"),
+        }
+
+        let err = Credentials::from_str("Foo bar").unwrap_err();
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            alloc::format!("{:?}", err.to_string())
+        );
+    }
+
+    #[cfg(all(feature = "scheme-basic", feature = "scheme-bearer"))]
+    #[test]
+    fn test_challenges() {
+        use crate::schemes::{basic, bearer};
+
+        // Generic code over `Structured` names the trait through the crate root.
+        fn to_value<T: crate::StructuredSerialize + ?Sized>(x: &T) -> serde_json::Value {
+            serde_json::to_value(Structured::new(x)).unwrap()
+        }
+
+        let s = alloc::format!(
+            "{}, {}",
+            basic::DEMO_CHALLENGE_STR,
+            bearer::DEMO_CHALLENGE_STR
+        );
+        let c = Challenges::from_str(&s).unwrap();
+
+        let json = serde_json::to_string(&c).unwrap();
+        let c: Challenges = serde_json::from_str(&json).unwrap();
+        assert_eq!(c.to_string(), s);
+
+        let json = to_value(&c);
+        assert_eq!(json[0]["scheme"], "Basic");
+        assert_eq!(json[0]["charset"], basic::DEMO_CHALLENGE_CHARSET_STR);
+        assert_eq!(json[1]["scheme"], "Bearer");
+        assert_eq!(json[1]["error"], bearer::DEMO_CHALLENGE_ERROR_STR);
+        assert!(json[1].get("scope").is_none());
+        let c: Challenges = serde_json::from_value(json).unwrap();
+        assert_eq!(c.to_string(), s);
+
+        // mixed forms
+        let c: Challenges = serde_json::from_str(&alloc::format!(
+            r#"[{:?}, {{"scheme": "Bearer", "realm": "bar"}}]"#,
+            basic::DEMO_CHALLENGE_STR_SIMPLE
+        ))
+        .unwrap();
+        assert_eq!(c.len(), 2);
+        assert_eq!(c[1].as_bearer().unwrap().realm, "bar".into());
+
+        //
+        let c: bearer::Challenge = serde_json::from_str(r#"{"realm": "bar"}"#).unwrap();
+        assert_eq!(c.to_string(), r#"Bearer realm="bar""#);
+        match serde_json::from_str::<bearer::Challenge>(&alloc::format!(
+            "{:?}",
+            basic::DEMO_CHALLENGE_STR_SIMPLE
+        )) {
+            Err(err) => assert!(err.to_string().contains("scheme mismatch")),
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[cfg(feature = "scheme-digest")]
+    #[test]
+    fn test_digest_challenge() {
+        use crate::schemes::digest::{Algorithm, DEMO_CHALLENGE_STR};
+
+        let c = Challenge::parse(DEMO_CHALLENGE_STR).unwrap();
+        let json = serde_json::to_value(Structured::new(&c)).unwrap();
+        assert_eq!(json["scheme"], "Digest");
+        assert_eq!(json["algorithm"], "SHA-256");
+        assert!(json.get("stale").is_none());
+
+        let c: crate::schemes::digest::Challenge = serde_json::from_value(json).unwrap();
+        assert_eq!(c.algorithm, Some(Algorithm::Sha256));
+        assert_eq!(c.to_string(), DEMO_CHALLENGE_STR);
+    }
+
+    #[cfg(feature = "scheme-bearer")]
+    #[test]
+    fn test_bearer_credentials_secrets() {
+        use crate::schemes::bearer::{DEMO_CREDENTIALS_STR, DEMO_CREDENTIALS_TOKEN_STR};
+
+        let c = crate::schemes::bearer::Credentials::from_str(DEMO_CREDENTIALS_STR).unwrap();
+        assert_eq!(
+            serde_json::to_string(&Structured::new(&c)).unwrap(),
+            r#"{"scheme":"Bearer"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Structured::new(&c).with_secrets()).unwrap(),
+            alloc::format!(r#"{{"scheme":"Bearer","token":"{DEMO_CREDENTIALS_TOKEN_STR}"}}"#)
+        );
+    }
+}
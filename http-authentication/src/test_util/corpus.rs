@@ -0,0 +1,112 @@
+//! `WWW-Authenticate` values sent by real servers, with the challenges expected from
+//! [`ChallengeRefs`](crate::ChallengeRefs) under [`ParseOptions::lenient`](crate::ParseOptions::lenient).
+//!
+//! Challenges of schemes this crate does not implement are skipped and absent from `expected`.
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    pub server: &'static str,
+    pub header: &'static str,
+    /// `(scheme, realm)` of each parsed challenge, in order.
+    pub expected: &'static [(&'static str, &'static str)],
+}
+
+pub const SAMPLES: &[Sample] = &[
+    Sample {
+        server: "GitHub API",
+        header: r#"Basic realm="GitHub""#,
+        expected: &[("Basic", "GitHub")],
+    },
+    Sample {
+        server: "nginx auth_basic",
+        header: r#"Basic realm="Restricted""#,
+        expected: &[("Basic", "Restricted")],
+    },
+    Sample {
+        server: "Apache mod_auth_basic",
+        header: r#"Basic realm="Restricted Files", charset="UTF-8""#,
+        expected: &[("Basic", "Restricted Files")],
+    },
+    Sample {
+        server: "Apache mod_auth_digest",
+        header: r#"Digest realm="private area", nonce="Mqfv6u5lBQA=ee4bd7ea1c5ed78f12a1d1acd2a2fc5d1a38afdc", algorithm=MD5, qop="auth""#,
+        expected: &[("Digest", "private area")],
+    },
+    Sample {
+        server: "RFC 7616 example",
+        header: r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS", Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=MD5, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#,
+        expected: &[
+            ("Digest", "http-auth@example.org"),
+            ("Digest", "http-auth@example.org"),
+        ],
+    },
+    Sample {
+        server: "Squid",
+        header: r#"Basic realm="Squid proxy-caching web server""#,
+        expected: &[("Basic", "Squid proxy-caching web server")],
+    },
+    Sample {
+        server: "Docker Hub registry",
+        header: r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/ubuntu:pull""#,
+        expected: &[("Bearer", "https://auth.docker.io/token")],
+    },
+    Sample {
+        server: "GitLab container registry",
+        header: r#"Bearer realm="https://gitlab.com/jwt/auth",service="container_registry",scope="repository:group/project:push,pull""#,
+        expected: &[("Bearer", "https://gitlab.com/jwt/auth")],
+    },
+    Sample {
+        server: "Google APIs",
+        header: r#"Bearer realm="https://accounts.google.com/", error="invalid_token""#,
+        expected: &[("Bearer", "https://accounts.google.com/")],
+    },
+    Sample {
+        server: "Microsoft Entra ID protected API",
+        header: r#"Bearer authorization_uri="https://login.microsoftonline.com/common/oauth2/authorize", error="invalid_token", error_description="The access token is missing""#,
+        expected: &[("Bearer", "")],
+    },
+    Sample {
+        server: "IIS",
+        header: r#"Negotiate, NTLM, Basic realm="example.com""#,
+        expected: &[("Basic", "example.com")],
+    },
+    Sample {
+        server: "Elasticsearch",
+        header: r#"Basic realm="security", charset="UTF-8", Bearer realm="security", ApiKey"#,
+        expected: &[("Basic", "security"), ("Bearer", "security")],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(
+        feature = "scheme-basic",
+        feature = "scheme-bearer",
+        feature = "scheme-digest"
+    ))]
+    #[test]
+    fn test_samples() {
+        use alloc::vec::Vec;
+
+        use crate::{ChallengeRef, ChallengeRefs, ParseOptions};
+
+        for sample in SAMPLES {
+            let parsed = ChallengeRefs::with_options(sample.header, ParseOptions::lenient())
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap_or_else(|err| panic!("{} {err:?}", sample.server));
+            let parsed = parsed
+                .iter()
+                .map(|c| match c {
+                    ChallengeRef::Basic(c) => ("Basic", c.realm),
+                    ChallengeRef::Bearer(c) => ("Bearer", c.realm),
+                    ChallengeRef::Digest(c) => ("Digest", c.realm),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(parsed, sample.expected, "{}", sample.server);
+        }
+    }
+}
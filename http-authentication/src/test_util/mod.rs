@@ -0,0 +1,14 @@
+//! Test support for clients, enabled by the `test-util` feature.
+//!
+//! [`TestServer`] is an in-memory server double issuing challenges and validating the responses,
+//! [`corpus`] holds `WWW-Authenticate` values sent by real servers.
+
+//
+pub mod server;
+
+pub use server::{TestFailure, TestResponse, TestServer};
+
+//
+pub mod corpus;
+
+pub use corpus::{Sample, SAMPLES};
@@ -0,0 +1,412 @@
+use alloc::{
+    boxed::Box,
+    string::{String, ToString as _},
+    vec::Vec,
+};
+#[cfg(feature = "scheme-digest")]
+use core::fmt::Write as _;
+
+use crate::{challenge::Challenge, credentials::Credentials};
+
+//
+pub const STATUS_OK: u16 = 200;
+pub const STATUS_UNAUTHORIZED: u16 = 401;
+
+#[cfg(feature = "scheme-digest")]
+pub const DEFAULT_DIGEST_NONCE: &str = "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v";
+
+//
+/// Issues the configured challenges and accepts the configured users and tokens.
+///
+/// Challenges are sent strongest first, Digest, then Bearer, then Basic.
+#[derive(Debug, Clone)]
+pub struct TestServer {
+    #[cfg(any(
+        feature = "scheme-basic",
+        feature = "scheme-bearer",
+        feature = "scheme-digest"
+    ))]
+    realm: Box<str>,
+    users: Vec<(Box<str>, Box<str>)>,
+    #[cfg(feature = "scheme-basic")]
+    basic: bool,
+    #[cfg(feature = "scheme-bearer")]
+    bearer_tokens: Option<Vec<Box<str>>>,
+    #[cfg(feature = "scheme-digest")]
+    digest_algorithms: Vec<crate::schemes::digest::Algorithm>,
+    #[cfg(feature = "scheme-digest")]
+    digest_nonce: Box<str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResponse {
+    pub status: u16,
+    /// The `WWW-Authenticate` values, empty unless the status is 401.
+    pub www_authenticate: Vec<String>,
+    /// The authenticated user-id, or the Bearer token.
+    pub principal: Option<Box<str>>,
+    pub failure: Option<TestFailure>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestFailure {
+    Missing,
+    Malformed(String),
+    SchemeNotOffered(Box<str>),
+    Invalid,
+}
+
+impl TestServer {
+    // `realm` is only used by the challenges of the enabled schemes.
+    #[allow(unused_variables)]
+    pub fn new(realm: impl AsRef<str>) -> Self {
+        Self {
+            #[cfg(any(
+                feature = "scheme-basic",
+                feature = "scheme-bearer",
+                feature = "scheme-digest"
+            ))]
+            realm: realm.as_ref().into(),
+            users: Vec::new(),
+            #[cfg(feature = "scheme-basic")]
+            basic: false,
+            #[cfg(feature = "scheme-bearer")]
+            bearer_tokens: None,
+            #[cfg(feature = "scheme-digest")]
+            digest_algorithms: Vec::new(),
+            #[cfg(feature = "scheme-digest")]
+            digest_nonce: DEFAULT_DIGEST_NONCE.into(),
+        }
+    }
+
+    /// A user accepted by Basic and Digest.
+    pub fn user(mut self, user_id: impl AsRef<str>, password: impl AsRef<str>) -> Self {
+        self.users
+            .push((user_id.as_ref().into(), password.as_ref().into()));
+        self
+    }
+
+    #[cfg(feature = "scheme-basic")]
+    pub fn basic(mut self) -> Self {
+        self.basic = true;
+        self
+    }
+
+    #[cfg(feature = "scheme-bearer")]
+    pub fn bearer_token(mut self, token: impl AsRef<str>) -> Self {
+        self.bearer_tokens
+            .get_or_insert_with(Vec::new)
+            .push(token.as_ref().into());
+        self
+    }
+
+    /// Each call adds a Digest challenge with `qop="auth"`.
+    #[cfg(feature = "scheme-digest")]
+    pub fn digest(mut self, algorithm: crate::schemes::digest::Algorithm) -> Self {
+        self.digest_algorithms.push(algorithm);
+        self
+    }
+
+    #[cfg(feature = "scheme-digest")]
+    pub fn digest_nonce(mut self, nonce: impl AsRef<str>) -> Self {
+        self.digest_nonce = nonce.as_ref().into();
+        self
+    }
+
+    pub fn challenges(&self) -> Vec<Challenge> {
+        #[allow(unused_mut)]
+        let mut list: Vec<Challenge> = Vec::new();
+        #[cfg(feature = "scheme-digest")]
+        for algorithm in &self.digest_algorithms {
+            let mut c = crate::schemes::digest::Challenge::new(&self.realm, &self.digest_nonce);
+            c.qop = Some("auth".into());
+            c.algorithm = Some(*algorithm);
+            list.push(c.into());
+        }
+        #[cfg(feature = "scheme-bearer")]
+        if self.bearer_tokens.is_some() {
+            list.push(crate::schemes::bearer::Challenge::new(&self.realm).into());
+        }
+        #[cfg(feature = "scheme-basic")]
+        if self.basic {
+            list.push(crate::schemes::basic::Challenge::new(&self.realm).into());
+        }
+        list
+    }
+
+    /// `uri` is the request-target as sent, Digest responses must carry the same value.
+    pub fn respond(&self, method: &str, uri: &str, authorization: Option<&str>) -> TestResponse {
+        match self.check(method, uri, authorization) {
+            Ok(principal) => TestResponse {
+                status: STATUS_OK,
+                www_authenticate: Vec::new(),
+                principal: Some(principal),
+                failure: None,
+            },
+            Err(failure) => TestResponse {
+                status: STATUS_UNAUTHORIZED,
+                www_authenticate: self.challenges().iter().map(|x| x.to_string()).collect(),
+                principal: None,
+                failure: Some(failure),
+            },
+        }
+    }
+
+    /// Same as [`TestServer::respond`], the challenges are appended to `WWW-Authenticate`.
+    #[cfg(feature = "http")]
+    pub fn respond_to<B>(&self, request: &http::Request<B>) -> http::Response<()> {
+        let authorization = request
+            .headers()
+            .get(http::header::AUTHORIZATION)
+            .and_then(|x| x.to_str().ok());
+        let uri = request
+            .uri()
+            .path_and_query()
+            .map(|x| x.as_str())
+            .unwrap_or("/");
+        let response = self.respond(request.method().as_str(), uri, authorization);
+
+        let mut builder = http::Response::builder().status(response.status);
+        for value in &response.www_authenticate {
+            builder = builder.header(http::header::WWW_AUTHENTICATE, value.as_str());
+        }
+        builder.body(()).expect("valid response")
+    }
+
+    fn check(
+        &self,
+        method: &str,
+        uri: &str,
+        authorization: Option<&str>,
+    ) -> Result<Box<str>, TestFailure> {
+        let authorization = authorization.ok_or(TestFailure::Missing)?;
+
+        #[cfg(feature = "scheme-digest")]
+        if authorization
+            .get(..crate::schemes::NAME_DIGEST.len() + 1)
+            .map(|x| x.eq_ignore_ascii_case("Digest "))
+            .unwrap_or(false)
+        {
+            return self.check_digest(method, uri, authorization);
+        }
+        let _ = (method, uri);
+
+        let credentials = authorization
+            .parse::<Credentials>()
+            .map_err(|err| TestFailure::Malformed(err.to_string()))?;
+        match &credentials {
+            #[cfg(feature = "scheme-basic")]
+            Credentials::Basic(c) if self.basic => self
+                .users
+                .iter()
+                .find(|(user_id, password)| *user_id == c.user_id && *password == c.password)
+                .map(|(user_id, _)| user_id.clone())
+                .ok_or(TestFailure::Invalid),
+            #[cfg(feature = "scheme-bearer")]
+            Credentials::Bearer(c) if self.bearer_tokens.is_some() => self
+                .bearer_tokens
+                .iter()
+                .flatten()
+                .find(|x| **x == c.token)
+                .cloned()
+                .ok_or(TestFailure::Invalid),
+            #[allow(unreachable_patterns)]
+            _ => Err(TestFailure::SchemeNotOffered(credentials.scheme().into())),
+        }
+    }
+
+    #[cfg(feature = "scheme-digest")]
+    fn check_digest(
+        &self,
+        method: &str,
+        uri: &str,
+        authorization: &str,
+    ) -> Result<Box<str>, TestFailure> {
+        use crate::schemes::digest::Algorithm;
+
+        let malformed = |msg: &str| TestFailure::Malformed(msg.into());
+
+        let c = http_auth::ChallengeParser::new(authorization)
+            .next()
+            .ok_or_else(|| malformed("empty"))?
+            .map_err(|err| TestFailure::Malformed(err.to_string()))?;
+        let param = |name: &str| {
+            c.params
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.to_unescaped())
+        };
+        let require = |name: &'static str| param(name).ok_or_else(|| malformed(name));
+
+        let algorithm = param("algorithm")
+            .map(|x| x.parse::<Algorithm>())
+            .transpose()
+            .map_err(|_| malformed("algorithm"))?
+            .unwrap_or_default();
+        if !self.digest_algorithms.contains(&algorithm) {
+            return Err(TestFailure::SchemeNotOffered(
+                alloc::format!("Digest {algorithm}").into(),
+            ));
+        }
+
+        let username = require("username")?;
+        if require("realm")? != *self.realm
+            || require("nonce")? != *self.digest_nonce
+            || require("uri")? != uri
+        {
+            return Err(TestFailure::Invalid);
+        }
+        let password = self
+            .users
+            .iter()
+            .find(|(user_id, _)| **user_id == username)
+            .map(|(_, password)| password)
+            .ok_or(TestFailure::Invalid)?;
+
+        let qop = require("qop")?;
+        let nc = require("nc")?;
+        let cnonce = require("cnonce")?;
+        if qop != "auth" {
+            return Err(malformed("qop"));
+        }
+
+        let mut h_a1 = digest_hash(
+            algorithm,
+            &alloc::format!("{username}:{}:{password}", self.realm),
+        );
+        if algorithm.is_sess() {
+            h_a1 = digest_hash(
+                algorithm,
+                &alloc::format!("{h_a1}:{}:{cnonce}", self.digest_nonce),
+            );
+        }
+        let h_a2 = digest_hash(algorithm, &alloc::format!("{method}:{uri}"));
+        let expected = digest_hash(
+            algorithm,
+            &alloc::format!("{h_a1}:{}:{nc}:{cnonce}:{qop}:{h_a2}", self.digest_nonce),
+        );
+
+        if require("response")? == expected {
+            Ok(username.into())
+        } else {
+            Err(TestFailure::Invalid)
+        }
+    }
+}
+
+#[cfg(feature = "scheme-digest")]
+fn digest_hash(algorithm: crate::schemes::digest::Algorithm, data: &str) -> String {
+    use crate::schemes::digest::Algorithm;
+    use sha2::Digest as _;
+
+    let digest = match algorithm {
+        Algorithm::Md5 | Algorithm::Md5Sess => md5::Md5::digest(data).to_vec(),
+        Algorithm::Sha256 | Algorithm::Sha256Sess => sha2::Sha256::digest(data).to_vec(),
+        Algorithm::Sha512_256 | Algorithm::Sha512_256Sess => {
+            sha2::Sha512_256::digest(data).to_vec()
+        }
+    };
+    let mut s = String::with_capacity(digest.len() * 2);
+    for b in digest {
+        let _ = write!(s, "{b:02x}");
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[cfg(all(feature = "scheme-basic", feature = "scheme-bearer"))]
+    #[test]
+    fn test_basic_and_bearer() {
+        let server = TestServer::new("test")
+            .user("aladdin", "open sesame")
+            .basic()
+            .bearer_token("mF_9.B5f-4.1JqM");
+
+        let response = server.respond("GET", "/", None);
+        assert_eq!(response.status, STATUS_UNAUTHORIZED);
+        assert_eq!(response.failure, Some(TestFailure::Missing));
+        assert_eq!(
+            response.www_authenticate,
+            [r#"Bearer realm="test""#, r#"Basic realm="test""#]
+        );
+
+        let response = server.respond("GET", "/", Some("Basic YWxhZGRpbjpvcGVuIHNlc2FtZQ=="));
+        assert_eq!(response.status, STATUS_OK);
+        assert_eq!(response.principal, Some("aladdin".into()));
+
+        let response = server.respond("GET", "/", Some("Bearer mF_9.B5f-4.1JqM"));
+        assert_eq!(response.status, STATUS_OK);
+
+        let response = server.respond("GET", "/", Some("Bearer foo"));
+        assert_eq!(response.failure, Some(TestFailure::Invalid));
+
+        let response = server.respond("GET", "/", Some("Foo bar"));
+        assert!(matches!(response.failure, Some(TestFailure::Malformed(_))));
+
+        let server = TestServer::new("test")
+            .user("aladdin", "open sesame")
+            .basic();
+        let response = server.respond("GET", "/", Some("Bearer foo"));
+        assert_eq!(
+            response.failure,
+            Some(TestFailure::SchemeNotOffered("Bearer".into()))
+        );
+    }
+
+    #[cfg(feature = "scheme-digest")]
+    #[test]
+    fn test_digest() {
+        use crate::schemes::digest::Algorithm;
+
+        let server = TestServer::new("http-auth@example.org")
+            .user("Mufasa", "Circle of Life")
+            .digest(Algorithm::Sha256)
+            .digest(Algorithm::Md5);
+        let response = server.respond("GET", "/dir/index.html", None);
+        assert_eq!(response.www_authenticate.len(), 2);
+
+        // Answered by an independent implementation.
+        let mut client =
+            http_auth::PasswordClient::try_from(response.www_authenticate.join(", ").as_str())
+                .unwrap();
+        let authorization = client
+            .respond(&http_auth::PasswordParams {
+                username: "Mufasa",
+                password: "Circle of Life",
+                uri: "/dir/index.html",
+                method: "GET",
+                body: None,
+            })
+            .unwrap();
+        let response = server.respond("GET", "/dir/index.html", Some(&authorization));
+        assert_eq!(response.failure, None);
+        assert_eq!(response.principal, Some("Mufasa".into()));
+
+        let response = server.respond("GET", "/other", Some(&authorization));
+        assert_eq!(response.failure, Some(TestFailure::Invalid));
+
+        // Ref https://www.rfc-editor.org/rfc/rfc7616.html#section-3.9.1
+        let authorization = r#"Digest username="Mufasa", realm="http-auth@example.org", uri="/dir/index.html", algorithm=MD5, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", nc=00000001, cnonce="f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ", qop=auth, response="8ca523f5e9506fed4657c9700eebdbec", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#;
+        let response = server.respond("GET", "/dir/index.html", Some(authorization));
+        assert_eq!(response.principal, Some("Mufasa".into()));
+    }
+
+    #[cfg(all(feature = "http", feature = "scheme-basic"))]
+    #[test]
+    fn test_respond_to() {
+        let server = TestServer::new("test")
+            .user("aladdin", "open sesame")
+            .basic();
+        let request = http::Request::get("/foo?bar").body(()).unwrap();
+        let response = server.respond_to(&request);
+        assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()[http::header::WWW_AUTHENTICATE],
+            r#"Basic realm="test""#
+        );
+    }
+}
@@ -0,0 +1,227 @@
+//! [`headers::Header`] implementations, for `TypedHeader` style access.
+
+use alloc::{string::ToString as _, vec::Vec};
+use core::ops::Deref;
+
+use headers::{Header, HeaderName, HeaderValue};
+use http::header::{AUTHORIZATION, PROXY_AUTHENTICATE, PROXY_AUTHORIZATION, WWW_AUTHENTICATE};
+
+use crate::{challenges::Challenges, credentials::Credentials};
+
+//
+macro_rules! credentials_header {
+    ($(#[$meta:meta])* $name:ident, $header_name:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name(pub Credentials);
+
+        impl Deref for $name {
+            type Target = Credentials;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl From<Credentials> for $name {
+            fn from(c: Credentials) -> Self {
+                Self(c)
+            }
+        }
+
+        impl Header for $name {
+            fn name() -> &'static HeaderName {
+                &$header_name
+            }
+
+            fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+            where
+                I: Iterator<Item = &'i HeaderValue>,
+            {
+                decode_credentials(values).map(Self)
+            }
+
+            fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+                encode(&self.0.to_string(), values)
+            }
+        }
+    };
+}
+
+macro_rules! challenges_header {
+    ($(#[$meta:meta])* $name:ident, $header_name:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name(pub Challenges);
+
+        impl Deref for $name {
+            type Target = Challenges;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl From<Challenges> for $name {
+            fn from(c: Challenges) -> Self {
+                Self(c)
+            }
+        }
+
+        impl Header for $name {
+            fn name() -> &'static HeaderName {
+                &$header_name
+            }
+
+            fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+            where
+                I: Iterator<Item = &'i HeaderValue>,
+            {
+                decode_challenges(values).map(Self)
+            }
+
+            fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+                if !self.0.is_empty() {
+                    encode(&self.0.to_string(), values)
+                }
+            }
+        }
+    };
+}
+
+credentials_header!(
+    /// `Authorization`, only the first value is used.
+    Authorization,
+    AUTHORIZATION
+);
+credentials_header!(
+    /// `Proxy-Authorization`, only the first value is used.
+    ProxyAuthorization,
+    PROXY_AUTHORIZATION
+);
+challenges_header!(
+    /// `WWW-Authenticate`, the challenges of all values are merged in order.
+    WwwAuthenticate,
+    WWW_AUTHENTICATE
+);
+challenges_header!(
+    /// `Proxy-Authenticate`, the challenges of all values are merged in order.
+    ProxyAuthenticate,
+    PROXY_AUTHENTICATE
+);
+
+//
+fn decode_credentials<'i, I>(values: &mut I) -> Result<Credentials, headers::Error>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let value = values.next().ok_or_else(headers::Error::invalid)?;
+    Credentials::from_bytes(value.as_bytes()).map_err(|_| headers::Error::invalid())
+}
+
+fn decode_challenges<'i, I>(values: &mut I) -> Result<Challenges, headers::Error>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let list = values
+        .map(|x| Challenges::from_bytes(x.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| headers::Error::invalid())?;
+    let list = list.into_iter().flat_map(|x| x.0).collect::<Vec<_>>();
+    Ok(Challenges::new(list))
+}
+
+fn encode<E: Extend<HeaderValue>>(s: &str, values: &mut E) {
+    // Rendered values are visible ASCII unless a param or token was built with control characters,
+    // such a value can not be sent, so it is dropped.
+    if let Ok(value) = HeaderValue::from_str(s) {
+        values.extend(core::iter::once(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::*;
+
+    #[allow(unused_imports)]
+    use headers::{HeaderMap, HeaderMapExt as _};
+
+    #[cfg(feature = "scheme-basic")]
+    #[test]
+    fn test_authorization() {
+        use crate::schemes::basic::{
+            DEMO_CREDENTIALS_PASSWORD_STR, DEMO_CREDENTIALS_STR, DEMO_CREDENTIALS_USER_ID_STR,
+        };
+
+        let mut map = HeaderMap::new();
+        assert!(map.typed_get::<Authorization>().is_none());
+
+        map.typed_insert(Authorization(Credentials::basic(
+            DEMO_CREDENTIALS_USER_ID_STR,
+            DEMO_CREDENTIALS_PASSWORD_STR,
+        )));
+        assert_eq!(map.get(AUTHORIZATION).unwrap(), DEMO_CREDENTIALS_STR);
+
+        let c = map.typed_get::<Authorization>().unwrap();
+        let c = c.as_basic().unwrap();
+        assert_eq!(c.user_id, DEMO_CREDENTIALS_USER_ID_STR.into());
+        assert_eq!(c.password, DEMO_CREDENTIALS_PASSWORD_STR.into());
+
+        //
+        let mut map = HeaderMap::new();
+        map.insert(PROXY_AUTHORIZATION, HeaderValue::from_static("Basic-"));
+        match map.typed_try_get::<ProxyAuthorization>() {
+            Err(_) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[cfg(all(feature = "scheme-basic", feature = "scheme-bearer"))]
+    #[test]
+    fn test_www_authenticate() {
+        use crate::schemes::{basic, bearer};
+
+        let mut map = HeaderMap::new();
+        map.append(
+            WWW_AUTHENTICATE,
+            HeaderValue::from_static(basic::DEMO_CHALLENGE_STR_SIMPLE),
+        );
+        map.append(
+            WWW_AUTHENTICATE,
+            HeaderValue::from_static(bearer::DEMO_CHALLENGE_STR_SIMPLE),
+        );
+
+        let c = map.typed_get::<WwwAuthenticate>().unwrap();
+        assert_eq!(c.len(), 2);
+        assert!(c[0].as_basic().is_some());
+        assert!(c[1].as_bearer().is_some());
+
+        let mut map = HeaderMap::new();
+        map.typed_insert(ProxyAuthenticate(c.0));
+        assert_eq!(
+            map.get(PROXY_AUTHENTICATE).unwrap(),
+            &alloc::format!(
+                "{}, {}",
+                basic::DEMO_CHALLENGE_STR_SIMPLE,
+                bearer::DEMO_CHALLENGE_STR_SIMPLE
+            )
+        );
+
+        //
+        let mut map = HeaderMap::new();
+        map.append(WWW_AUTHENTICATE, HeaderValue::from_static("Foo"));
+        match map.typed_try_get::<WwwAuthenticate>() {
+            Err(_) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[cfg(feature = "scheme-bearer")]
+    #[test]
+    fn test_encode_invalid() {
+        let mut map = HeaderMap::new();
+        map.typed_insert(Authorization(Credentials::bearer("foo\nbar")));
+        assert!(map.get(AUTHORIZATION).is_none());
+    }
+}
@@ -0,0 +1,183 @@
+//! Server-side verification of [`Credentials`].
+
+use alloc::boxed::Box;
+use core::{net::IpAddr, time::Duration};
+
+use crate::credentials::{Credentials, CredentialsParseError};
+
+//
+/// Checks credentials and returns the authenticated identity.
+pub trait Verifier {
+    type Identity;
+
+    fn verify(
+        &self,
+        credentials: &Credentials,
+        ctx: &VerifyContext<'_>,
+    ) -> Result<Self::Identity, Rejection>;
+
+    /// Called before the credentials are parsed, for missing or malformed credentials.
+    ///
+    /// Wrappers use it to observe rejections that never reach [`Verifier::verify`].
+    fn reject(&self, rejection: Rejection, ctx: &VerifyContext<'_>) -> Rejection {
+        let _ = ctx;
+        rejection
+    }
+}
+
+/// Verifies the result of `get_authorization` or `get_proxy_authorization`.
+pub fn verify_credentials<V: Verifier + ?Sized>(
+    verifier: &V,
+    credentials: Option<Result<Credentials, CredentialsParseError>>,
+    ctx: &VerifyContext<'_>,
+) -> Result<V::Identity, Rejection> {
+    match credentials {
+        Some(Ok(credentials)) => verifier.verify(&credentials, ctx),
+        Some(Err(err)) => Err(verifier.reject(Rejection::Malformed(err), ctx)),
+        None => Err(verifier.reject(Rejection::Missing, ctx)),
+    }
+}
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CredentialsSource {
+    Authorization,
+    ProxyAuthorization,
+    /// An API key, verified as Bearer credentials.
+    ApiKey(ApiKeyLocation),
+}
+
+/// Where an API key is read from, the name of the header, param or cookie is configured separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApiKeyLocation {
+    Header,
+    Query,
+    Cookie,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyContext<'a> {
+    pub source: CredentialsSource,
+    pub realm: Option<&'a str>,
+    pub client_addr: Option<IpAddr>,
+}
+
+impl<'a> VerifyContext<'a> {
+    pub fn new(source: CredentialsSource) -> Self {
+        Self {
+            source,
+            realm: None,
+            client_addr: None,
+        }
+    }
+
+    pub fn with_realm(mut self, realm: &'a str) -> Self {
+        self.realm = Some(realm);
+        self
+    }
+
+    pub fn with_client_addr(mut self, client_addr: IpAddr) -> Self {
+        self.client_addr = Some(client_addr);
+        self
+    }
+}
+
+//
+#[derive(Debug)]
+pub enum Rejection {
+    Missing,
+    Malformed(CredentialsParseError),
+    SchemeUnsupported,
+    Invalid,
+    TooManyAttempts {
+        retry_after: Duration,
+    },
+    /// The credentials are valid but lack `scope`, space-separated.
+    InsufficientScope {
+        scope: Box<str>,
+    },
+    Other(&'static str),
+}
+
+impl Rejection {
+    /// A stable label, e.g. the `reason` field of audit events.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Missing => "Missing",
+            Self::Malformed(_) => "Malformed",
+            Self::SchemeUnsupported => "SchemeUnsupported",
+            Self::Invalid => "Invalid",
+            Self::TooManyAttempts { .. } => "TooManyAttempts",
+            Self::InsufficientScope { .. } => "InsufficientScope",
+            Self::Other(_) => "Other",
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::TooManyAttempts { retry_after } => Some(*retry_after),
+            _ => None,
+        }
+    }
+
+    /// The `Retry-After` value in seconds, rounded up.
+    pub fn retry_after_secs(&self) -> Option<u64> {
+        self.retry_after()
+            .map(|x| x.as_secs() + u64::from(x.subsec_nanos() > 0))
+    }
+}
+
+impl core::fmt::Display for Rejection {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Rejection {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DenyAll;
+
+    impl Verifier for DenyAll {
+        type Identity = ();
+
+        fn verify(
+            &self,
+            _credentials: &Credentials,
+            _ctx: &VerifyContext<'_>,
+        ) -> Result<Self::Identity, Rejection> {
+            Err(Rejection::Invalid)
+        }
+    }
+
+    #[test]
+    fn test_verify_credentials() {
+        let ctx = VerifyContext::new(CredentialsSource::Authorization);
+
+        match verify_credentials(&DenyAll, None, &ctx) {
+            Err(Rejection::Missing) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match verify_credentials(&DenyAll, Some(Credentials::from_bytes("Foo bar")), &ctx) {
+            Err(Rejection::Malformed(CredentialsParseError::SchemeUnknown(_))) => {}
+            x => panic!("{x:?}"),
+        }
+
+        #[cfg(feature = "scheme-bearer")]
+        match verify_credentials(&DenyAll, Some(Ok(Credentials::bearer("foo"))), &ctx) {
+            Err(Rejection::Invalid) => {}
+            x => panic!("{x:?}"),
+        }
+
+        let rejection = Rejection::TooManyAttempts {
+            retry_after: Duration::from_millis(1500),
+        };
+        assert_eq!(rejection.retry_after_secs(), Some(2));
+        assert_eq!(Rejection::Invalid.retry_after_secs(), None);
+    }
+}
@@ -0,0 +1,260 @@
+use http::{header::CONTENT_TYPE, HeaderMap};
+
+use crate::{error::Error, Body};
+
+//
+#[cfg(feature = "json")]
+pub const MIME_APPLICATION_JSON: &str = "application/json";
+#[cfg(feature = "form")]
+pub const MIME_APPLICATION_FORM: &str = "application/x-www-form-urlencoded";
+#[cfg(feature = "text")]
+pub const MIME_TEXT: &str = "text/*";
+
+//
+/// The lowercase essence and the `charset` parameter of `Content-Type`.
+pub fn media_type(header_map: &HeaderMap) -> Option<(String, Option<String>)> {
+    let value = header_map.get(CONTENT_TYPE)?.to_str().ok()?;
+    let mut parts = value.split(';');
+    let essence = parts.next()?.trim().to_ascii_lowercase();
+    let charset = parts.find_map(|x| {
+        let (name, value) = x.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"').to_ascii_lowercase())
+    });
+    Some((essence, charset))
+}
+
+fn check_media_type(
+    header_map: &HeaderMap,
+    expected: &'static str,
+    matches: impl FnOnce(&str) -> bool,
+) -> Result<Option<String>, Error> {
+    match media_type(header_map) {
+        Some((essence, charset)) if matches(&essence) => Ok(charset),
+        x => Err(Error::MediaTypeUnsupported {
+            expected,
+            actual: x.map(|(essence, _)| essence.into()),
+        }),
+    }
+}
+
+impl Body {
+    /// Requires `application/json` or `application/*+json`, at most `limit` bytes.
+    #[cfg(feature = "json")]
+    pub async fn json<T>(self, header_map: &HeaderMap, limit: usize) -> Result<T, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        check_media_type(header_map, MIME_APPLICATION_JSON, |x| {
+            x == MIME_APPLICATION_JSON || (x.starts_with("application/") && x.ends_with("+json"))
+        })?;
+        let bytes = self.to_bytes_limited_async(limit).await?;
+
+        let mut de = serde_json::Deserializer::from_slice(&bytes);
+        let value = serde_path_to_error::deserialize(&mut de).map_err(|err| Error::Json {
+            path: err.path().to_string(),
+            source: err.into_inner(),
+        })?;
+        de.end().map_err(|err| Error::Json {
+            path: ".".into(),
+            source: err,
+        })?;
+        Ok(value)
+    }
+
+    /// Requires `application/x-www-form-urlencoded`, at most `limit` bytes.
+    #[cfg(feature = "form")]
+    pub async fn form<T>(self, header_map: &HeaderMap, limit: usize) -> Result<T, Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        check_media_type(header_map, MIME_APPLICATION_FORM, |x| {
+            x == MIME_APPLICATION_FORM
+        })?;
+        let bytes = self.to_bytes_limited_async(limit).await?;
+
+        serde_urlencoded::from_bytes(&bytes).map_err(Error::Form)
+    }
+
+    /// Requires `text/*`, at most `limit` bytes, decoded with the `charset` parameter, UTF-8 when absent.
+    #[cfg(feature = "text")]
+    pub async fn text(self, header_map: &HeaderMap, limit: usize) -> Result<String, Error> {
+        let charset = check_media_type(header_map, MIME_TEXT, |x| x.starts_with("text/"))?;
+        let encoding = match charset {
+            Some(charset) => encoding_rs::Encoding::for_label(charset.as_bytes())
+                .ok_or_else(|| Error::CharsetUnsupported(charset.into()))?,
+            None => encoding_rs::UTF_8,
+        };
+        let bytes = self.to_bytes_limited_async(limit).await?;
+
+        encoding
+            .decode_without_bom_handling_and_without_replacement(&bytes)
+            .map(Into::into)
+            .ok_or(Error::TextInvalid {
+                charset: encoding.name(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn headers(content_type: &'static str) -> HeaderMap {
+        let mut header_map = HeaderMap::new();
+        header_map.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        header_map
+    }
+
+    #[test]
+    fn test_media_type() {
+        assert_eq!(
+            media_type(&headers("Text/Plain; Charset=\"ISO-8859-1\"")),
+            Some(("text/plain".into(), Some("iso-8859-1".into())))
+        );
+        assert_eq!(
+            media_type(&headers("application/json")),
+            Some(("application/json".into(), None))
+        );
+        assert_eq!(media_type(&HeaderMap::new()), None);
+    }
+
+    #[cfg(feature = "json")]
+    #[tokio::test]
+    async fn test_json() {
+        #[derive(serde::Deserialize, Debug)]
+        struct Item {
+            #[allow(dead_code)]
+            id: u32,
+        }
+        #[derive(serde::Deserialize, Debug)]
+        struct Order {
+            items: Vec<Item>,
+        }
+
+        let header_map = headers("application/json; charset=utf-8");
+
+        let order = Body::with_bytes_from(r#"{"items":[{"id":1}]}"#)
+            .json::<Order>(&header_map, 1024)
+            .await
+            .unwrap();
+        assert_eq!(order.items.len(), 1);
+
+        match Body::with_bytes_from(r#"{"items":[{"id":1},{"id":"2"}]}"#)
+            .json::<Order>(&header_map, 1024)
+            .await
+        {
+            Err(Error::Json { path, source: _ }) => assert_eq!(path, "items[1].id"),
+            x => panic!("{x:?}"),
+        }
+
+        match Body::with_bytes_from(r#"{"items":[]} x"#)
+            .json::<Order>(&header_map, 1024)
+            .await
+        {
+            Err(Error::Json { path, source: _ }) => assert_eq!(path, "."),
+            x => panic!("{x:?}"),
+        }
+
+        match Body::with_bytes_from(r#"{"items":[]}"#)
+            .json::<Order>(&header_map, 4)
+            .await
+        {
+            Err(Error::LengthLimitExceeded { limit: 4, .. }) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match Body::with_bytes_from(r#"{"items":[]}"#)
+            .json::<Order>(&headers("text/plain"), 1024)
+            .await
+        {
+            Err(Error::MediaTypeUnsupported {
+                expected: MIME_APPLICATION_JSON,
+                actual: Some(x),
+            }) => assert_eq!(&*x, "text/plain"),
+            x => panic!("{x:?}"),
+        }
+
+        Body::with_bytes_from(r#"{"items":[]}"#)
+            .json::<Order>(&headers("application/merge-patch+json"), 1024)
+            .await
+            .unwrap();
+    }
+
+    #[cfg(feature = "form")]
+    #[tokio::test]
+    async fn test_form() {
+        #[derive(serde::Deserialize, Debug)]
+        struct Login {
+            user: String,
+            remember: bool,
+        }
+
+        let header_map = headers("application/x-www-form-urlencoded");
+
+        let login = Body::with_bytes_from("user=foo%20bar&remember=true")
+            .form::<Login>(&header_map, 1024)
+            .await
+            .unwrap();
+        assert_eq!(login.user, "foo bar");
+        assert!(login.remember);
+
+        match Body::with_bytes_from("user=foo&remember=x")
+            .form::<Login>(&header_map, 1024)
+            .await
+        {
+            Err(Error::Form(_)) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match Body::with_bytes_from("user=foo&remember=true")
+            .form::<Login>(&HeaderMap::new(), 1024)
+            .await
+        {
+            Err(Error::MediaTypeUnsupported {
+                expected: MIME_APPLICATION_FORM,
+                actual: None,
+            }) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[cfg(feature = "text")]
+    #[tokio::test]
+    async fn test_text() {
+        assert_eq!(
+            Body::with_bytes_from("héllo")
+                .text(&headers("text/plain"), 1024)
+                .await
+                .unwrap(),
+            "héllo"
+        );
+
+        assert_eq!(
+            Body::with_bytes_from(&b"h\xe9llo"[..])
+                .text(&headers("text/plain; charset=ISO-8859-1"), 1024)
+                .await
+                .unwrap(),
+            "héllo"
+        );
+
+        match Body::with_bytes_from(&b"h\xe9llo"[..])
+            .text(&headers("text/plain"), 1024)
+            .await
+        {
+            Err(Error::TextInvalid { charset: "UTF-8" }) => {}
+            x => panic!("{x:?}"),
+        }
+
+        match Body::with_bytes_from("hello")
+            .text(&headers("text/plain; charset=foo"), 1024)
+            .await
+        {
+            Err(Error::CharsetUnsupported(x)) => assert_eq!(&*x, "foo"),
+            x => panic!("{x:?}"),
+        }
+    }
+}
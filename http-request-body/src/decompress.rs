@@ -0,0 +1,368 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::io;
+
+use bytes::Bytes;
+use futures_util::{
+    io::{AsyncBufRead, AsyncRead, BufReader},
+    Stream, TryStreamExt as _,
+};
+use http::{header::CONTENT_ENCODING, HeaderMap};
+
+use crate::{error::Error, Body};
+
+//
+const READ_BUF_SIZE: usize = 8 * 1024;
+
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContentCoding {
+    #[cfg(feature = "gzip")]
+    Gzip,
+    #[cfg(feature = "deflate")]
+    Deflate,
+    #[cfg(feature = "brotli")]
+    Brotli,
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl core::str::FromStr for ContentCoding {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            #[cfg(feature = "gzip")]
+            "gzip" | "x-gzip" => Ok(Self::Gzip),
+            #[cfg(feature = "deflate")]
+            "deflate" => Ok(Self::Deflate),
+            #[cfg(feature = "brotli")]
+            "br" => Ok(Self::Brotli),
+            #[cfg(feature = "zstd")]
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(Error::ContentEncodingUnsupported(s.into())),
+        }
+    }
+}
+
+/// The codings of `Content-Encoding` in the order they were applied, `identity` is skipped.
+pub fn content_codings(header_map: &HeaderMap) -> Result<Vec<ContentCoding>, Error> {
+    let mut list = vec![];
+    for value in header_map.get_all(CONTENT_ENCODING) {
+        let value = value.to_str().map_err(|_| {
+            Error::ContentEncodingUnsupported(String::from_utf8_lossy(value.as_bytes()).into())
+        })?;
+        for x in value.split(',').map(str::trim) {
+            if x.is_empty() || x.eq_ignore_ascii_case("identity") {
+                continue;
+            }
+            list.push(x.parse()?);
+        }
+    }
+    Ok(list)
+}
+
+//
+/// Both limits are in bytes, the decompressed one defends against decompression bombs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecompressLimits {
+    pub compressed: usize,
+    pub decompressed: usize,
+}
+
+impl DecompressLimits {
+    pub fn new(compressed: usize, decompressed: usize) -> Self {
+        Self {
+            compressed,
+            decompressed,
+        }
+    }
+}
+
+impl Body {
+    /// Decodes the codings of `Content-Encoding`, the body is returned as is without any.
+    ///
+    /// The compressed body fails with [`Error::LengthLimitExceeded`],
+    /// the decompressed one with [`Error::DecompressedLengthLimitExceeded`].
+    /// `Content-Encoding` and `Content-Length` of the request no longer apply to the returned body.
+    pub fn decompress(
+        self,
+        header_map: &HeaderMap,
+        limits: DecompressLimits,
+    ) -> Result<Self, Error> {
+        let codings = content_codings(header_map)?;
+        if codings.is_empty() {
+            return Ok(self);
+        }
+        Ok(Self::with_stream(Decompressed::new(
+            self.limit(limits.compressed),
+            &codings,
+            limits.decompressed,
+        )))
+    }
+}
+
+//
+type BoxBufRead = Pin<Box<dyn AsyncBufRead + Send>>;
+type BoxRead = Pin<Box<dyn AsyncRead + Send>>;
+
+/// Streams the decompressed chunks of `inner`.
+pub struct Decompressed {
+    reader: BoxRead,
+    /// Allocated once, each chunk is copied out of it.
+    buf: Box<[u8]>,
+    limit: usize,
+    received: usize,
+    done: bool,
+}
+
+impl core::fmt::Debug for Decompressed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Decompressed")
+            .field("limit", &self.limit)
+            .field("received", &self.received)
+            .finish()
+    }
+}
+
+impl Decompressed {
+    /// `codings` in the order they were applied.
+    pub fn new(
+        inner: impl Stream<Item = Result<Bytes, Error>> + Send + 'static,
+        codings: &[ContentCoding],
+        limit: usize,
+    ) -> Self {
+        let mut reader: BoxBufRead = Box::pin(inner.map_err(io::Error::other).into_async_read());
+        let mut codings = codings.iter().rev().peekable();
+        let decoded: BoxRead = loop {
+            let decoder = match codings.next() {
+                Some(coding) => decoder(*coding, reader),
+                None => break reader,
+            };
+            if codings.peek().is_none() {
+                break decoder;
+            }
+            reader = Box::pin(BufReader::new(decoder));
+        };
+
+        Self {
+            reader: decoded,
+            buf: vec![0; READ_BUF_SIZE].into_boxed_slice(),
+            limit,
+            received: 0,
+            done: false,
+        }
+    }
+}
+
+fn decoder(coding: ContentCoding, reader: BoxBufRead) -> BoxRead {
+    use async_compression::futures::bufread;
+
+    match coding {
+        #[cfg(feature = "gzip")]
+        ContentCoding::Gzip => {
+            let mut decoder = bufread::GzipDecoder::new(reader);
+            decoder.multiple_members(true);
+            Box::pin(decoder)
+        }
+        // `deflate` of HTTP is the zlib format.
+        #[cfg(feature = "deflate")]
+        ContentCoding::Deflate => Box::pin(bufread::ZlibDecoder::new(reader)),
+        #[cfg(feature = "brotli")]
+        ContentCoding::Brotli => Box::pin(bufread::BrotliDecoder::new(reader)),
+        #[cfg(feature = "zstd")]
+        ContentCoding::Zstd => Box::pin(bufread::ZstdDecoder::new(reader)),
+    }
+}
+
+impl Stream for Decompressed {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        match this.reader.as_mut().poll_read(cx, &mut this.buf) {
+            Poll::Ready(Ok(0)) => {
+                this.done = true;
+                Poll::Ready(None)
+            }
+            Poll::Ready(Ok(n)) => {
+                this.received = this.received.saturating_add(n);
+                if this.received > this.limit {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(Error::DecompressedLengthLimitExceeded {
+                        limit: this.limit,
+                        received: this.received,
+                    })));
+                }
+                Poll::Ready(Some(Ok(Bytes::copy_from_slice(&this.buf[..n]))))
+            }
+            Poll::Ready(Err(err)) => {
+                this.done = true;
+                Poll::Ready(Some(Err(from_io_error(err))))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// Errors of the compressed stream are passed through the reader as `io::Error`.
+fn from_io_error(err: io::Error) -> Error {
+    if !err.get_ref().is_some_and(|x| x.is::<Error>()) {
+        return Error::Decompress(err);
+    }
+    match err.into_inner().map(|x| x.downcast::<Error>()) {
+        Some(Ok(err)) => *err,
+        Some(Err(err)) => Error::Other(err),
+        None => Error::Decompress(io::ErrorKind::Other.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_compression::futures::bufread;
+    use futures_util::io::AsyncReadExt as _;
+    use http::HeaderValue;
+
+    use super::*;
+
+    async fn encode(coding: ContentCoding, bytes: &[u8]) -> Vec<u8> {
+        let mut buf = vec![];
+        match coding {
+            #[cfg(feature = "gzip")]
+            ContentCoding::Gzip => bufread::GzipEncoder::new(bytes)
+                .read_to_end(&mut buf)
+                .await
+                .unwrap(),
+            #[cfg(feature = "deflate")]
+            ContentCoding::Deflate => bufread::ZlibEncoder::new(bytes)
+                .read_to_end(&mut buf)
+                .await
+                .unwrap(),
+            #[cfg(feature = "brotli")]
+            ContentCoding::Brotli => bufread::BrotliEncoder::new(bytes)
+                .read_to_end(&mut buf)
+                .await
+                .unwrap(),
+            #[cfg(feature = "zstd")]
+            ContentCoding::Zstd => bufread::ZstdEncoder::new(bytes)
+                .read_to_end(&mut buf)
+                .await
+                .unwrap(),
+        };
+        buf
+    }
+
+    fn chunked(bytes: Vec<u8>) -> Body {
+        let chunks = bytes
+            .chunks(7)
+            .map(|x| Ok(Bytes::copy_from_slice(x)))
+            .collect::<Vec<_>>();
+        Body::with_stream(futures_util::stream::iter(chunks))
+    }
+
+    #[tokio::test]
+    async fn test_decompress() {
+        let limits = DecompressLimits::new(1024, 1024);
+        let codings = [
+            #[cfg(feature = "gzip")]
+            ("gzip", ContentCoding::Gzip),
+            #[cfg(feature = "deflate")]
+            ("deflate", ContentCoding::Deflate),
+            #[cfg(feature = "brotli")]
+            ("br", ContentCoding::Brotli),
+            #[cfg(feature = "zstd")]
+            ("zstd", ContentCoding::Zstd),
+        ];
+
+        for (name, coding) in codings {
+            let mut header_map = HeaderMap::new();
+            header_map.insert(CONTENT_ENCODING, HeaderValue::from_static(name));
+
+            let body = chunked(encode(coding, br#"{"foo":"bar"}"#).await)
+                .decompress(&header_map, limits)
+                .unwrap();
+            assert_eq!(body.to_bytes_async().await.unwrap(), r#"{"foo":"bar"}"#);
+
+            //
+            let body = chunked(vec![0xff; 32])
+                .decompress(&header_map, limits)
+                .unwrap();
+            match body.to_bytes_async().await {
+                Err(Error::Decompress(_)) => {}
+                x => panic!("{name} {x:?}"),
+            }
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn test_decompress_limits() {
+        let mut header_map = HeaderMap::new();
+        header_map.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+
+        let bomb = encode(ContentCoding::Gzip, &[0; 64 * 1024]).await;
+        assert!(bomb.len() < 1024);
+
+        let body = chunked(bomb.clone())
+            .decompress(&header_map, DecompressLimits::new(1024, 16 * 1024))
+            .unwrap();
+        match body.to_bytes_async().await {
+            Err(Error::DecompressedLengthLimitExceeded {
+                limit: 16384,
+                received: _,
+            }) => {}
+            x => panic!("{x:?}"),
+        }
+
+        let body = chunked(bomb)
+            .decompress(&header_map, DecompressLimits::new(16, 1024 * 1024))
+            .unwrap();
+        match body.to_bytes_async().await {
+            Err(Error::LengthLimitExceeded { limit: 16, .. }) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
+    #[cfg(all(feature = "gzip", feature = "deflate"))]
+    #[tokio::test]
+    async fn test_decompress_stacked() {
+        let mut header_map = HeaderMap::new();
+        header_map.insert(
+            CONTENT_ENCODING,
+            HeaderValue::from_static("deflate, identity"),
+        );
+        header_map.append(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+        assert_eq!(
+            content_codings(&header_map).unwrap(),
+            [ContentCoding::Deflate, ContentCoding::Gzip]
+        );
+
+        let bytes = encode(ContentCoding::Deflate, b"foo").await;
+        let bytes = encode(ContentCoding::Gzip, &bytes).await;
+        let body = chunked(bytes)
+            .decompress(&header_map, DecompressLimits::new(1024, 1024))
+            .unwrap();
+        assert_eq!(body.to_bytes_async().await.unwrap(), "foo");
+
+        header_map.insert(CONTENT_ENCODING, HeaderValue::from_static("compress"));
+        match Body::default().decompress(&header_map, DecompressLimits::new(1, 1)) {
+            Err(Error::ContentEncodingUnsupported(x)) => assert_eq!(&*x, "compress"),
+            x => panic!("{x:?}"),
+        }
+
+        header_map.insert(
+            CONTENT_ENCODING,
+            HeaderValue::from_bytes(b"gzip, caf\xe9").unwrap(),
+        );
+        match Body::default().decompress(&header_map, DecompressLimits::new(1, 1)) {
+            Err(Error::ContentEncodingUnsupported(x)) => assert_eq!(&*x, "gzip, caf\u{fffd}"),
+            x => panic!("{x:?}"),
+        }
+    }
+}
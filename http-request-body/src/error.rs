@@ -1,6 +1,74 @@
 //
+/// More variants may be added, match with a wildcard arm.
+///
+/// The decompression and decoding variants only exist with their feature,
+/// the wildcard arm keeps matches compiling when another crate enables one.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
+    /// More than `limit` bytes were sent, `received` counts up to the chunk crossing the limit.
+    LengthLimitExceeded {
+        limit: usize,
+        received: usize,
+    },
+    /// `Content-Length` is not a decimal integer, or its values disagree.
+    ContentLengthInvalid,
+    /// The stream ended before the declared `Content-Length`.
+    Truncated {
+        expected: u64,
+        received: u64,
+    },
+    /// The stream went past the declared `Content-Length`.
+    TooLong {
+        expected: u64,
+    },
+    /// A coding of `Content-Encoding` without its feature enabled.
+    #[cfg(any(
+        feature = "gzip",
+        feature = "deflate",
+        feature = "brotli",
+        feature = "zstd"
+    ))]
+    ContentEncodingUnsupported(Box<str>),
+    #[cfg(any(
+        feature = "gzip",
+        feature = "deflate",
+        feature = "brotli",
+        feature = "zstd"
+    ))]
+    DecompressedLengthLimitExceeded {
+        limit: usize,
+        received: usize,
+    },
+    /// The compressed data is corrupt.
+    #[cfg(any(
+        feature = "gzip",
+        feature = "deflate",
+        feature = "brotli",
+        feature = "zstd"
+    ))]
+    Decompress(std::io::Error),
+    /// `Content-Type` is missing or not `expected`.
+    #[cfg(any(feature = "json", feature = "form", feature = "text"))]
+    MediaTypeUnsupported {
+        expected: &'static str,
+        actual: Option<Box<str>>,
+    },
+    /// `path` locates the field in the document, e.g. `items[1].id`.
+    #[cfg(feature = "json")]
+    Json {
+        path: String,
+        source: serde_json::Error,
+    },
+    #[cfg(feature = "form")]
+    Form(serde_urlencoded::de::Error),
+    #[cfg(feature = "text")]
+    CharsetUnsupported(Box<str>),
+    /// The bytes are not valid in `charset`.
+    #[cfg(feature = "text")]
+    TextInvalid {
+        charset: &'static str,
+    },
     Other(Box<dyn std::error::Error + Send + Sync + 'static>),
 }
 impl core::fmt::Display for Error {
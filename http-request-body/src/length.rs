@@ -0,0 +1,189 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures_util::Stream;
+use http::{
+    header::{CONTENT_LENGTH, TRANSFER_ENCODING},
+    HeaderMap,
+};
+use pin_project_lite::pin_project;
+
+use crate::error::Error;
+
+//
+/// The declared length of the body.
+///
+/// `None` when there is no `Content-Length`, or when `Transfer-Encoding` is present,
+/// in which case `Content-Length` must be ignored, see [RFC 9112 Section 6.3](https://www.rfc-editor.org/rfc/rfc9112#section-6.3).
+pub fn content_length(header_map: &HeaderMap) -> Result<Option<u64>, Error> {
+    if header_map.contains_key(TRANSFER_ENCODING) {
+        return Ok(None);
+    }
+
+    let mut content_length = None;
+    for value in header_map.get_all(CONTENT_LENGTH) {
+        // A list of identical values is accepted, e.g. `Content-Length: 42, 42`.
+        for x in value
+            .to_str()
+            .map_err(|_| Error::ContentLengthInvalid)?
+            .split(',')
+        {
+            let x = x.trim();
+            if x.is_empty() || !x.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(Error::ContentLengthInvalid);
+            }
+            let x = x.parse::<u64>().map_err(|_| Error::ContentLengthInvalid)?;
+            match content_length {
+                Some(y) if y != x => return Err(Error::ContentLengthInvalid),
+                _ => content_length = Some(x),
+            }
+        }
+    }
+    Ok(content_length)
+}
+
+//
+pin_project! {
+    /// Checks the stream yields exactly `expected` bytes, with [`Error::TooLong`] and [`Error::Truncated`].
+    pub struct ContentLength<S> {
+        #[pin]
+        inner: S,
+        expected: u64,
+        received: u64,
+        done: bool,
+    }
+}
+
+impl<S> ContentLength<S> {
+    pub fn new(inner: S, expected: u64) -> Self {
+        Self {
+            inner,
+            expected,
+            received: 0,
+            done: false,
+        }
+    }
+
+    pub fn expected(&self) -> u64 {
+        self.expected
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.expected.saturating_sub(self.received)
+    }
+}
+
+impl<S> Stream for ContentLength<S>
+where
+    S: Stream<Item = Result<Bytes, Error>>,
+{
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        match this.inner.poll_next(cx) {
+            Poll::Ready(Some(Ok(bytes))) => {
+                *this.received = this.received.saturating_add(bytes.len() as u64);
+                if *this.received > *this.expected {
+                    *this.done = true;
+                    Poll::Ready(Some(Err(Error::TooLong {
+                        expected: *this.expected,
+                    })))
+                } else {
+                    Poll::Ready(Some(Ok(bytes)))
+                }
+            }
+            Poll::Ready(None) => {
+                *this.done = true;
+                if *this.received < *this.expected {
+                    Poll::Ready(Some(Err(Error::Truncated {
+                        expected: *this.expected,
+                        received: *this.received,
+                    })))
+                } else {
+                    Poll::Ready(None)
+                }
+            }
+            x => x,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt as _;
+    use http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_content_length() {
+        let mut header_map = HeaderMap::new();
+        assert_eq!(content_length(&header_map).unwrap(), None);
+
+        header_map.insert(CONTENT_LENGTH, HeaderValue::from_static("42"));
+        assert_eq!(content_length(&header_map).unwrap(), Some(42));
+
+        header_map.append(CONTENT_LENGTH, HeaderValue::from_static("42, 42"));
+        assert_eq!(content_length(&header_map).unwrap(), Some(42));
+
+        header_map.append(CONTENT_LENGTH, HeaderValue::from_static("43"));
+        match content_length(&header_map) {
+            Err(Error::ContentLengthInvalid) => {}
+            x => panic!("{x:?}"),
+        }
+
+        header_map.insert(CONTENT_LENGTH, HeaderValue::from_static("+42"));
+        match content_length(&header_map) {
+            Err(Error::ContentLengthInvalid) => {}
+            x => panic!("{x:?}"),
+        }
+
+        header_map.insert(TRANSFER_ENCODING, HeaderValue::from_static("chunked"));
+        assert_eq!(content_length(&header_map).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        let chunks = || {
+            futures_util::stream::iter(vec![
+                Ok(Bytes::from_static(b"foo")),
+                Ok(Bytes::from_static(b"bar")),
+            ])
+        };
+
+        let stream = ContentLength::new(chunks(), 6);
+        assert_eq!(
+            stream.map(|x| x.unwrap().to_vec()).concat().await,
+            b"foobar"
+        );
+
+        let mut stream = ContentLength::new(chunks(), 7);
+        assert_eq!(stream.next().await.unwrap().unwrap(), "foo");
+        assert_eq!(stream.remaining(), 4);
+        assert_eq!(stream.next().await.unwrap().unwrap(), "bar");
+        match stream.next().await {
+            Some(Err(Error::Truncated {
+                expected: 7,
+                received: 6,
+            })) => {}
+            x => panic!("{x:?}"),
+        }
+        assert!(stream.next().await.is_none());
+
+        let mut stream = ContentLength::new(chunks(), 4);
+        assert_eq!(stream.next().await.unwrap().unwrap(), "foo");
+        match stream.next().await {
+            Some(Err(Error::TooLong { expected: 4 })) => {}
+            x => panic!("{x:?}"),
+        }
+        assert!(stream.next().await.is_none());
+    }
+}
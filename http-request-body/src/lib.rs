@@ -10,19 +10,39 @@ use core::{
 
 use bytes::Bytes;
 use futures_util::Stream;
+use http::HeaderMap;
 use pin_project_lite::pin_project;
 
+#[cfg(any(feature = "json", feature = "form", feature = "text"))]
+pub mod decode;
+#[cfg(any(
+    feature = "gzip",
+    feature = "deflate",
+    feature = "brotli",
+    feature = "zstd"
+))]
+pub mod decompress;
 pub mod error;
+pub mod length;
+pub mod limit;
 mod utils;
 
 use error::Error;
+use length::ContentLength;
+use limit::Limited;
+
+type BoxStream = Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send + 'static>>;
 
 //
 pin_project! {
+    /// More variants may be added, match with a wildcard arm.
     #[project = BodyProj]
+    #[non_exhaustive]
     pub enum Body {
         Bytes { inner: Bytes },
-        Stream { #[pin] inner: Pin<Box<dyn Stream<Item = Result<Bytes, Error>> + Send + 'static>> },
+        Stream { #[pin] inner: BoxStream },
+        /// A stream with a declared `Content-Length`.
+        KnownLength { #[pin] inner: ContentLength<BoxStream> },
     }
 }
 
@@ -31,6 +51,10 @@ impl core::fmt::Debug for Body {
         match self {
             Self::Bytes { inner } => f.debug_tuple("Bytes").field(&inner).finish(),
             Self::Stream { inner: _ } => write!(f, "Stream"),
+            Self::KnownLength { inner } => f
+                .debug_tuple("KnownLength")
+                .field(&inner.expected())
+                .finish(),
         }
     }
 }
@@ -68,6 +92,50 @@ impl Body {
         }
     }
 
+    /// Uses `Content-Length` of the request, see [`length::content_length`].
+    pub fn with_stream_and_headers(
+        stream: impl Stream<Item = Result<Bytes, Error>> + Send + 'static,
+        header_map: &HeaderMap,
+    ) -> Result<Self, Error> {
+        Self::with_stream(stream).with_headers(header_map)
+    }
+
+    /// Uses `Content-Length` of the request, see [`length::content_length`].
+    pub fn with_bytes_and_headers(bytes: Bytes, header_map: &HeaderMap) -> Result<Self, Error> {
+        Self::with_bytes(bytes).with_headers(header_map)
+    }
+
+    /// Applies `Content-Length` of the request, e.g. after [`Body::from_hyper_body`].
+    pub fn with_headers(self, header_map: &HeaderMap) -> Result<Self, Error> {
+        match length::content_length(header_map)? {
+            Some(content_length) => self.with_content_length(content_length),
+            None => Ok(self),
+        }
+    }
+
+    /// A `Bytes` body is checked at once, a stream is checked while read.
+    pub fn with_content_length(self, content_length: u64) -> Result<Self, Error> {
+        match self {
+            Self::Bytes { inner } => match (inner.len() as u64).cmp(&content_length) {
+                core::cmp::Ordering::Less => Err(Error::Truncated {
+                    expected: content_length,
+                    received: inner.len() as u64,
+                }),
+                core::cmp::Ordering::Greater => Err(Error::TooLong {
+                    expected: content_length,
+                }),
+                core::cmp::Ordering::Equal => Ok(Self::Bytes { inner }),
+            },
+            Self::Stream { inner } => Ok(Self::KnownLength {
+                inner: ContentLength::new(inner, content_length),
+            }),
+            Self::KnownLength { inner } if inner.expected() == content_length => {
+                Ok(Self::KnownLength { inner })
+            }
+            Self::KnownLength { inner: _ } => Err(Error::ContentLengthInvalid),
+        }
+    }
+
     #[cfg(feature = "hyper-request-body")]
     pub fn from_hyper_body(body: hyper_request_body::HyperBody) -> Self {
         use futures_util::TryStreamExt as _;
@@ -131,22 +199,89 @@ impl From<warp_request_body::Body> for Body {
 
 impl Body {
     pub fn require_to_bytes_async(&self) -> bool {
-        matches!(self, Self::Stream { inner: _ })
+        matches!(
+            self,
+            Self::Stream { inner: _ } | Self::KnownLength { inner: _ }
+        )
+    }
+
+    /// The bounds of the remaining length, like [`Stream::size_hint`] but in bytes.
+    pub fn size_hint(&self) -> (u64, Option<u64>) {
+        match self {
+            Self::Bytes { inner } => (inner.len() as u64, Some(inner.len() as u64)),
+            Self::Stream { inner: _ } => (0, None),
+            Self::KnownLength { inner } => (inner.remaining(), Some(inner.remaining())),
+        }
     }
 
     pub fn to_bytes(self) -> Bytes {
         match self {
             Self::Bytes { inner } => inner,
-            Self::Stream { inner: _ } => panic!("Please call require_to_bytes_async first"),
+            Self::Stream { inner: _ } | Self::KnownLength { inner: _ } => {
+                panic!("Please call require_to_bytes_async first")
+            }
         }
     }
 
     pub async fn to_bytes_async(self) -> Result<Bytes, Error> {
         match self {
             Self::Bytes { inner } => Ok(inner),
-            Self::Stream { inner } => utils::bytes_stream_to_bytes(inner)
-                .await
-                .map_err(Into::into),
+            Self::Stream { inner } => utils::bytes_stream_to_bytes(inner, None).await,
+            Self::KnownLength { inner } => {
+                let capacity = inner.remaining();
+                utils::bytes_stream_to_bytes(inner, Some(capacity)).await
+            }
+        }
+    }
+
+    /// Same as [`Body::to_bytes_async`], fails with [`Error::LengthLimitExceeded`]
+    /// as soon as more than `limit` bytes were received.
+    ///
+    /// A declared `Content-Length` over `limit` fails before anything is read, `received` is 0.
+    pub async fn to_bytes_limited_async(self, limit: usize) -> Result<Bytes, Error> {
+        match self {
+            Self::Bytes { inner } if inner.len() > limit => Err(Error::LengthLimitExceeded {
+                limit,
+                received: inner.len(),
+            }),
+            Self::Bytes { inner } => Ok(inner),
+            Self::Stream { inner } => {
+                utils::bytes_stream_to_bytes(Limited::new(inner, limit), None).await
+            }
+            Self::KnownLength { inner } if inner.remaining() > limit as u64 => {
+                Err(Error::LengthLimitExceeded { limit, received: 0 })
+            }
+            // The declared length is within the limit and `ContentLength` fails past it.
+            Self::KnownLength { inner } => {
+                let capacity = inner.remaining();
+                utils::bytes_stream_to_bytes(inner, Some(capacity)).await
+            }
+        }
+    }
+
+    /// Streams at most `limit` bytes, see [`Limited`].
+    ///
+    /// A `Bytes` body over the limit becomes a stream yielding the error, so does a `KnownLength`
+    /// body whose declared length is over it, with `received` 0.
+    /// A `KnownLength` body within the limit is kept as is, it already fails past its length.
+    pub fn limit(self, limit: usize) -> Self {
+        match self {
+            Self::Bytes { inner } if inner.len() > limit => {
+                Self::with_stream(futures_util::stream::once(async move {
+                    Err(Error::LengthLimitExceeded {
+                        limit,
+                        received: inner.len(),
+                    })
+                }))
+            }
+            Self::Bytes { inner } => Self::Bytes { inner },
+            Self::Stream { inner } => Self::with_stream(Limited::new(inner, limit)),
+            Self::KnownLength { inner } if inner.remaining() > limit as u64 => {
+                Self::with_stream(futures_util::stream::once(async move {
+                    Err(Error::LengthLimitExceeded { limit, received: 0 })
+                }))
+            }
+            Self::KnownLength { inner } => Self::KnownLength { inner },
         }
     }
 }
@@ -166,7 +301,8 @@ impl Stream for Body {
                     Poll::Ready(None)
                 }
             }
-            BodyProj::Stream { inner } => inner.poll_next(cx).map_err(Into::into),
+            BodyProj::Stream { inner } => inner.poll_next(cx),
+            BodyProj::KnownLength { inner } => inner.poll_next(cx),
         }
     }
 }
@@ -201,6 +337,134 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_limit() {
+        //
+        let body = Body::with_bytes_from("foo");
+        assert_eq!(body.to_bytes_limited_async(3).await.unwrap(), "foo");
+
+        let body = Body::with_bytes_from("foo");
+        match body.to_bytes_limited_async(2).await {
+            Err(Error::LengthLimitExceeded {
+                limit: 2,
+                received: 3,
+            }) => {}
+            x => panic!("{x:?}"),
+        }
+
+        let mut body = Body::with_bytes_from("foo").limit(2);
+        match body.next().await {
+            Some(Err(Error::LengthLimitExceeded {
+                limit: 2,
+                received: 3,
+            })) => {}
+            x => panic!("{x:?}"),
+        }
+
+        //
+        let stream = futures_util::stream::iter(vec![
+            Ok(Bytes::from_static(b"foo")),
+            Ok(Bytes::from_static(b"bar")),
+        ]);
+        let body = Body::with_stream(stream);
+        match body.to_bytes_limited_async(4).await {
+            Err(Error::LengthLimitExceeded {
+                limit: 4,
+                received: 6,
+            }) => {}
+            x => panic!("{x:?}"),
+        }
+
+        let stream = futures_util::stream::iter(vec![
+            Ok(Bytes::from_static(b"foo")),
+            Ok(Bytes::from_static(b"bar")),
+        ]);
+        let body = Body::with_stream(stream).limit(6);
+        assert_eq!(body.to_bytes_async().await.unwrap(), "foobar");
+    }
+
+    #[tokio::test]
+    async fn test_with_headers() {
+        use http::{header::CONTENT_LENGTH, HeaderValue};
+
+        let mut header_map = HeaderMap::new();
+        header_map.insert(CONTENT_LENGTH, HeaderValue::from_static("6"));
+
+        //
+        let stream = futures_util::stream::iter(vec![
+            Ok(Bytes::from_static(b"foo")),
+            Ok(Bytes::from_static(b"bar")),
+        ]);
+        let body = Body::with_stream_and_headers(stream, &header_map).unwrap();
+        assert!(body.require_to_bytes_async());
+        assert_eq!(body.size_hint(), (6, Some(6)));
+        assert_eq!(body.to_bytes_async().await.unwrap(), "foobar");
+
+        let stream = futures_util::stream::once(async { Ok(Bytes::from_static(b"foo")) });
+        let body = Body::with_stream_and_headers(stream, &header_map).unwrap();
+        match body.to_bytes_async().await {
+            Err(Error::Truncated {
+                expected: 6,
+                received: 3,
+            }) => {}
+            x => panic!("{x:?}"),
+        }
+
+        //
+        let body =
+            Body::with_bytes_and_headers(Bytes::from_static(b"foobar"), &header_map).unwrap();
+        assert_eq!(body.size_hint(), (6, Some(6)));
+
+        match Body::with_bytes_and_headers(Bytes::from_static(b"foobarbaz"), &header_map) {
+            Err(Error::TooLong { expected: 6 }) => {}
+            x => panic!("{x:?}"),
+        }
+
+        assert_eq!(
+            Body::with_stream(futures_util::stream::empty()).size_hint(),
+            (0, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_limit_with_headers() {
+        use http::{header::CONTENT_LENGTH, HeaderValue};
+
+        let mut header_map = HeaderMap::new();
+        header_map.insert(CONTENT_LENGTH, HeaderValue::from_static("6"));
+
+        let stream = futures_util::stream::iter(vec![
+            Ok(Bytes::from_static(b"foo")),
+            Ok(Bytes::from_static(b"barbaz")),
+        ]);
+        let body = Body::with_stream_and_headers(stream, &header_map)
+            .unwrap()
+            .limit(10);
+        assert_eq!(body.size_hint(), (6, Some(6)));
+        match body.to_bytes_async().await {
+            Err(Error::TooLong { expected: 6 }) => {}
+            x => panic!("{x:?}"),
+        }
+
+        // Fails without reading the stream, it never yields.
+        let body =
+            || Body::with_stream_and_headers(futures_util::stream::pending(), &header_map).unwrap();
+        match body().to_bytes_limited_async(5).await {
+            Err(Error::LengthLimitExceeded {
+                limit: 5,
+                received: 0,
+            }) => {}
+            x => panic!("{x:?}"),
+        }
+        match body().limit(5).next().await {
+            Some(Err(Error::LengthLimitExceeded {
+                limit: 5,
+                received: 0,
+            })) => {}
+            x => panic!("{x:?}"),
+        }
+    }
+
     #[cfg(feature = "hyper-request-body")]
     #[tokio::test]
     async fn test_from_hyper_body() {
@@ -0,0 +1,109 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures_util::Stream;
+use pin_project_lite::pin_project;
+
+use crate::error::Error;
+
+//
+pin_project! {
+    /// Fails with [`Error::LengthLimitExceeded`] once more than `limit` bytes were read, then ends.
+    pub struct Limited<S> {
+        #[pin]
+        inner: S,
+        limit: usize,
+        received: usize,
+        exceeded: bool,
+    }
+}
+
+impl<S> Limited<S> {
+    pub fn new(inner: S, limit: usize) -> Self {
+        Self {
+            inner,
+            limit,
+            received: 0,
+            exceeded: false,
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> Stream for Limited<S>
+where
+    S: Stream<Item = Result<Bytes, Error>>,
+{
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.exceeded {
+            return Poll::Ready(None);
+        }
+
+        match this.inner.poll_next(cx) {
+            Poll::Ready(Some(Ok(bytes))) => {
+                *this.received = this.received.saturating_add(bytes.len());
+                if *this.received > *this.limit {
+                    *this.exceeded = true;
+                    Poll::Ready(Some(Err(Error::LengthLimitExceeded {
+                        limit: *this.limit,
+                        received: *this.received,
+                    })))
+                } else {
+                    Poll::Ready(Some(Ok(bytes)))
+                }
+            }
+            x => x,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.exceeded {
+            return (0, Some(0));
+        }
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limited() {
+        let stream = futures_util::stream::iter(vec![
+            Ok(Bytes::from_static(b"foo")),
+            Ok(Bytes::from_static(b"bar")),
+            Ok(Bytes::from_static(b"baz")),
+        ]);
+        let mut stream = Limited::new(stream, 5);
+
+        assert_eq!(stream.next().await.unwrap().unwrap(), "foo");
+        match stream.next().await {
+            Some(Err(Error::LengthLimitExceeded {
+                limit: 5,
+                received: 6,
+            })) => {}
+            x => panic!("{x:?}"),
+        }
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -3,10 +3,18 @@ use futures_util::{Stream, StreamExt as _};
 
 use crate::Error;
 
+// A declared length is not trusted for more than this before the bytes arrive.
+const PREALLOCATE_MAX: usize = 1024 * 1024;
+
+/// `capacity` is the expected length, e.g. from `Content-Length`.
 pub async fn bytes_stream_to_bytes(
     mut stream: impl Stream<Item = Result<Bytes, Error>> + Unpin,
+    capacity: Option<u64>,
 ) -> Result<Bytes, Error> {
-    let mut bytes_mut = BytesMut::new();
+    let capacity = capacity
+        .map(|x| x.min(PREALLOCATE_MAX as u64) as usize)
+        .unwrap_or_default();
+    let mut bytes_mut = BytesMut::with_capacity(capacity);
     while let Some(bytes) = stream.next().await {
         let bytes = bytes?;
         bytes_mut.extend_from_slice(&bytes[..]);
@@ -23,7 +31,7 @@ mod tests {
         let stream =
             futures_util::stream::once(async { Ok(Bytes::copy_from_slice(b"foo")) }).boxed();
         assert_eq!(
-            bytes_stream_to_bytes(stream).await.unwrap(),
+            bytes_stream_to_bytes(stream, None).await.unwrap(),
             Bytes::copy_from_slice(b"foo")
         );
     }
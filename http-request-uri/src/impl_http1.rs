@@ -0,0 +1,41 @@
+use http1::Uri;
+
+use crate::{
+    uri_parts::{AuthorityParts, UriParts},
+    RequestUri,
+};
+
+impl<'a> TryFrom<&'a Uri> for RequestUri<'a> {
+    type Error = &'static str;
+
+    fn try_from(uri: &'a Uri) -> Result<Self, Self::Error> {
+        UriParts {
+            scheme: uri.scheme_str(),
+            authority: uri.authority().map(|x| AuthorityParts {
+                as_str: x.as_str(),
+                host: x.host(),
+                port: x.port_u16(),
+            }),
+            path: uri.path(),
+            query: uri.query(),
+        }
+        .try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::TEST_DATA;
+
+    #[test]
+    fn test_from_uri() {
+        for (uri_str, request_uri) in TEST_DATA {
+            assert_eq!(
+                &RequestUri::try_from(&uri_str.parse::<Uri>().unwrap()).unwrap(),
+                request_uri
+            );
+        }
+    }
+}
@@ -25,8 +25,13 @@ pub enum RequestUri<'a> {
     Asterisk,
 }
 
+#[cfg(any(feature = "http", feature = "http1"))]
+mod uri_parts;
+
 #[cfg(feature = "http")]
 mod impl_http;
+#[cfg(feature = "http1")]
+mod impl_http1;
 
 #[cfg(test)]
 #[allow(dead_code)]
@@ -0,0 +1,87 @@
+use crate::RequestUri;
+
+/// The parts of a `Uri` that a [`RequestUri`] is built from, shared by the http 0.2 and http 1.x impls.
+pub(crate) struct UriParts<'a> {
+    pub(crate) scheme: Option<&'a str>,
+    pub(crate) authority: Option<AuthorityParts<'a>>,
+    pub(crate) path: &'a str,
+    pub(crate) query: Option<&'a str>,
+}
+
+pub(crate) struct AuthorityParts<'a> {
+    pub(crate) as_str: &'a str,
+    pub(crate) host: &'a str,
+    pub(crate) port: Option<u16>,
+}
+
+impl<'a> TryFrom<UriParts<'a>> for RequestUri<'a> {
+    type Error = &'static str;
+
+    fn try_from(parts: UriParts<'a>) -> Result<Self, Self::Error> {
+        if let Some(scheme) = parts.scheme {
+            if let Some(authority) = parts.authority {
+                let (mut username, mut password) = (None, None);
+
+                let mut split = authority.as_str.split('@').rev();
+                if split.next().is_none() {
+                    debug_assert!(false, "unreachable");
+                    return Err("authority invalid");
+                }
+
+                if let Some(username_password) = split.next() {
+                    if split.next().is_some() {
+                        debug_assert!(false, "unreachable");
+                        return Err("authority invalid");
+                    }
+
+                    let mut split = username_password.split(':');
+                    let username_tmp = split.next().ok_or("username missing")?;
+                    if !username_tmp.is_empty() {
+                        username = Some(username_tmp);
+                    }
+                    if let Some(password_tmp) = split.next() {
+                        if split.next().is_some() {
+                            debug_assert!(false, "unreachable");
+                            return Err("authority invalid");
+                        }
+
+                        if !password_tmp.is_empty() {
+                            password = Some(password_tmp);
+                        }
+                    }
+                }
+
+                Ok(Self::AbsoluteUri {
+                    scheme,
+                    username,
+                    password,
+                    host: authority.host,
+                    port: authority.port,
+                    path: parts.path,
+                    query: parts.query,
+                    fragment: None,
+                })
+            } else {
+                Err("authority missing")
+            }
+        } else if let Some(authority) = parts.authority {
+            Ok(Self::Authority {
+                host: authority.host,
+                port: authority.port,
+            })
+        } else if parts.path == "*" {
+            Ok(Self::Asterisk)
+        } else {
+            if !parts.path.starts_with('/') {
+                debug_assert!(false, "unreachable");
+                return Err("path invalid");
+            }
+
+            Ok(Self::Origin {
+                path: parts.path,
+                query: parts.query,
+                fragment: None,
+            })
+        }
+    }
+}